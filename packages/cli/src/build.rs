@@ -1,15 +1,28 @@
 use crate::Cli;
 use anstream::{eprintln, println};
 use crossterm::style::Stylize as _;
-use futures::{FutureExt as _, TryStreamExt as _};
-use std::path::PathBuf;
+use futures::{FutureExt as _, StreamExt as _, TryStreamExt as _};
+use std::{path::PathBuf, pin::pin};
 use tangram_client::{self as tg, prelude::*};
+use tangram_either::Either;
 use tangram_futures::task::Task;
 
 /// Spawn and await a sandboxed process.
 #[derive(Clone, Debug, clap::Args)]
 #[group(skip)]
 pub struct Args {
+	/// Check in the watched path deterministically.
+	#[arg(long)]
+	pub deterministic: bool,
+
+	/// If false, don't parse ignore files when checking in the watched path.
+	#[arg(default_value = "true", long, action = clap::ArgAction::Set)]
+	pub ignore: bool,
+
+	/// If false, don't write lockfiles when checking in the watched path.
+	#[arg(default_value = "true", long, action = clap::ArgAction::Set)]
+	pub lockfile: bool,
+
 	#[command(flatten)]
 	pub options: Options,
 
@@ -20,6 +33,10 @@ pub struct Args {
 	/// Set arguments.
 	#[arg(index = 2, trailing_var_arg = true)]
 	pub trailing: Vec<String>,
+
+	/// Watch the referenced path and build again each time it changes.
+	#[arg(long)]
+	pub watch: bool,
 }
 
 #[derive(Clone, Debug, Default, clap::Args)]
@@ -53,6 +70,12 @@ pub enum View {
 
 impl Cli {
 	pub async fn command_build(&mut self, args: Args) -> tg::Result<()> {
+		// If the watch flag is set, then build each time the referenced path changes.
+		if args.watch {
+			self.build_watch(args).await?;
+			return Ok(());
+		}
+
 		// Get the reference.
 		let reference = args.reference.unwrap_or_else(|| ".".parse().unwrap());
 
@@ -63,6 +86,86 @@ impl Cli {
 		Ok(())
 	}
 
+	async fn build_watch(&mut self, args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+		let Args {
+			deterministic,
+			ignore,
+			lockfile,
+			options,
+			reference,
+			trailing,
+			..
+		} = args;
+		let reference = reference.unwrap_or_else(|| ".".parse().unwrap());
+
+		// Get the path.
+		let tg::reference::Item::Path(path) = reference.item() else {
+			return Err(tg::error!(%reference, "expected a path reference to watch"));
+		};
+		let path = std::path::absolute(path)
+			.map_err(|source| tg::error!(!source, "failed to get the path"))?;
+
+		// Watch the path with the user's checkin options.
+		let arg = tg::checkin::Arg {
+			destructive: false,
+			deterministic,
+			ignore,
+			locked: options.spawn.locked,
+			lockfile,
+			path,
+			updates: Vec::new(),
+			watch: true,
+		};
+		let stream = handle
+			.checkin(arg)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to watch the path"))?;
+
+		// Build each checked in artifact, reporting failures without ending the watch.
+		let mut stream = pin!(stream.peekable());
+		while let Some(mut output) = self.render_progress_stream_next(stream.as_mut()).await? {
+			// Drain the stream to the latest output, so that changes made during the previous build are built once.
+			while let Some(Some(_)) = stream.as_mut().peek().now_or_never() {
+				let Some(next) = self.render_progress_stream_next(stream.as_mut()).await? else {
+					break;
+				};
+				output = next;
+			}
+
+			let referent = output
+				.referent
+				.map(|item| Either::Right(tg::Object::with_id(item.into())));
+			let spawn = crate::process::spawn::Options {
+				sandbox: true,
+				..options.spawn.clone()
+			};
+			let result = async {
+				let (referent, process) = self
+					.spawn_with_referent(
+						spawn,
+						&reference,
+						referent,
+						trailing.clone(),
+						None,
+						None,
+						None,
+					)
+					.boxed()
+					.await?;
+				self.build_process(options.clone(), referent, process, true)
+					.boxed()
+					.await
+			}
+			.await;
+			if let Err(error) = result {
+				self.print_error(&error, None).await;
+			}
+		}
+
+		Ok(())
+	}
+
 	pub async fn build(
 		&mut self,
 		options: Options,
@@ -70,18 +173,29 @@ impl Cli {
 		trailing: Vec<String>,
 		print: bool,
 	) -> tg::Result<Option<tg::Value>> {
-		let handle = self.handle().await?;
-
 		// Spawn the process.
 		let spawn = crate::process::spawn::Options {
 			sandbox: true,
-			..options.spawn
+			..options.spawn.clone()
 		};
 		let (referent, process) = self
 			.spawn(spawn, reference, trailing, None, None, None)
 			.boxed()
 			.await?;
 
+		// Build.
+		self.build_process(options, referent, process, print).await
+	}
+
+	async fn build_process(
+		&mut self,
+		options: Options,
+		referent: tg::Referent<tg::object::Id>,
+		process: tg::Process,
+		print: bool,
+	) -> tg::Result<Option<tg::Value>> {
+		let handle = self.handle().await?;
+
 		// If the detach flag is set, then print the process ID and return.
 		if options.detach {
 			if print {
@@ -1,5 +1,6 @@
 use crate::Cli;
-use std::path::PathBuf;
+use futures::StreamExt as _;
+use std::{path::PathBuf, pin::pin};
 use tangram_client::{self as tg, prelude::*};
 
 /// Check in an artifact.
@@ -32,6 +33,10 @@ pub struct Args {
 
 	#[arg(short, long, num_args = 1.., action = clap::ArgAction::Append)]
 	pub patterns: Option<Vec<tg::tag::Pattern>>,
+
+	/// Watch the path and check in the artifact again each time it changes.
+	#[arg(long)]
+	pub watch: bool,
 }
 
 impl Cli {
@@ -54,11 +59,22 @@ impl Cli {
 			lockfile: args.lockfile,
 			path,
 			updates,
+			watch: args.watch,
 		};
 		let stream = handle
 			.checkin(arg)
 			.await
 			.map_err(|source| tg::error!(!source, "failed to check in the artifact"))?;
+
+		// If this is a watch, then print the artifact each time it changes.
+		if args.watch {
+			let mut stream = pin!(stream.peekable());
+			while let Some(output) = self.render_progress_stream_next(stream.as_mut()).await? {
+				println!("{}", output.referent.item);
+			}
+			return Ok(());
+		}
+
 		let output = self.render_progress_stream(stream).await?;
 
 		// Print the artifact.
@@ -4,13 +4,31 @@ use tangram_client::{self as tg, prelude::*};
 /// Remove unused processes and objects.
 #[derive(Clone, Debug, clap::Args)]
 #[group(skip)]
-pub struct Args {}
+pub struct Args {
+	/// Report what would be removed without removing anything.
+	#[arg(long)]
+	pub dry_run: bool,
+
+	/// Print the ids of the removed items.
+	#[arg(long)]
+	pub ids: bool,
+
+	#[arg(long)]
+	pub pretty: Option<bool>,
+}
 
 impl Cli {
-	pub async fn command_clean(&mut self, _args: Args) -> tg::Result<()> {
+	pub async fn command_clean(&mut self, args: Args) -> tg::Result<()> {
 		let handle = self.handle().await?;
-		let stream = handle.clean().await?;
-		self.render_progress_stream(stream).await?;
+		let arg = tg::clean::Arg {
+			dry_run: args.dry_run,
+			ids: args.ids,
+		};
+		let stream = handle.clean(arg).await?;
+		let output = self.render_progress_stream(stream).await?;
+		if args.dry_run || args.ids {
+			Self::print_json(&output, args.pretty).await?;
+		}
 		Ok(())
 	}
 }
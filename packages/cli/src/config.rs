@@ -15,6 +15,10 @@ pub struct Config {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub authentication: Option<Either<bool, Authentication>>,
 
+	/// Configure authorization.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub authorization: Option<Either<bool, Authorization>>,
+
 	/// Configure the cleaner task.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub cleaner: Option<Either<bool, Cleaner>>,
@@ -80,6 +84,10 @@ pub struct Advanced {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub init_autobuild_reference: Option<tg::Reference>,
 
+	/// Whether to apply pending database and index migrations when the server starts. If false, then the server refuses to start while migrations are pending, and they must be applied with `tangram server migrate`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub migrate: Option<bool>,
+
 	/// Whether to preserve temporary directories.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub preserve_temp_directories: Option<bool>,
@@ -89,6 +97,11 @@ pub struct Advanced {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub process_dequeue_timeout: Option<Duration>,
 
+	/// The duration a queued process must wait to be dequeued ahead of a process with one greater priority.
+	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub process_priority_aging: Option<Duration>,
+
 	/// Whether to enable publishing of data to tokio console.
 	#[serde(default, skip_serializing_if = "is_false")]
 	pub tokio_console: bool,
@@ -97,6 +110,10 @@ pub struct Advanced {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub shared_directory: Option<bool>,
 
+	/// Whether to compress objects in the store.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub store_compression: Option<Either<bool, StoreCompression>>,
+
 	/// Whether to write process logs to the server's stderr.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub write_process_logs_to_stderr: Option<bool>,
@@ -116,6 +133,14 @@ pub struct AuthenticationProviders {
 	pub github: Option<Oauth>,
 }
 
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Authorization {
+	/// The scopes granted to requests that do not include a token.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub anonymous_scopes: Option<Vec<tg::token::Scope>>,
+}
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Oauth {
@@ -133,11 +158,35 @@ pub struct Cleaner {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub batch_size: Option<usize>,
 
+	/// The maximum total size of the objects in the store. When it is exceeded, the least recently used unreferenced objects are removed regardless of the ttl.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_store_size: Option<u64>,
+
+	/// The minimum time since an object was last touched before it can be removed to bring the store under its maximum size.
+	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub min_eviction_age: Option<Duration>,
+
+	/// Retention policies for tags.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub retention: Option<Vec<CleanerRetention>>,
+
 	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub ttl: Option<Duration>,
 }
 
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CleanerRetention {
+	/// The number of most recently updated matching tags to keep. Less recently updated matching tags are deleted so their items can be collected. If this is not set, matching tags are never deleted.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub keep: Option<usize>,
+
+	/// The tag pattern this policy applies to, such as `release/*`.
+	pub pattern: tg::tag::Pattern,
+}
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Database {
@@ -212,6 +261,9 @@ pub struct SqliteIndex {
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Indexer {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_backlog: Option<u64>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub message_batch_size: Option<usize>,
 
@@ -226,11 +278,19 @@ pub struct Indexer {
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields, tag = "kind", rename_all = "snake_case")]
 pub enum Messenger {
+	Local(LocalMessenger),
 	#[default]
 	Memory,
 	Nats(NatsMessenger),
 }
 
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalMessenger {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub path: Option<PathBuf>,
+}
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct NatsMessenger {
@@ -242,6 +302,11 @@ pub struct NatsMessenger {
 #[serde(deny_unknown_fields)]
 pub struct Remote {
 	pub name: String,
+
+	/// The token to use when connecting to the remote.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub token: Option<String>,
+
 	pub url: Url,
 }
 
@@ -249,6 +314,10 @@ pub struct Remote {
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Runner {
+	/// A delegated cgroup v2 that contains no processes, in which a cgroup is created for each sandboxed process. This is required to enforce cpu, memory, and pids limits.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cgroup: Option<PathBuf>,
+
 	/// The maximum number of concurrent processes.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub concurrency: Option<usize>,
@@ -258,6 +327,10 @@ pub struct Runner {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub heartbeat_interval: Option<Duration>,
 
+	/// The labels to advertise when dequeuing processes.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub labels: Option<Vec<String>>,
+
 	/// The remotes to run processes for.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub remotes: Option<Vec<String>>,
@@ -268,10 +341,12 @@ pub struct Runner {
 pub enum Store {
 	#[cfg(feature = "foundationdb")]
 	Fdb(FdbStore),
+	Fs(FsStore),
 	Lmdb(LmdbStore),
 	#[default]
 	Memory,
 	S3(S3Store),
+	Tiered(TieredStore),
 }
 
 #[cfg(feature = "foundationdb")]
@@ -283,6 +358,13 @@ pub struct FdbStore {
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields)]
+pub struct FsStore {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct LmdbStore {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub path: Option<PathBuf>,
@@ -292,12 +374,69 @@ pub struct LmdbStore {
 #[serde(deny_unknown_fields)]
 pub struct S3Store {
 	pub access_key: Option<String>,
+
 	pub bucket: String,
+
+	/// The maximum number of concurrent requests.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub concurrency: Option<usize>,
+
+	/// The maximum number of times to retry a request that failed with a server error or was throttled.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_retries: Option<usize>,
+
+	/// The size in bytes of each part of a multipart upload.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub multipart_part_size: Option<usize>,
+
+	/// The size in bytes above which objects are uploaded with a multipart upload.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub multipart_threshold: Option<usize>,
+
+	/// Whether to use path-style addressing, `{url}/{bucket}/{key}`, instead of virtual-host addressing, `{bucket}.{host}/{key}`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub path_style: Option<bool>,
+
 	pub region: Option<String>,
+
 	pub secret_key: Option<String>,
+
 	pub url: Url,
 }
 
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StoreCompression {
+	/// The zstd compression level.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub level: Option<i32>,
+
+	/// The size in bytes below which objects are not compressed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub threshold: Option<usize>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TieredStore {
+	/// The interval at which objects are evicted from the local store.
+	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub eviction_interval: Option<Duration>,
+
+	/// The local store.
+	#[serde(default)]
+	pub local: LmdbStore,
+
+	/// The maximum size of the local store in bytes.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_size: Option<u64>,
+
+	/// The remote store.
+	pub remote: S3Store,
+}
+
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Tracing {
@@ -354,6 +493,11 @@ pub struct Watchdog {
 	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
 	pub interval: Option<Duration>,
 
+	/// The duration to wait after sending SIGTERM to a process that exceeded its timeout before sending SIGKILL.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
+	pub timeout_grace_period: Option<Duration>,
+
 	/// The duration without a heartbeat before a process is canceled.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	#[serde_as(as = "Option<DurationSecondsWithFrac>")]
@@ -10,9 +10,12 @@ impl Cli {
 	pub async fn command_health(&mut self, _args: Args) -> tg::Result<()> {
 		let handle = self.handle().await?;
 		let health = handle.health().await?;
-		let health = serde_json::to_string_pretty(&health)
+		let json = serde_json::to_string_pretty(&health)
 			.map_err(|source| tg::error!(!source, "failed to serialize the health"))?;
-		println!("{health}");
+		println!("{json}");
+		if health.status == tg::health::Status::Degraded {
+			return Err(tg::error!("the server is degraded"));
+		}
 		Ok(())
 	}
 }
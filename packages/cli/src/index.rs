@@ -1,16 +1,33 @@
 use crate::Cli;
 use tangram_client::{self as tg, prelude::*};
 
+pub mod rebuild;
+
 /// Index processes and objects.
 #[derive(Clone, Debug, clap::Args)]
 #[group(skip)]
-pub struct Args {}
+pub struct Args {
+	#[command(subcommand)]
+	pub command: Option<Command>,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum Command {
+	Rebuild(self::rebuild::Args),
+}
 
 impl Cli {
-	pub async fn command_index(&mut self, _args: Args) -> tg::Result<()> {
-		let handle = self.handle().await?;
-		let stream = handle.index().await?;
-		self.render_progress_stream(stream).await?;
+	pub async fn command_index(&mut self, args: Args) -> tg::Result<()> {
+		match args.command {
+			None => {
+				let handle = self.handle().await?;
+				let stream = handle.index().await?;
+				self.render_progress_stream(stream).await?;
+			},
+			Some(Command::Rebuild(args)) => {
+				self.command_index_rebuild(args).await?;
+			},
+		}
 		Ok(())
 	}
 }
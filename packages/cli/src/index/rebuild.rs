@@ -0,0 +1,27 @@
+use crate::Cli;
+use tangram_client::{self as tg, prelude::*};
+
+/// Rebuild the index from the store, the cache directory, and the database.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	#[arg(long)]
+	pub pretty: Option<bool>,
+
+	/// Remove every row from the index before rebuilding it.
+	#[arg(long)]
+	pub truncate: bool,
+}
+
+impl Cli {
+	pub async fn command_index_rebuild(&mut self, args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+		let arg = tg::index::rebuild::Arg {
+			truncate: args.truncate,
+		};
+		let stream = handle.rebuild_index(arg).await?;
+		let output = self.render_progress_stream(stream).await?;
+		Self::print_json(&output, args.pretty).await?;
+		Ok(())
+	}
+}
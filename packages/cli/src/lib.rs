@@ -56,6 +56,7 @@ mod sandbox;
 mod server;
 mod tag;
 mod tangram;
+mod token;
 mod tree;
 mod update;
 mod util;
@@ -105,6 +106,10 @@ struct Args {
 	#[arg(short, long)]
 	quiet: bool,
 
+	/// The token to use when connecting to the server.
+	#[arg(long, env = "TANGRAM_TOKEN")]
+	token: Option<String>,
+
 	/// Override the `url` key in the config.
 	#[arg(short, long, env = "TANGRAM_URL")]
 	url: Option<Url>,
@@ -239,6 +244,8 @@ enum Command {
 	#[command(name = "self")]
 	Tangram(self::tangram::Args),
 
+	Token(self::token::Args),
+
 	#[command(hide = true)]
 	Tree(self::tree::Args),
 
@@ -273,12 +280,13 @@ impl Cli {
 
 		// Get the mode.
 		let mode = match &args {
-			// If the command is `tg serve` or `tg server run`, then set the mode to `server`.
+			// If the command is `tg serve`, `tg server run`, or `tg server migrate-store`, then set the mode to `server`.
 			Args {
 				command:
 					Command::Serve(_)
 					| Command::Server(self::server::Args {
-						command: self::server::Command::Run(_),
+						command:
+							self::server::Command::Run(_) | self::server::Command::MigrateStore(_),
 						..
 					}),
 				..
@@ -439,7 +447,7 @@ impl Cli {
 			});
 
 		// Create the client.
-		let client = tg::Client::new(url, Some(version()));
+		let client = tg::Client::new(url, Some(version()), self.args.token.clone());
 
 		// Attempt to connect to the server.
 		let mut connected = client.connect().await.is_ok();
@@ -535,7 +543,7 @@ impl Cli {
 			});
 
 		// Create the client.
-		let client = tg::Client::new(url, Some(version()));
+		let client = tg::Client::new(url, Some(version()), self.args.token.clone());
 
 		// Try to connect for up to one second. If the client is still not connected, then return an error.
 		let mut connected = false;
@@ -554,6 +562,18 @@ impl Cli {
 	}
 
 	async fn server(&self) -> tg::Result<Server> {
+		// Get the config.
+		let config = self.server_config()?;
+
+		// Start the server.
+		let server = tangram_server::Server::start(config)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to start the server"))?;
+
+		Ok(server)
+	}
+
+	fn server_config(&self) -> tg::Result<tangram_server::Config> {
 		// Create the default config.
 		let directory = self
 			.args
@@ -567,6 +587,7 @@ impl Cli {
 		let parallelism = std::thread::available_parallelism().unwrap().into();
 		let advanced = tangram_server::config::Advanced::default();
 		let authentication = None;
+		let authorization = None;
 		let cleaner = None;
 		let database =
 			tangram_server::config::Database::Sqlite(tangram_server::config::SqliteDatabase {
@@ -584,8 +605,10 @@ impl Cli {
 		let messenger = tangram_server::config::Messenger::default();
 		let remotes = None;
 		let runner = Some(tangram_server::config::Runner {
+			cgroup: None,
 			concurrency: parallelism,
 			heartbeat_interval: Duration::from_secs(1),
+			labels: Vec::new(),
 			remotes: Vec::new(),
 		});
 
@@ -637,6 +660,7 @@ impl Cli {
 		let mut config = tangram_server::Config {
 			advanced,
 			authentication,
+			authorization,
 			cleaner,
 			database,
 			http,
@@ -659,15 +683,42 @@ impl Cli {
 			.as_ref()
 			.and_then(|config| config.advanced.as_ref())
 		{
+			if let Some(migrate) = advanced.migrate {
+				config.advanced.migrate = migrate;
+			}
 			if let Some(process_dequeue_timeout) = advanced.process_dequeue_timeout {
 				config.advanced.process_dequeue_timeout = process_dequeue_timeout;
 			}
+			if let Some(process_priority_aging) = advanced.process_priority_aging {
+				config.advanced.process_priority_aging = process_priority_aging;
+			}
 			if let Some(preserve_temp_directories) = advanced.preserve_temp_directories {
 				config.advanced.preserve_temp_directories = preserve_temp_directories;
 			}
 			if let Some(shared_directory) = advanced.shared_directory {
 				config.advanced.shared_directory = shared_directory;
 			}
+			match advanced.store_compression.clone() {
+				None => (),
+				Some(Either::Left(false)) => {
+					config.advanced.store_compression = None;
+				},
+				Some(Either::Left(true)) => {
+					config.advanced.store_compression =
+						Some(tangram_server::config::StoreCompression::default());
+				},
+				Some(Either::Right(store_compression)) => {
+					let mut store_compression_ =
+						config.advanced.store_compression.take().unwrap_or_default();
+					if let Some(level) = store_compression.level {
+						store_compression_.level = level;
+					}
+					if let Some(threshold) = store_compression.threshold {
+						store_compression_.threshold = threshold;
+					}
+					config.advanced.store_compression = Some(store_compression_);
+				},
+			}
 			if let Some(write_process_logs_to_stderr) = advanced.write_process_logs_to_stderr {
 				config.advanced.write_process_logs_to_stderr = write_process_logs_to_stderr;
 			}
@@ -703,6 +754,28 @@ impl Cli {
 			},
 		}
 
+		// Set the authorization config.
+		match self
+			.config
+			.as_ref()
+			.and_then(|config| config.authorization.as_ref())
+		{
+			None => (),
+			Some(Either::Left(false)) => {
+				config.authorization = None;
+			},
+			Some(Either::Left(true)) => {
+				config.authorization = Some(tangram_server::config::Authorization::default());
+			},
+			Some(Either::Right(authorization)) => {
+				let mut authorization_ = config.authorization.unwrap_or_default();
+				if let Some(anonymous_scopes) = authorization.anonymous_scopes.clone() {
+					authorization_.anonymous_scopes = anonymous_scopes;
+				}
+				config.authorization = Some(authorization_);
+			},
+		}
+
 		// Set the cleaner config.
 		match self
 			.config
@@ -721,6 +794,21 @@ impl Cli {
 				if let Some(batch_size) = cleaner.batch_size {
 					cleaner_.batch_size = batch_size;
 				}
+				if let Some(max_store_size) = cleaner.max_store_size {
+					cleaner_.max_store_size = Some(max_store_size);
+				}
+				if let Some(min_eviction_age) = cleaner.min_eviction_age {
+					cleaner_.min_eviction_age = min_eviction_age;
+				}
+				if let Some(retention) = cleaner.retention {
+					cleaner_.retention = retention
+						.into_iter()
+						.map(|retention| tangram_server::config::CleanerRetention {
+							keep: retention.keep,
+							pattern: retention.pattern,
+						})
+						.collect();
+				}
 				if let Some(ttl) = cleaner.ttl {
 					cleaner_.ttl = ttl;
 				}
@@ -829,6 +917,9 @@ impl Cli {
 			},
 			Some(Either::Right(indexer)) => {
 				let mut indexer_ = config.indexer.unwrap_or_default();
+				if let Some(max_backlog) = indexer.max_backlog {
+					indexer_.max_backlog = Some(max_backlog);
+				}
 				if let Some(message_batch_size) = indexer.message_batch_size {
 					indexer_.message_batch_size = message_batch_size;
 				}
@@ -849,6 +940,15 @@ impl Cli {
 			.and_then(|config| config.messenger.clone())
 		{
 			config.messenger = match messenger {
+				self::config::Messenger::Local(messenger) => {
+					tangram_server::config::Messenger::Local(
+						tangram_server::config::LocalMessenger {
+							path: messenger
+								.path
+								.unwrap_or_else(|| config.directory.join("messenger")),
+						},
+					)
+				},
 				self::config::Messenger::Memory => tangram_server::config::Messenger::Memory,
 				self::config::Messenger::Nats(messenger) => {
 					let mut messenger_ = tangram_server::config::NatsMessenger::default();
@@ -871,6 +971,7 @@ impl Cli {
 					.iter()
 					.map(|remote| tangram_server::config::Remote {
 						name: remote.name.clone(),
+						token: remote.token.clone(),
 						url: remote.url.clone(),
 					})
 					.collect(),
@@ -892,12 +993,18 @@ impl Cli {
 			},
 			Some(Either::Right(runner)) => {
 				let mut runner_ = config.runner.unwrap_or_default();
+				if let Some(cgroup) = runner.cgroup.clone() {
+					runner_.cgroup = Some(cgroup);
+				}
 				if let Some(concurrency) = runner.concurrency {
 					runner_.concurrency = concurrency;
 				}
 				if let Some(heartbeat_interval) = runner.heartbeat_interval {
 					runner_.heartbeat_interval = heartbeat_interval;
 				}
+				if let Some(labels) = runner.labels.clone() {
+					runner_.labels = labels;
+				}
 				if let Some(remotes) = runner.remotes.clone() {
 					runner_.remotes = remotes;
 				}
@@ -907,29 +1014,7 @@ impl Cli {
 
 		// Set the store config.
 		if let Some(store) = self.config.as_ref().and_then(|config| config.store.clone()) {
-			config.store = match store {
-				#[cfg(feature = "foundationdb")]
-				config::Store::Fdb(fdb) => {
-					tangram_server::config::Store::Fdb(tangram_server::config::FdbStore {
-						path: fdb.path,
-					})
-				},
-				config::Store::Lmdb(lmdb) => {
-					tangram_server::config::Store::Lmdb(tangram_server::config::LmdbStore {
-						path: lmdb.path.unwrap_or_else(|| config.directory.join("store")),
-					})
-				},
-				config::Store::Memory => tangram_server::config::Store::Memory,
-				config::Store::S3(s3) => {
-					tangram_server::config::Store::S3(tangram_server::config::S3Store {
-						access_key: s3.access_key,
-						bucket: s3.bucket,
-						region: s3.region,
-						secret_key: s3.secret_key,
-						url: s3.url,
-					})
-				},
-			};
+			config.store = Self::server_store_config(store, &config.directory)?;
 		}
 
 		// Set the vfs config.
@@ -977,6 +1062,9 @@ impl Cli {
 				if let Some(interval) = watchdog.interval {
 					watchdog_.interval = interval;
 				}
+				if let Some(timeout_grace_period) = watchdog.timeout_grace_period {
+					watchdog_.timeout_grace_period = timeout_grace_period;
+				}
 				if let Some(ttl) = watchdog.ttl {
 					watchdog_.ttl = ttl;
 				}
@@ -984,12 +1072,71 @@ impl Cli {
 			},
 		}
 
-		// Start the server.
-		let server = tangram_server::Server::start(config)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to start the server"))?;
+		Ok(config)
+	}
 
-		Ok(server)
+	fn server_store_config(
+		store: config::Store,
+		directory: &Path,
+	) -> tg::Result<tangram_server::config::Store> {
+		let s3_store = |s3: config::S3Store| {
+			let mut s3_ = tangram_server::config::S3Store {
+				access_key: s3.access_key,
+				bucket: s3.bucket,
+				region: s3.region,
+				secret_key: s3.secret_key,
+				url: s3.url,
+				..Default::default()
+			};
+			if let Some(concurrency) = s3.concurrency {
+				s3_.concurrency = concurrency;
+			}
+			if let Some(max_retries) = s3.max_retries {
+				s3_.max_retries = max_retries;
+			}
+			if let Some(multipart_part_size) = s3.multipart_part_size {
+				s3_.multipart_part_size = multipart_part_size;
+			}
+			if let Some(multipart_threshold) = s3.multipart_threshold {
+				s3_.multipart_threshold = multipart_threshold;
+			}
+			if let Some(path_style) = s3.path_style {
+				s3_.path_style = path_style;
+			}
+			s3_.validate()?;
+			Ok::<_, tg::Error>(s3_)
+		};
+		let store = match store {
+			#[cfg(feature = "foundationdb")]
+			config::Store::Fdb(fdb) => {
+				tangram_server::config::Store::Fdb(tangram_server::config::FdbStore {
+					path: fdb.path,
+				})
+			},
+			config::Store::Fs(fs) => {
+				tangram_server::config::Store::Fs(tangram_server::config::FsStore {
+					path: fs.path.unwrap_or_else(|| directory.join("objects")),
+				})
+			},
+			config::Store::Lmdb(lmdb) => {
+				tangram_server::config::Store::Lmdb(tangram_server::config::LmdbStore {
+					path: lmdb.path.unwrap_or_else(|| directory.join("store")),
+				})
+			},
+			config::Store::Memory => tangram_server::config::Store::Memory,
+			config::Store::S3(s3) => tangram_server::config::Store::S3(s3_store(s3)?),
+			config::Store::Tiered(tiered) => {
+				tangram_server::config::Store::Tiered(tangram_server::config::TieredStore {
+					eviction_interval: tiered.eviction_interval.unwrap_or(Duration::from_secs(60)),
+					local: tangram_server::config::LmdbStore {
+						path: tiered.local.path.unwrap_or_else(|| directory.join("store")),
+					},
+					max_size: tiered.max_size.unwrap_or(10_000_000_000),
+					remote: s3_store(tiered.remote)?,
+				})
+			},
+		};
+		Ok(store)
 	}
 
 	/// Start the server.
@@ -1167,6 +1314,7 @@ impl Cli {
 			Command::Status(args) => self.command_process_status(args).boxed(),
 			Command::Tag(args) => self.command_tag(args).boxed(),
 			Command::Tangram(args) => self.command_tangram(args).boxed(),
+			Command::Token(args) => self.command_token(args).boxed(),
 			Command::Tree(args) => self.command_tree(args).boxed(),
 			Command::Update(args) => self.command_update(args).boxed(),
 			Command::View(args) => self.command_view(args).boxed(),
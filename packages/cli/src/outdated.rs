@@ -1,20 +1,198 @@
 use crate::Cli;
-use tangram_client as tg;
+use std::collections::BTreeSet;
+use tangram_client::{self as tg, prelude::*};
 
 /// Get a package's outdated dependencies.
 #[derive(Clone, Debug, clap::Args)]
 #[group(skip)]
 pub struct Args {
+	/// The format of the output.
+	#[arg(long)]
+	pub format: Option<Format>,
+
 	/// If this flag is set, the package's lockfile will not be updated.
 	#[arg(long)]
 	pub locked: bool,
 
 	#[arg(index = 1, default_value = ".")]
 	pub package: tg::Reference,
+
+	#[arg(long)]
+	pub pretty: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum Format {
+	Json,
+	#[default]
+	Table,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Entry {
+	/// The dependency's reference.
+	pub reference: tg::Reference,
+
+	/// The tag the dependency is locked to.
+	pub locked: Option<tg::Tag>,
+
+	/// The newest tag that matches the dependency's pattern.
+	pub compatible: Option<tg::Tag>,
+
+	/// The newest tag with the dependency's name.
+	pub latest: Option<tg::Tag>,
 }
 
 impl Cli {
-	pub async fn command_outdated(&mut self, _args: Args) -> tg::Result<()> {
-		Err(tg::error!("unimplemented"))
+	pub async fn command_outdated(&mut self, args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+
+		// Get the path.
+		let path = args
+			.package
+			.item()
+			.try_unwrap_path_ref()
+			.map_err(|_| tg::error!(%reference = args.package, "expected a path reference"))?;
+		let path = std::path::absolute(path)
+			.map_err(|source| tg::error!(!source, "failed to get the absolute path"))?;
+
+		// Check in the package.
+		let arg = tg::checkin::Arg {
+			destructive: false,
+			deterministic: false,
+			ignore: true,
+			locked: args.locked,
+			lockfile: true,
+			path: path.clone(),
+			updates: Vec::new(),
+			watch: false,
+		};
+		let stream = handle
+			.checkin(arg)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to check in the package"))?;
+		self.render_progress_stream(stream).await?;
+
+		// Read the lockfile.
+		let metadata = tokio::fs::metadata(&path).await.map_err(
+			|source| tg::error!(!source, %path = path.display(), "failed to get the metadata"),
+		)?;
+		let directory = if metadata.is_dir() {
+			path.as_path()
+		} else {
+			path.parent().ok_or_else(
+				|| tg::error!(%path = path.display(), "expected the path to have a parent"),
+			)?
+		};
+		let lockfile_path = directory.join(tg::package::LOCKFILE_FILE_NAME);
+		let lockfile = tg::Lockfile::try_read(&lockfile_path)
+			.await?
+			.unwrap_or_default();
+
+		// Collect the tag dependencies.
+		let dependencies = lockfile
+			.nodes
+			.iter()
+			.filter_map(|node| node.try_unwrap_file_ref().ok())
+			.flat_map(|file| file.dependencies.iter())
+			.filter(|(reference, _)| reference.item().try_unwrap_tag_ref().is_ok())
+			.map(|(reference, referent)| (reference.clone(), referent.tag.clone()))
+			.collect::<BTreeSet<_>>();
+
+		// Get the newest tags for each dependency.
+		let mut entries = Vec::with_capacity(dependencies.len());
+		for (reference, locked) in dependencies {
+			let pattern = reference.item().unwrap_tag_ref();
+			let remote = reference
+				.options()
+				.and_then(|options| options.remote.clone());
+
+			// Get the newest tag that matches the pattern.
+			let compatible = self.get_newest_tag(pattern.clone(), remote.clone()).await?;
+
+			// Get the newest tag with the same name.
+			let latest = if matches!(
+				pattern.components().last(),
+				Some(
+					tg::tag::pattern::Component::Version(_) | tg::tag::pattern::Component::Wildcard
+				)
+			) {
+				let pattern = format!("{}/*", pattern.name()).parse()?;
+				self.get_newest_tag(pattern, remote).await?
+			} else {
+				compatible.clone()
+			};
+
+			entries.push(Entry {
+				reference,
+				locked,
+				compatible,
+				latest,
+			});
+		}
+
+		// Print the entries.
+		match args.format.unwrap_or_default() {
+			Format::Json => {
+				Self::print_json(&entries, args.pretty).await?;
+			},
+			Format::Table => {
+				Self::print_outdated_table(&entries);
+			},
+		}
+
+		Ok(())
+	}
+
+	async fn get_newest_tag(
+		&mut self,
+		pattern: tg::tag::Pattern,
+		remote: Option<String>,
+	) -> tg::Result<Option<tg::Tag>> {
+		let handle = self.handle().await?;
+		let arg = tg::tag::list::Arg {
+			length: Some(1),
+			pattern: pattern.clone(),
+			remote,
+			reverse: true,
+		};
+		let output = handle
+			.list_tags(arg)
+			.await
+			.map_err(|source| tg::error!(!source, %pattern, "failed to list the tags"))?;
+		let tag = output.data.into_iter().next().map(|output| output.tag);
+		Ok(tag)
+	}
+
+	fn print_outdated_table(entries: &[Entry]) {
+		let header = ["dependency", "locked", "compatible", "latest"].map(ToOwned::to_owned);
+		let rows = entries
+			.iter()
+			.map(|entry| {
+				let tag =
+					|tag: Option<&tg::Tag>| tag.map_or_else(|| "-".to_owned(), ToString::to_string);
+				[
+					entry.reference.to_string(),
+					tag(entry.locked.as_ref()),
+					tag(entry.compatible.as_ref()),
+					tag(entry.latest.as_ref()),
+				]
+			})
+			.collect::<Vec<_>>();
+		let mut widths = header.clone().map(|column| column.len());
+		for row in &rows {
+			for (width, column) in widths.iter_mut().zip(row) {
+				*width = (*width).max(column.len());
+			}
+		}
+		for row in std::iter::once(&header).chain(&rows) {
+			let line = row
+				.iter()
+				.zip(widths)
+				.map(|(column, width)| format!("{column:<width$}"))
+				.collect::<Vec<_>>()
+				.join("  ");
+			println!("{}", line.trim_end());
+		}
 	}
 }
@@ -1,6 +1,8 @@
 use crate::Cli;
 use futures::TryStreamExt as _;
+use num::ToPrimitive as _;
 use tangram_client as tg;
+use time::format_description::well_known::Rfc3339;
 use tokio::io::AsyncWriteExt as _;
 
 /// Get a process's log.
@@ -20,8 +22,24 @@ pub struct Args {
 	#[arg(short, long)]
 	pub remote: Option<Option<String>>,
 
+	/// Only print the bytes written at or after this time, as an RFC 3339 timestamp or seconds since the Unix epoch.
+	#[arg(long, value_parser = parse_timestamp)]
+	pub since: Option<i64>,
+
 	#[arg(long)]
 	pub size: Option<u64>,
+
+	/// Only print the bytes written to this stream.
+	#[arg(long)]
+	pub stream: Option<tg::process::log::Stream>,
+
+	/// Prefix each line with the time it was written.
+	#[arg(long)]
+	pub timestamps: bool,
+
+	/// Only print the bytes written before this time, as an RFC 3339 timestamp or seconds since the Unix epoch.
+	#[arg(long, value_parser = parse_timestamp)]
+	pub until: Option<i64>,
 }
 
 impl Cli {
@@ -37,7 +55,10 @@ impl Cli {
 			length: args.length,
 			position: args.position.map(std::io::SeekFrom::Start),
 			remote,
+			since: args.since,
 			size: args.size,
+			stream: args.stream,
+			until: args.until,
 		};
 		let mut log = process
 			.log(&handle, arg)
@@ -46,9 +67,31 @@ impl Cli {
 
 		// Print the log.
 		let mut stdout = tokio::io::stdout();
+		let mut line_start = true;
 		while let Some(chunk) = log.try_next().await? {
+			let bytes = if args.timestamps {
+				let timestamp = chunk
+					.timestamp
+					.and_then(|timestamp| {
+						time::OffsetDateTime::from_unix_timestamp_nanos(timestamp.into()).ok()
+					})
+					.and_then(|timestamp| timestamp.format(&Rfc3339).ok())
+					.unwrap_or_else(|| "-".to_owned());
+				let mut bytes = Vec::with_capacity(chunk.bytes.len());
+				for line in chunk.bytes.split_inclusive(|byte| *byte == b'\n') {
+					if line_start {
+						bytes.extend_from_slice(timestamp.as_bytes());
+						bytes.push(b' ');
+					}
+					bytes.extend_from_slice(line);
+					line_start = line.ends_with(b"\n");
+				}
+				bytes.into()
+			} else {
+				chunk.bytes
+			};
 			stdout
-				.write_all(&chunk.bytes)
+				.write_all(&bytes)
 				.await
 				.map_err(|source| tg::error!(!source, "failed to write to stdout"))?;
 		}
@@ -60,3 +103,19 @@ impl Cli {
 		Ok(())
 	}
 }
+
+/// Parse a timestamp into nanoseconds since the Unix epoch.
+fn parse_timestamp(s: &str) -> tg::Result<i64> {
+	let timestamp = if let Ok(timestamp) = s.parse::<i64>() {
+		time::OffsetDateTime::from_unix_timestamp(timestamp)
+			.map_err(|source| tg::error!(!source, %timestamp = s, "invalid timestamp"))?
+	} else {
+		time::OffsetDateTime::parse(s, &Rfc3339)
+			.map_err(|source| tg::error!(!source, %timestamp = s, "invalid timestamp"))?
+	};
+	let timestamp = timestamp
+		.unix_timestamp_nanos()
+		.to_i64()
+		.ok_or_else(|| tg::error!(%timestamp = s, "invalid timestamp"))?;
+	Ok(timestamp)
+}
@@ -39,6 +39,10 @@ pub struct Options {
 	#[arg(long)]
 	pub checksum: Option<tg::Checksum>,
 
+	/// Limit the process's CPU quota in millicores, where 1000 is one core.
+	#[arg(long)]
+	pub cpu: Option<u64>,
+
 	/// Set the working directory for the process.
 	#[arg(short = 'C', long)]
 	pub cwd: Option<PathBuf>,
@@ -55,10 +59,18 @@ pub struct Options {
 	#[arg(long)]
 	pub host: Option<String>,
 
+	/// Require a label on the runner that runs the process.
+	#[arg(long = "label", num_args = 1, action = clap::ArgAction::Append)]
+	pub labels: Vec<String>,
+
 	/// If this flag is set, the package's lockfile will not be updated.
 	#[arg(long)]
 	pub locked: bool,
 
+	/// Limit the process's memory, for example "512MiB".
+	#[arg(long)]
+	pub memory: Option<byte_unit::Byte>,
+
 	/// Configure mounts.
 	#[arg(short, long = "mount", num_args = 1, action = clap::ArgAction::Append)]
 	pub mounts: Vec<Either<tg::process::Mount, tg::command::Mount>>,
@@ -67,6 +79,18 @@ pub struct Options {
 	#[arg(long)]
 	pub network: bool,
 
+	/// Limit the number of files the process may have open.
+	#[arg(long)]
+	pub open_files: Option<u64>,
+
+	/// Limit the number of processes and threads the process may create.
+	#[arg(long)]
+	pub pids: Option<u64>,
+
+	/// Set the priority. Processes with a higher priority are dequeued first.
+	#[arg(long, allow_hyphen_values = true)]
+	pub priority: Option<i64>,
+
 	/// The remote to use.
 	#[allow(clippy::option_option)]
 	#[arg(short, long)]
@@ -84,6 +108,10 @@ pub struct Options {
 	#[arg(long)]
 	pub tag: Option<tg::Tag>,
 
+	/// Limit the process's wall-clock time in seconds.
+	#[arg(long)]
+	pub timeout: Option<u64>,
+
 	/// Allocate a terminal when running the process.
 	#[arg(long, default_value = "true", action = clap::ArgAction::Set)]
 	pub tty: bool,
@@ -109,17 +137,6 @@ impl Cli {
 		stdin: Option<tg::process::Stdio>,
 		stdout: Option<tg::process::Stdio>,
 	) -> tg::Result<(tg::Referent<tg::object::Id>, tg::Process)> {
-		let handle = self.handle().await?;
-
-		// Get the remote.
-		let remote = options
-			.remote
-			.clone()
-			.map(|remote| remote.unwrap_or_else(|| "default".to_owned()));
-
-		// Determine if the process is sandboxed.
-		let sandbox = options.sandbox || remote.is_some();
-
 		// If the reference is a path to a directory and the path does not contain a root module, then init.
 		if let Ok(path) = reference.item().try_unwrap_path_ref() {
 			let metadata = tokio::fs::metadata(&path).await.map_err(
@@ -149,6 +166,37 @@ impl Cli {
 
 		// Get the reference.
 		let referent = self.get_reference(&reference).await?;
+
+		// Spawn the process.
+		self.spawn_with_referent(
+			options, &reference, referent, trailing, stderr, stdin, stdout,
+		)
+		.await
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	pub(crate) async fn spawn_with_referent(
+		&mut self,
+		options: Options,
+		reference: &tg::Reference,
+		referent: tg::Referent<Either<tg::Process, tg::Object>>,
+		trailing: Vec<String>,
+		stderr: Option<tg::process::Stdio>,
+		stdin: Option<tg::process::Stdio>,
+		stdout: Option<tg::process::Stdio>,
+	) -> tg::Result<(tg::Referent<tg::object::Id>, tg::Process)> {
+		let handle = self.handle().await?;
+
+		// Get the remote.
+		let remote = options
+			.remote
+			.clone()
+			.map(|remote| remote.unwrap_or_else(|| "default".to_owned()));
+
+		// Determine if the process is sandboxed.
+		let sandbox = options.sandbox || remote.is_some();
+
+		// Get the item.
 		let item = referent
 			.item
 			.right()
@@ -415,21 +463,34 @@ impl Cli {
 			}
 		}
 
+		// Get the limits.
+		let limits = tg::process::Limits {
+			cpu: options.cpu,
+			memory: options.memory.map(|memory| memory.as_u64()),
+			open_files: options.open_files,
+			pids: options.pids,
+		};
+		let limits = (!limits.is_empty()).then_some(limits);
+
 		// Spawn the process.
 		let arg = tg::process::spawn::Arg {
 			cached: options.cached,
 			checksum: options.checksum,
 			command: Some(command.id()),
+			labels: options.labels,
+			limits,
 			mounts,
 			network,
 			parent: None,
 			path: None,
+			priority: options.priority,
 			remote: remote.clone(),
 			retry,
 			stderr,
 			stdin,
 			stdout,
 			tag: None,
+			timeout: options.timeout,
 		};
 		let process = tg::Process::spawn(&handle, arg).await?;
 
@@ -462,17 +523,24 @@ impl Default for Options {
 			arg_values: vec![],
 			cached: None,
 			checksum: None,
+			cpu: None,
 			cwd: None,
 			env_strings: vec![],
 			env_values: vec![],
 			host: None,
+			labels: vec![],
 			locked: false,
+			memory: None,
 			mounts: vec![],
 			network: false,
+			open_files: None,
+			pids: None,
+			priority: None,
 			remote: None,
 			retry: false,
 			sandbox: false,
 			tag: None,
+			timeout: None,
 			tty: true,
 		}
 	}
@@ -1,16 +1,16 @@
 use crate::{Cli, viewer::clip};
 use crossterm::{self as ct, style::Stylize as _};
-use futures::{FutureExt as _, Stream, StreamExt as _, future};
+use futures::{FutureExt as _, Stream, StreamExt as _, future, stream::Peekable};
 use indexmap::IndexMap;
 use num::ToPrimitive as _;
 use std::{
 	fmt::Write as _,
 	io::{IsTerminal as _, Write as _},
-	pin::pin,
+	pin::{Pin, pin},
 	time::Duration,
 };
 use tangram_client as tg;
-use tangram_futures::stream::TryExt as _;
+use tangram_futures::stream::{Ext as _, TryExt as _};
 
 struct State<T> {
 	indicators: IndexMap<String, tg::progress::Indicator>,
@@ -90,6 +90,27 @@ impl Cli {
 		Ok(output)
 	}
 
+	/// Render a progress stream that produces an output each time something changes, such as a watch, up to and including its next output. Returns `None` if the stream has ended.
+	pub async fn render_progress_stream_next<T, S>(
+		&mut self,
+		mut stream: Pin<&mut Peekable<S>>,
+	) -> tg::Result<Option<T>>
+	where
+		S: Stream<Item = tg::Result<tg::progress::Event<T>>>,
+	{
+		if stream.as_mut().peek().await.is_none() {
+			return Ok(None);
+		}
+		let stream = stream.take_while_inclusive(|event| {
+			future::ready(!matches!(
+				event,
+				Ok(tg::progress::Event::Output(_)) | Err(_)
+			))
+		});
+		let output = self.render_progress_stream(stream).await?;
+		Ok(Some(output))
+	}
+
 	async fn render_progress_stream_update<T>(
 		&mut self,
 		state: &mut State<T>,
@@ -9,6 +9,10 @@ pub struct Args {
 	#[arg(index = 1)]
 	pub name: String,
 
+	/// The token to use when connecting to the remote.
+	#[arg(long)]
+	pub token: Option<String>,
+
 	#[arg(index = 2)]
 	pub url: Url,
 }
@@ -17,8 +21,9 @@ impl Cli {
 	pub async fn command_remote_put(&mut self, args: Args) -> tg::Result<()> {
 		let handle = self.handle().await?;
 		let name = args.name;
+		let token = args.token;
 		let url = args.url;
-		let arg = tg::remote::put::Arg { url };
+		let arg = tg::remote::put::Arg { token, url };
 		handle.put_remote(&name, arg).await?;
 		Ok(())
 	}
@@ -1,11 +1,15 @@
 use crate::Cli;
 use tangram_client as tg;
 
+pub mod fsck;
+pub mod migrate;
+pub mod migrate_store;
 pub mod restart;
 pub mod run;
 pub mod start;
 pub mod status;
 pub mod stop;
+pub mod token;
 
 /// Manage the server.
 #[derive(Clone, Debug, clap::Args)]
@@ -17,16 +21,29 @@ pub struct Args {
 
 #[derive(Clone, Debug, clap::Subcommand)]
 pub enum Command {
+	Fsck(self::fsck::Args),
+	Migrate(self::migrate::Args),
+	MigrateStore(self::migrate_store::Args),
 	Restart(self::restart::Args),
 	Run(self::run::Args),
 	Start(self::start::Args),
 	Status(self::status::Args),
 	Stop(self::stop::Args),
+	Token(self::token::Args),
 }
 
 impl Cli {
 	pub async fn command_server(&mut self, args: Args) -> tg::Result<()> {
 		match args.command {
+			Command::Fsck(args) => {
+				self.command_server_fsck(args).await?;
+			},
+			Command::Migrate(args) => {
+				self.command_server_migrate(args).await?;
+			},
+			Command::MigrateStore(args) => {
+				self.command_server_migrate_store(args).await?;
+			},
 			Command::Restart(args) => {
 				self.command_server_restart(args).await?;
 			},
@@ -42,6 +59,9 @@ impl Cli {
 			Command::Stop(args) => {
 				self.command_server_stop(args).await?;
 			},
+			Command::Token(args) => {
+				self.command_server_token(args).await?;
+			},
 		}
 		Ok(())
 	}
@@ -0,0 +1,30 @@
+use crate::Cli;
+use tangram_client::{self as tg, prelude::*};
+
+/// Verify the integrity of the store and the index.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	#[arg(long)]
+	pub pretty: Option<bool>,
+
+	/// Repair the discrepancies that are found.
+	#[arg(long)]
+	pub repair: bool,
+}
+
+impl Cli {
+	pub async fn command_server_fsck(&mut self, args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+		let arg = tg::fsck::Arg {
+			repair: args.repair,
+		};
+		let stream = handle.fsck(arg).await?;
+		let output = self.render_progress_stream(stream).await?;
+		Self::print_json(&output, args.pretty).await?;
+		if !output.is_empty() && !output.repaired {
+			return Err(tg::error!("found discrepancies in the store and the index"));
+		}
+		Ok(())
+	}
+}
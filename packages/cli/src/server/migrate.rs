@@ -0,0 +1,23 @@
+use crate::Cli;
+use anstream::eprintln;
+use crossterm::style::Stylize as _;
+use tangram_client as tg;
+
+/// Apply pending database and index migrations. The server must not be running.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {}
+
+impl Cli {
+	pub async fn command_server_migrate(&mut self, _args: Args) -> tg::Result<()> {
+		let config = self.server_config()?;
+		let output = tangram_server::Server::migrate(&config).await?;
+		eprintln!(
+			"{} applied {} database migrations and {} index migrations",
+			"info".blue().bold(),
+			output.database,
+			output.index,
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,57 @@
+use crate::{Cli, config};
+use std::path::PathBuf;
+use tangram_client as tg;
+
+/// Migrate every object from one store to another.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	/// The path to a file that records the migration's progress. If the migration is interrupted, then running it again with the same checkpoint resumes it.
+	#[arg(long)]
+	pub checkpoint: Option<PathBuf>,
+
+	/// The store to migrate from, either as JSON or as a path to a JSON file, in the same format as the store config.
+	#[arg(long)]
+	pub from: String,
+
+	/// The store to migrate to, either as JSON or as a path to a JSON file, in the same format as the store config.
+	#[arg(long)]
+	pub to: String,
+}
+
+impl Cli {
+	pub async fn command_server_migrate_store(&mut self, args: Args) -> tg::Result<()> {
+		let config = self.server_config()?;
+
+		// Parse the stores.
+		let from = Self::parse_store_config(&args.from)?;
+		let from = Self::server_store_config(from, &config.directory);
+		let to = Self::parse_store_config(&args.to)?;
+		let to = Self::server_store_config(to, &config.directory);
+
+		// Migrate the store.
+		let arg = tangram_server::MigrateStoreArg {
+			checkpoint: args.checkpoint,
+			compression: config.advanced.store_compression,
+			from,
+			to,
+		};
+		let stream = tangram_server::migrate_store(arg)?;
+		self.render_progress_stream(stream).await?;
+
+		Ok(())
+	}
+
+	fn parse_store_config(string: &str) -> tg::Result<config::Store> {
+		let json = if string.trim_start().starts_with('{') {
+			string.to_owned()
+		} else {
+			std::fs::read_to_string(string).map_err(
+				|source| tg::error!(!source, %path = string, "failed to read the store config"),
+			)?
+		};
+		let store = serde_json::from_str(&json)
+			.map_err(|source| tg::error!(!source, "failed to deserialize the store config"))?;
+		Ok(store)
+	}
+}
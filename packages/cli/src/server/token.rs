@@ -0,0 +1,30 @@
+use crate::Cli;
+use tangram_client as tg;
+
+/// Manage tokens directly in the server's database, without a running server.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	#[command(subcommand)]
+	pub command: Command,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum Command {
+	/// Create a token. Use this to create the first token when authorization is enabled.
+	Create(crate::token::create::Args),
+}
+
+impl Cli {
+	pub async fn command_server_token(&mut self, args: Args) -> tg::Result<()> {
+		match args.command {
+			Command::Create(args) => {
+				let config = self.server_config()?;
+				let arg = Self::token_create_arg(args)?;
+				let output = tangram_server::Server::create_token_offline(&config, arg).await?;
+				println!("{}", output.token);
+			},
+		}
+		Ok(())
+	}
+}
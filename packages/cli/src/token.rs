@@ -0,0 +1,38 @@
+use crate::Cli;
+use tangram_client as tg;
+
+pub mod create;
+pub mod list;
+pub mod revoke;
+
+/// Manage tokens.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	#[command(subcommand)]
+	pub command: Command,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum Command {
+	Create(self::create::Args),
+	List(self::list::Args),
+	Revoke(self::revoke::Args),
+}
+
+impl Cli {
+	pub async fn command_token(&mut self, args: Args) -> tg::Result<()> {
+		match args.command {
+			Command::Create(args) => {
+				self.command_token_create(args).await?;
+			},
+			Command::List(args) => {
+				self.command_token_list(args).await?;
+			},
+			Command::Revoke(args) => {
+				self.command_token_revoke(args).await?;
+			},
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,57 @@
+use crate::Cli;
+use tangram_client::{self as tg, prelude::*};
+
+/// Create a token.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	/// The number of seconds until the token expires.
+	#[arg(long)]
+	pub expires_in: Option<u64>,
+
+	/// A name to identify the token.
+	#[arg(long)]
+	pub name: Option<String>,
+
+	/// A scope to grant the token. If none are provided, then the token is granted every scope.
+	#[arg(long = "scope")]
+	pub scopes: Vec<tg::token::Scope>,
+}
+
+impl Cli {
+	pub async fn command_token_create(&mut self, args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+		let arg = Self::token_create_arg(args)?;
+		let output = handle.create_token(arg).await?;
+		println!("{}", output.token);
+		Ok(())
+	}
+
+	pub(crate) fn token_create_arg(args: Args) -> tg::Result<tg::token::create::Arg> {
+		let expires_at = args
+			.expires_in
+			.map(|expires_in| {
+				let expires_in = i64::try_from(expires_in)
+					.map_err(|source| tg::error!(!source, "invalid expiration"))?;
+				let now = std::time::SystemTime::now()
+					.duration_since(std::time::UNIX_EPOCH)
+					.map_err(|source| tg::error!(!source, "failed to get the current time"))?
+					.as_secs();
+				let now = i64::try_from(now)
+					.map_err(|source| tg::error!(!source, "failed to get the current time"))?;
+				Ok::<_, tg::Error>(now + expires_in)
+			})
+			.transpose()?;
+		let scopes = if args.scopes.is_empty() {
+			tg::token::Scope::all()
+		} else {
+			args.scopes
+		};
+		let arg = tg::token::create::Arg {
+			expires_at,
+			name: args.name,
+			scopes,
+		};
+		Ok(arg)
+	}
+}
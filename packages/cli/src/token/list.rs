@@ -0,0 +1,22 @@
+use crate::Cli;
+use itertools::Itertools as _;
+use tangram_client::{self as tg, prelude::*};
+
+/// List tokens.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {}
+
+impl Cli {
+	pub async fn command_token_list(&mut self, _args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+		let arg = tg::token::list::Arg::default();
+		let tokens = handle.list_tokens(arg).await?;
+		for token in tokens.data {
+			let name = token.name.as_deref().unwrap_or("-");
+			let scopes = token.scopes.iter().join(",");
+			println!("{} {name} {scopes}", token.id);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,18 @@
+use crate::Cli;
+use tangram_client::{self as tg, prelude::*};
+
+/// Revoke a token.
+#[derive(Clone, Debug, clap::Args)]
+#[group(skip)]
+pub struct Args {
+	#[arg(index = 1)]
+	pub id: tg::token::Id,
+}
+
+impl Cli {
+	pub async fn command_token_revoke(&mut self, args: Args) -> tg::Result<()> {
+		let handle = self.handle().await?;
+		handle.delete_token(&args.id).await?;
+		Ok(())
+	}
+}
@@ -39,6 +39,7 @@ impl Cli {
 			lockfile: true,
 			path,
 			updates,
+			watch: false,
 		};
 		let stream = handle.checkin(arg).await?;
 		stream.map_ok(|_| ()).try_collect::<()>().await?;
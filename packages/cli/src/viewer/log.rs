@@ -30,7 +30,7 @@ pub struct Log<H> {
 	handle: H,
 
 	// The lines of text that will be displayed.
-	lines: Mutex<Vec<String>>,
+	lines: Mutex<Vec<scroll::Line>>,
 
 	// The maximum position of the log seen so far.
 	max_position: AtomicU64,
@@ -384,7 +384,12 @@ where
 		self.rect.lock().unwrap().replace(area);
 		let lines = self.lines.lock().unwrap();
 		for (y, line) in (0..area.height).zip(lines.iter()) {
-			buf.set_line(area.x, area.y + y, &tui::text::Line::raw(line), area.width);
+			let style = match line.stream {
+				Some(tg::process::log::Stream::Stderr) => Style::default().fg(Color::Red),
+				_ => Style::default(),
+			};
+			let line = tui::text::Line::styled(line.text.as_str(), style);
+			buf.set_line(area.x, area.y + y, &line, area.width);
 		}
 	}
 }
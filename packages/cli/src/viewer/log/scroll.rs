@@ -20,6 +20,16 @@ pub struct Scroll {
 	buffer: Vec<u8>,
 }
 
+/// A line of log text and the stream it was written to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Line {
+	/// The stream of the chunk containing the start of the line.
+	pub stream: Option<tg::process::log::Stream>,
+
+	/// The text of the line.
+	pub text: String,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum Error {
 	Append,
@@ -100,7 +110,7 @@ impl Scroll {
 		Ok(count)
 	}
 
-	pub fn read_lines(&mut self, chunks: &[Chunk]) -> tg::Result<Vec<String>, Error> {
+	pub fn read_lines(&mut self, chunks: &[Chunk]) -> tg::Result<Vec<Line>, Error> {
 		read_lines_inner(
 			&mut self.buffer,
 			self.start,
@@ -186,16 +196,24 @@ fn read_lines_inner(
 	max_width: usize,
 	num_lines: usize,
 	chunks: &[Chunk],
-) -> tg::Result<Vec<String>, Error> {
+) -> tg::Result<Vec<Line>, Error> {
 	let last = chunks.last().unwrap();
 	let last = last.position + last.bytes.len().to_u64().unwrap();
 	let mut lines = Vec::with_capacity(num_lines);
 	'outer: for _ in 0..num_lines {
+		let stream = chunks
+			.iter()
+			.find(|chunk| {
+				chunk.position <= position
+					&& chunk.position + chunk.bytes.len().to_u64().unwrap() > position
+			})
+			.and_then(|chunk| chunk.stream);
 		let mut line = String::with_capacity(max_width);
 		let mut width = 0;
 		loop {
 			if position == last {
-				lines.push(line.replace('\n', "").replace('\t', "  "));
+				let text = line.replace('\n', "").replace('\t', "  ");
+				lines.push(Line { stream, text });
 				break 'outer;
 			}
 			let (grapheme, next_position) = next_grapheme(buffer, position, true, chunks)?;
@@ -215,7 +233,8 @@ fn read_lines_inner(
 				break;
 			}
 		}
-		lines.push(line.replace('\n', "").replace('\t', "  "));
+		let text = line.replace('\n', "").replace('\t', "  ");
+		lines.push(Line { stream, text });
 	}
 	Ok(lines)
 }
@@ -487,6 +506,8 @@ mod tests {
 		let chunks = [tg::process::log::get::Chunk {
 			position: 0,
 			bytes: b"1 abcdef\n2 abcdef\n3 abcdef\n".to_vec().into(),
+			stream: None,
+			timestamp: None,
 		}];
 		let mut scroll = Scroll::new(Rect::new(0, 0, 20, 1), &chunks).unwrap();
 		let (init_start, init_end) = (scroll.start, scroll.end);
@@ -507,14 +528,20 @@ mod tests {
 			tg::process::log::get::Chunk {
 				position: 0,
 				bytes: b"a".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 1,
 				bytes: vec![0b1010_1010].into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 2,
 				bytes: b"b".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 		];
 		let mut buffer = Vec::new();
@@ -546,14 +573,20 @@ mod tests {
 			tg::process::log::get::Chunk {
 				position: 0,
 				bytes: "1——👍👌👉👈——\n".as_bytes().to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 30,
 				bytes: "2——👍👌👉👈——\n".as_bytes().to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 60,
 				bytes: "3——👍👌👉👈——\n".as_bytes().to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 		];
 		let mut scroll = Scroll {
@@ -564,6 +597,10 @@ mod tests {
 				+ chunks.last().unwrap().bytes.len().to_u64().unwrap(),
 		};
 		let lines = scroll.read_lines(&chunks).unwrap();
+		let lines = lines
+			.iter()
+			.map(|line| line.text.as_str())
+			.collect::<Vec<_>>();
 		assert_eq!(&lines, &["1——👍👌👉👈——", "2——👍👌👉👈——", "3——👍👌👉👈——"]);
 
 		// Tailing case.
@@ -575,14 +612,22 @@ mod tests {
 						.as_bytes()
 						.to_vec()
 						.into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 128,
 				bytes: "\"4——👍👌👉👈——\"\n".as_bytes().to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 		];
 		let mut scroll = Scroll::new(Rect::new(0, 0, 20, 10), &chunks).unwrap();
 		let lines = scroll.read_lines(&chunks).unwrap();
+		let lines = lines
+			.iter()
+			.map(|line| line.text.as_str())
+			.collect::<Vec<_>>();
 		assert_eq!(
 			&lines,
 			&[
@@ -601,11 +646,37 @@ mod tests {
 		let chunks = vec![tg::process::log::get::Chunk {
 			position: 0,
 			bytes: "😀😀".as_bytes().to_vec().into(),
+			stream: None,
+			timestamp: None,
 		}];
 		let mut scroll = Scroll::new(Rect::new(0, 0, 2, 4), &chunks).unwrap();
 		let lines = scroll.read_lines(&chunks).unwrap();
-		assert_eq!(&lines[0], "😀");
-		assert_eq!(&lines[1], "😀");
+		assert_eq!(lines[0].text, "😀");
+		assert_eq!(lines[1].text, "😀");
+	}
+
+	#[test]
+	fn streams() {
+		let chunks = vec![
+			tg::process::log::get::Chunk {
+				position: 0,
+				bytes: b"out\n".to_vec().into(),
+				stream: Some(tg::process::log::Stream::Stdout),
+				timestamp: None,
+			},
+			tg::process::log::get::Chunk {
+				position: 4,
+				bytes: b"err\n".to_vec().into(),
+				stream: Some(tg::process::log::Stream::Stderr),
+				timestamp: None,
+			},
+		];
+		let mut scroll = Scroll::new(Rect::new(0, 0, 20, 3), &chunks).unwrap();
+		let lines = scroll.read_lines(&chunks).unwrap();
+		assert_eq!(lines[0].text, "out");
+		assert_eq!(lines[0].stream, Some(tg::process::log::Stream::Stdout));
+		assert_eq!(lines[1].text, "err");
+		assert_eq!(lines[1].stream, Some(tg::process::log::Stream::Stderr));
 	}
 
 	#[test]
@@ -614,7 +685,12 @@ mod tests {
 		let mut chunks = Vec::new();
 		for n in 0..24 {
 			let bytes = format!("\"log line {n}\"\n").into();
-			let chunk = tg::process::log::get::Chunk { position, bytes };
+			let chunk = tg::process::log::get::Chunk {
+				bytes,
+				position,
+				stream: None,
+				timestamp: None,
+			};
 			position += chunk.bytes.len().to_u64().unwrap();
 			chunks.push(chunk);
 		}
@@ -632,7 +708,12 @@ mod tests {
 		let mut chunks = Vec::new();
 		for n in 0..8 {
 			let bytes = format!("\"log line {n}\"\n").into();
-			let chunk = tg::process::log::get::Chunk { position, bytes };
+			let chunk = tg::process::log::get::Chunk {
+				bytes,
+				position,
+				stream: None,
+				timestamp: None,
+			};
 			position += chunk.bytes.len().to_u64().unwrap();
 			chunks.push(chunk);
 		}
@@ -659,7 +740,12 @@ mod tests {
 		let mut chunks = Vec::new();
 		for n in 0..8 {
 			let bytes = format!("\"log line {n}\"\n").into();
-			let chunk = tg::process::log::get::Chunk { position, bytes };
+			let chunk = tg::process::log::get::Chunk {
+				bytes,
+				position,
+				stream: None,
+				timestamp: None,
+			};
 			position += chunk.bytes.len().to_u64().unwrap();
 			chunks.push(chunk);
 		}
@@ -697,22 +783,32 @@ mod tests {
 			tg::process::log::get::Chunk {
 				position: 114,
 				bytes: b"\"doing stuff 6...\"\n".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 133,
 				bytes: b"\"doing stuff 7...\"\n".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 152,
 				bytes: b"\"doing stuff 8...\"\n".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 171,
 				bytes: b"\"doing stuff 9...\"\n".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 			tg::process::log::get::Chunk {
 				position: 190,
 				bytes: b"\"doing stuff 10...\"\n".to_vec().into(),
+				stream: None,
+				timestamp: None,
 			},
 		];
 
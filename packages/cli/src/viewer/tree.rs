@@ -647,14 +647,26 @@ where
 			})
 			.unwrap();
 
-		// Get the output.
-		let output = handle
-			.get_process(process.id())
-			.await?
-			.data
-			.output
-			.map(tg::Value::try_from)
-			.transpose()?;
+		// Get the output and the usage.
+		let data = handle.get_process(process.id()).await?.data;
+		let output = data.output.map(tg::Value::try_from).transpose()?;
+		let usage = data.usage.map(|usage| {
+			[
+				("cpu_system", usage.cpu_system),
+				("cpu_user", usage.cpu_user),
+				("max_rss", usage.max_rss),
+				("read_bytes", usage.read_bytes),
+				("wall_time", usage.wall_time),
+				("written_bytes", usage.written_bytes),
+			]
+			.into_iter()
+			.filter_map(|(name, value)| {
+				#[allow(clippy::cast_precision_loss)]
+				let value = tg::Value::Number(value? as f64);
+				Some((name.to_owned(), value))
+			})
+			.collect::<tg::value::Map>()
+		});
 		if let Some(output) = output {
 			let handle = handle.clone();
 			let update = move |node: Rc<RefCell<Node>>| {
@@ -668,6 +680,19 @@ where
 			};
 			update_sender.send(Box::new(update)).unwrap();
 		}
+		if let Some(usage) = usage {
+			let handle = handle.clone();
+			let update = move |node: Rc<RefCell<Node>>| {
+				let usage = Self::create_node(
+					&handle,
+					&node,
+					Some("usage".into()),
+					Some(tg::Referent::with_item(Item::Value(tg::Value::Map(usage)))),
+				);
+				node.borrow_mut().children.push(usage);
+			};
+			update_sender.send(Box::new(update)).unwrap();
+		}
 
 		// Create the children stream.
 		let mut children = process
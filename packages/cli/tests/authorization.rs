@@ -0,0 +1,110 @@
+use std::sync::Arc;
+use tangram_cli::{
+	assert_failure, assert_success,
+	config::Config,
+	test::{Server, test},
+};
+use tangram_client as tg;
+use tangram_either::Either;
+
+const TG: &str = env!("CARGO_BIN_EXE_tangram");
+
+#[tokio::test]
+async fn scopes() {
+	test(TG, async move |context| {
+		let config = Config {
+			authorization: Some(Either::Left(true)),
+			remotes: Some(Vec::new()),
+			..Default::default()
+		};
+		let server = context.spawn_server_with_config(config).await.unwrap();
+
+		// Create a token with every scope directly in the database.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("token")
+			.arg("create")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let admin = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+
+		// Get a command that requires each scope.
+		let process = tg::process::Id::new().to_string();
+		let commands = [
+			("read_objects", vec!["tag", "list"]),
+			("write_objects", vec!["index"]),
+			(
+				"spawn_processes",
+				vec!["process", "cancel", &process, "token"],
+			),
+			("write_tags", vec!["tag", "delete", "foo"]),
+			("manage_remotes", vec!["remote", "list"]),
+			("manage_tokens", vec!["token", "list"]),
+			("administer", vec!["clean", "--dry-run"]),
+		];
+
+		for (scope, args) in &commands {
+			// Verify that the request is unauthorized without a token.
+			let output = server.tg().args(args).output().await.unwrap();
+			assert_failure!(output);
+			let stderr = String::from_utf8(output.stderr).unwrap();
+			assert!(stderr.contains("a token is required"), "{scope}: {stderr}");
+
+			// Verify that the request is forbidden with a token that has a different scope.
+			let other = if *scope == "read_objects" {
+				"write_tags"
+			} else {
+				"read_objects"
+			};
+			let token = create_token(&server, &admin, other).await;
+			let output = server
+				.tg()
+				.env("TANGRAM_TOKEN", &token)
+				.args(args)
+				.output()
+				.await
+				.unwrap();
+			assert_failure!(output);
+			let stderr = String::from_utf8(output.stderr).unwrap();
+			assert!(
+				stderr.contains("the token does not have the required scope"),
+				"{scope}: {stderr}"
+			);
+
+			// Verify that the request is authorized with a token that has the scope.
+			let token = create_token(&server, &admin, scope).await;
+			let output = server
+				.tg()
+				.env("TANGRAM_TOKEN", &token)
+				.args(args)
+				.output()
+				.await
+				.unwrap();
+			let stderr = String::from_utf8(output.stderr).unwrap();
+			assert!(
+				!stderr.contains("a token is required")
+					&& !stderr.contains("the token does not have the required scope"),
+				"{scope}: {stderr}"
+			);
+		}
+	})
+	.await;
+}
+
+async fn create_token(server: &Arc<Server>, admin: &str, scope: &str) -> String {
+	let output = server
+		.tg()
+		.env("TANGRAM_TOKEN", admin)
+		.arg("token")
+		.arg("create")
+		.arg("--scope")
+		.arg(scope)
+		.output()
+		.await
+		.unwrap();
+	assert_success!(output);
+	String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
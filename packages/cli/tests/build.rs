@@ -1,7 +1,13 @@
 use indoc::indoc;
 use insta::assert_snapshot;
 use num::ToPrimitive as _;
-use tangram_cli::{assert_failure, assert_success, test::test};
+use std::time::Duration;
+use tangram_cli::{
+	assert_failure, assert_success,
+	config::{Config, Watchdog},
+	test::test,
+};
+use tangram_either::Either;
 use tangram_temp::{self as temp, Temp};
 
 const TG: &str = env!("CARGO_BIN_EXE_tangram");
@@ -1152,6 +1158,82 @@ async fn test_signal_cacheable_processs() {
 	.await;
 }
 
+#[tokio::test]
+async fn timeout() {
+	test(TG, async move |context| {
+		let config = Config {
+			remotes: Some(Vec::new()),
+			watchdog: Some(Either::Right(Watchdog {
+				interval: Some(Duration::from_millis(100)),
+				timeout_grace_period: Some(Duration::from_secs(1)),
+				..Default::default()
+			})),
+			..Default::default()
+		};
+		let server = context.spawn_server_with_config(config).await.unwrap();
+		let artifact = temp::directory! {
+			"tangram.ts" => indoc!(r"
+				export default async () => {
+					await tg.sleep(60);
+					return 42;
+				};
+			")
+		};
+		let temp = Temp::new();
+		artifact.to_path(temp.as_ref()).await.unwrap();
+
+		// Build with a timeout that expires before the process finishes.
+		let output = server
+			.tg()
+			.arg("build")
+			.arg("--timeout")
+			.arg("1")
+			.arg(temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_failure!(output);
+		let stderr = String::from_utf8(output.stderr).unwrap();
+		assert!(
+			stderr.contains("the process exceeded its timeout"),
+			"{stderr}"
+		);
+	})
+	.await;
+}
+
+#[tokio::test]
+async fn limits_js() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+		let artifact = temp::directory! {
+			"tangram.ts" => indoc!(r"
+				export default () => 42;
+			")
+		};
+		let temp = Temp::new();
+		artifact.to_path(temp.as_ref()).await.unwrap();
+
+		// Build with a limit, which js processes do not support.
+		let output = server
+			.tg()
+			.arg("build")
+			.arg("--pids")
+			.arg("8")
+			.arg(temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_failure!(output);
+		let stderr = String::from_utf8(output.stderr).unwrap();
+		assert!(
+			stderr.contains("limits are not supported for js processes"),
+			"{stderr}"
+		);
+	})
+	.await;
+}
+
 #[tokio::test]
 async fn test_single_cancellation() {
 	test(TG, async move |context| {
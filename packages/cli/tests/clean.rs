@@ -263,6 +263,51 @@ async fn objects() {
 	.await;
 }
 
+#[tokio::test]
+async fn dry_run() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+		let directory = temp::directory! {
+			"tangram.ts" => indoc!(r#"
+				export let a = () => tg.file("a");
+			"#),
+		};
+
+		let artifact: temp::Artifact = directory.into();
+		let artifact_temp = Temp::new();
+		artifact.to_path(artifact_temp.as_ref()).await.unwrap();
+
+		let (_, a) = build(&server, artifact_temp.path(), "a").await;
+
+		// Clean with a dry run.
+		let output = server
+			.tg()
+			.arg("clean")
+			.arg("--dry-run")
+			.arg("--ids")
+			.arg("--pretty=false")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let output = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+		let objects = output["ids"]["objects"].as_array().unwrap();
+		assert!(objects.iter().any(|id| id.as_str() == Some(a.as_str())));
+
+		// Confirm the presence of the object.
+		let output = server
+			.tg()
+			.arg("object")
+			.arg("get")
+			.arg(a)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+	})
+	.await;
+}
+
 async fn build(server: &Server, path: &Path, name: &str) -> (String, String) {
 	let output = server
 		.tg()
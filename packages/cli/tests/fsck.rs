@@ -0,0 +1,169 @@
+use insta::assert_snapshot;
+use std::path::{Path, PathBuf};
+use tangram_cli::{
+	assert_failure, assert_success,
+	config::{Config, FsStore, Store},
+	test::test,
+};
+use tangram_temp::{self as temp, Temp};
+
+const TG: &str = env!("CARGO_BIN_EXE_tangram");
+
+#[tokio::test]
+async fn consistent() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Write the artifact.
+		let artifact: temp::Artifact = temp::directory! {
+			"hello.txt" => "hello, world!",
+			"link" => temp::symlink!("hello.txt"),
+		}
+		.into();
+		let temp = Temp::new();
+		artifact.to_path(temp.path()).await.unwrap();
+
+		// Check in the artifact.
+		let output = server
+			.tg()
+			.arg("checkin")
+			.arg(temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Index.
+		let output = server.tg().arg("index").output().await.unwrap();
+		assert_success!(output);
+
+		// Verify the store and the index.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("fsck")
+			.arg("--pretty=false")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = std::str::from_utf8(&output.stdout).unwrap();
+		assert_snapshot!(stdout, @"{}");
+	})
+	.await;
+}
+
+#[tokio::test]
+async fn corrupt_leaf() {
+	test(TG, async move |context| {
+		let config = Config {
+			remotes: Some(Vec::new()),
+			store: Some(Store::Fs(FsStore { path: None })),
+			..Default::default()
+		};
+		let server = context.spawn_server_with_config(config).await.unwrap();
+
+		// Write the artifact.
+		let artifact: temp::Artifact = temp::directory! {
+			"hello.txt" => "hello, world!",
+		}
+		.into();
+		let temp = Temp::new();
+		artifact.to_path(temp.path()).await.unwrap();
+
+		// Check in the artifact.
+		let output = server
+			.tg()
+			.arg("checkin")
+			.arg(temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Index.
+		let output = server.tg().arg("index").output().await.unwrap();
+		assert_success!(output);
+		let index = server.temp().path().join(".tangram/index");
+		assert_eq!(complete_count(&index), 3);
+
+		// Corrupt the blob.
+		let (blob, path) = find_blob(&server.temp().path().join(".tangram/objects")).await;
+		tokio::fs::write(&path, "corrupt").await.unwrap();
+
+		// Verify the store and the index.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("fsck")
+			.arg("--pretty=false")
+			.output()
+			.await
+			.unwrap();
+		assert_failure!(output);
+		let stdout: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+		assert_eq!(stdout, serde_json::json!({ "corrupt": [blob] }));
+
+		// Repair the store and the index.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("fsck")
+			.arg("--pretty=false")
+			.arg("--repair")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+		assert_eq!(
+			stdout,
+			serde_json::json!({ "corrupt": [blob], "repaired": true })
+		);
+
+		// Confirm that the blob was removed and that its ancestors are no longer complete.
+		assert!(!tokio::fs::try_exists(&path).await.unwrap());
+		assert_eq!(complete_count(&index), 0);
+
+		// Confirm that the file is reported as missing its child.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("fsck")
+			.arg("--pretty=false")
+			.output()
+			.await
+			.unwrap();
+		assert_failure!(output);
+		let stdout: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+		assert_eq!(stdout["missing_children"][0]["child"], blob);
+	})
+	.await;
+}
+
+fn complete_count(index: &Path) -> u64 {
+	let connection = rusqlite::Connection::open(index).unwrap();
+	connection
+		.query_row(
+			"select count(*) from objects where complete = 1",
+			[],
+			|row| row.get(0),
+		)
+		.unwrap()
+}
+
+async fn find_blob(objects: &Path) -> (String, PathBuf) {
+	let mut paths = vec![objects.to_owned()];
+	while let Some(path) = paths.pop() {
+		let mut entries = tokio::fs::read_dir(&path).await.unwrap();
+		while let Some(entry) = entries.next_entry().await.unwrap() {
+			let name = entry.file_name().into_string().unwrap();
+			if entry.file_type().await.unwrap().is_dir() {
+				paths.push(entry.path());
+			} else if name.starts_with("blb_") && !name.contains('.') {
+				return (name, entry.path());
+			}
+		}
+	}
+	panic!("failed to find the blob");
+}
@@ -0,0 +1,178 @@
+use indoc::indoc;
+use insta::assert_snapshot;
+use tangram_cli::{assert_success, test::test};
+use tangram_temp::{self as temp, Temp};
+
+const TG: &str = env!("CARGO_BIN_EXE_tangram");
+
+#[tokio::test]
+async fn stream() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Write the package.
+		let artifact: temp::Artifact = temp::directory! {
+			"tangram.ts" => indoc!(r#"
+				export default () => {
+					console.log("stdout 1");
+					console.error("stderr 1");
+					console.log("stdout 2");
+				};
+			"#),
+		}
+		.into();
+		let temp = Temp::new();
+		artifact.to_path(temp.path()).await.unwrap();
+
+		// Build the package.
+		let output = server
+			.tg()
+			.arg("build")
+			.arg("--detach")
+			.arg(temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let process = std::str::from_utf8(&output.stdout)
+			.unwrap()
+			.trim()
+			.to_owned();
+
+		// Wait for the process.
+		let output = server
+			.tg()
+			.arg("wait")
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Get the stdout log.
+		let output = server
+			.tg()
+			.arg("log")
+			.arg("--stream")
+			.arg("stdout")
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = std::str::from_utf8(&output.stdout).unwrap();
+		assert_snapshot!(stdout, @r"
+		stdout 1
+		stdout 2
+		");
+
+		// Get the stderr log.
+		let output = server
+			.tg()
+			.arg("log")
+			.arg("--stream")
+			.arg("stderr")
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = std::str::from_utf8(&output.stdout).unwrap();
+		assert_snapshot!(stdout, @r"
+		stderr 1
+		");
+	})
+	.await;
+}
+
+#[tokio::test]
+async fn since_and_until() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Write the package.
+		let artifact: temp::Artifact = temp::directory! {
+			"tangram.ts" => indoc!(r#"
+				export default async () => {
+					console.log("before");
+					await tg.sleep(1);
+					console.log("after");
+				};
+			"#),
+		}
+		.into();
+		let temp = Temp::new();
+		artifact.to_path(temp.path()).await.unwrap();
+
+		// Build the package.
+		let output = server
+			.tg()
+			.arg("build")
+			.arg("--detach")
+			.arg(temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let process = std::str::from_utf8(&output.stdout)
+			.unwrap()
+			.trim()
+			.to_owned();
+
+		// Wait for the process.
+		let output = server
+			.tg()
+			.arg("wait")
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Get the time that the second line was written.
+		let output = server
+			.tg()
+			.arg("log")
+			.arg("--timestamps")
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = std::str::from_utf8(&output.stdout).unwrap();
+		let timestamp = stdout
+			.lines()
+			.find_map(|line| line.strip_suffix(" after"))
+			.unwrap()
+			.to_owned();
+
+		// Get the log since the second line.
+		let output = server
+			.tg()
+			.arg("log")
+			.arg("--since")
+			.arg(&timestamp)
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = std::str::from_utf8(&output.stdout).unwrap();
+		assert_snapshot!(stdout, @"after");
+
+		// Get the log until the second line.
+		let output = server
+			.tg()
+			.arg("log")
+			.arg("--until")
+			.arg(&timestamp)
+			.arg(&process)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = std::str::from_utf8(&output.stdout).unwrap();
+		assert_snapshot!(stdout, @"before");
+	})
+	.await;
+}
@@ -0,0 +1,79 @@
+use insta::assert_snapshot;
+use tangram_cli::{assert_success, test::test};
+
+const TG: &str = env!("CARGO_BIN_EXE_tangram");
+
+#[tokio::test]
+async fn up_to_date() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Stop the server, because migrations can only be applied while it is not running.
+		server.stop_gracefully().await;
+
+		// Migrate.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("migrate")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stderr = std::str::from_utf8(&output.stderr).unwrap();
+		assert_snapshot!(stderr, @"info applied 0 database migrations and 0 index migrations");
+	})
+	.await;
+}
+
+#[tokio::test]
+async fn baseline() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Stop the server, because migrations can only be applied while it is not running.
+		server.stop_gracefully().await;
+
+		// Replace the database with one that has the baseline schema.
+		let path = server.temp().path().join(".tangram/database");
+		for suffix in ["", "-shm", "-wal"] {
+			let path = format!("{}{suffix}", path.display());
+			tokio::fs::remove_file(path).await.ok();
+		}
+		let connection = rusqlite::Connection::open(&path).unwrap();
+		connection
+			.execute_batch(include_str!("../../server/src/database/schema.sql"))
+			.unwrap();
+		connection.pragma_update(None, "user_version", 1).unwrap();
+		drop(connection);
+
+		// Migrate.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("migrate")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stderr = std::str::from_utf8(&output.stderr).unwrap();
+		assert_snapshot!(stderr, @"info applied 10 database migrations and 0 index migrations");
+
+		// Verify that the columns and tables added since the baseline exist.
+		let connection = rusqlite::Connection::open(&path).unwrap();
+		let statements = [
+			"select token from remotes;",
+			r#"select created_at, expires_at, hash, name, scopes, "user" from tokens;"#,
+			"select labels, limits, priority, timeout, usage from processes;",
+			"select process, position, length, stream, timestamp from process_log_entries;",
+			"select device, inode, size, mtime, ctime, mode, blob from checkin_files;",
+			"select updated_at from tags;",
+		];
+		for statement in statements {
+			connection
+				.prepare(statement)
+				.unwrap_or_else(|error| panic!("{statement}: {error}"));
+		}
+	})
+	.await;
+}
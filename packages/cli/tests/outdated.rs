@@ -0,0 +1,81 @@
+use indoc::indoc;
+use insta::assert_json_snapshot;
+use tangram_cli::{assert_success, test::test};
+use tangram_temp::{self as temp, Temp};
+
+const TG: &str = env!("CARGO_BIN_EXE_tangram");
+
+#[tokio::test]
+async fn outdated() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Tag the first version of the dependency.
+		let tag = async |tag: &str| {
+			let artifact: temp::Artifact = temp::directory! {
+				"tangram.ts" => format!(r#"export default () => "{tag}";"#),
+			}
+			.into();
+			let temp = Temp::new();
+			artifact.to_path(temp.path()).await.unwrap();
+			let output = server
+				.tg()
+				.arg("tag")
+				.arg(tag)
+				.arg(temp.path())
+				.output()
+				.await
+				.unwrap();
+			assert_success!(output);
+		};
+		tag("a/1.0.0").await;
+
+		// Check in a package that depends on the dependency.
+		let package: temp::Artifact = temp::directory! {
+			"tangram.ts" => indoc!(r#"
+				import a from "a/^1";
+				export default () => tg.run(a);
+			"#),
+		}
+		.into();
+		let package_temp = Temp::new();
+		package.to_path(package_temp.path()).await.unwrap();
+		let output = server
+			.tg()
+			.arg("checkin")
+			.arg(package_temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Tag newer versions of the dependency.
+		tag("a/1.1.0").await;
+		tag("a/2.0.0").await;
+
+		// Get the outdated dependencies.
+		let output = server
+			.tg()
+			.arg("outdated")
+			.arg("--locked")
+			.arg("--format")
+			.arg("json")
+			.arg(package_temp.path())
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let entries = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+		assert_json_snapshot!(entries, @r#"
+		[
+		  {
+		    "reference": "a/^1",
+		    "locked": "a/1.0.0",
+		    "compatible": "a/1.1.0",
+		    "latest": "a/2.0.0"
+		  }
+		]
+		"#);
+	})
+	.await;
+}
@@ -0,0 +1,170 @@
+use std::time::Duration;
+use tangram_cli::{assert_failure, assert_success, config::Config, test::test};
+use tangram_either::Either;
+
+const TG: &str = env!("CARGO_BIN_EXE_tangram");
+
+#[tokio::test]
+async fn create_list_and_revoke() {
+	test(TG, async move |context| {
+		let server = context.spawn_server().await.unwrap();
+
+		// Create a token.
+		let output = server
+			.tg()
+			.arg("token")
+			.arg("create")
+			.arg("--name")
+			.arg("ci")
+			.arg("--scope")
+			.arg("read_objects")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let token = String::from_utf8(output.stdout).unwrap();
+		assert!(token.trim().starts_with("tg_"));
+
+		// List the tokens.
+		let output = server.tg().arg("token").arg("list").output().await.unwrap();
+		assert_success!(output);
+		let stdout = String::from_utf8(output.stdout).unwrap();
+		let lines = stdout.lines().collect::<Vec<_>>();
+		assert_eq!(lines.len(), 1);
+		let (id, rest) = lines[0].split_once(' ').unwrap();
+		assert_eq!(rest, "ci read_objects");
+
+		// Revoke the token.
+		let output = server
+			.tg()
+			.arg("token")
+			.arg("revoke")
+			.arg(id)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Verify that the token was revoked.
+		let output = server.tg().arg("token").arg("list").output().await.unwrap();
+		assert_success!(output);
+		assert!(output.stdout.is_empty());
+	})
+	.await;
+}
+
+#[tokio::test]
+async fn revoked_and_expired_tokens_are_rejected() {
+	test(TG, async move |context| {
+		let config = Config {
+			authorization: Some(Either::Left(true)),
+			remotes: Some(Vec::new()),
+			..Default::default()
+		};
+		let server = context.spawn_server_with_config(config).await.unwrap();
+
+		// Create the first token directly in the database.
+		let output = server
+			.tg()
+			.arg("server")
+			.arg("token")
+			.arg("create")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let admin = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+
+		// Create a token and verify that it is accepted.
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &admin)
+			.arg("token")
+			.arg("create")
+			.arg("--scope")
+			.arg("read_objects")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let token = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &token)
+			.arg("tag")
+			.arg("list")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+
+		// Revoke the token and verify that it is rejected.
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &admin)
+			.arg("token")
+			.arg("list")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let stdout = String::from_utf8(output.stdout).unwrap();
+		let id = stdout
+			.lines()
+			.find(|line| line.ends_with(" read_objects"))
+			.and_then(|line| line.split_once(' '))
+			.unwrap()
+			.0
+			.to_owned();
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &admin)
+			.arg("token")
+			.arg("revoke")
+			.arg(id)
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &token)
+			.arg("tag")
+			.arg("list")
+			.output()
+			.await
+			.unwrap();
+		assert_failure!(output);
+		let stderr = String::from_utf8(output.stderr).unwrap();
+		assert!(stderr.contains("the token is invalid"));
+
+		// Create a token that expires, wait for it to expire, and verify that it is rejected.
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &admin)
+			.arg("token")
+			.arg("create")
+			.arg("--expires-in")
+			.arg("1")
+			.arg("--scope")
+			.arg("read_objects")
+			.output()
+			.await
+			.unwrap();
+		assert_success!(output);
+		let token = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		let output = server
+			.tg()
+			.env("TANGRAM_TOKEN", &token)
+			.arg("tag")
+			.arg("list")
+			.output()
+			.await
+			.unwrap();
+		assert_failure!(output);
+		let stderr = String::from_utf8(output.stderr).unwrap();
+		assert!(stderr.contains("the token is invalid"));
+	})
+	.await;
+}
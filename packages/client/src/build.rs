@@ -10,14 +10,18 @@ pub struct Arg {
 	pub env: tg::value::Map,
 	pub executable: Option<tg::command::Executable>,
 	pub host: Option<String>,
+	pub labels: Vec<String>,
+	pub limits: Option<tg::process::Limits>,
 	pub mounts: Vec<tg::command::Mount>,
 	pub network: bool,
 	pub parent: Option<tg::process::Id>,
 	pub path: Option<tg::PathBuf>,
+	pub priority: Option<i64>,
 	pub remote: Option<String>,
 	pub retry: bool,
 	pub stdin: Option<tg::Blob>,
 	pub tag: Option<tg::Tag>,
+	pub timeout: Option<u64>,
 	pub user: Option<String>,
 }
 
@@ -49,16 +53,20 @@ where
 		cached: arg.cached,
 		checksum: arg.checksum,
 		command: Some(command_id),
+		labels: arg.labels,
+		limits: arg.limits,
 		mounts: vec![],
 		network: arg.network,
 		parent: arg.parent,
 		path: arg.path,
+		priority: arg.priority,
 		remote: arg.remote,
 		retry: arg.retry,
 		stderr: None,
 		stdin: None,
 		stdout: None,
 		tag: arg.tag,
+		timeout: arg.timeout,
 	};
 	let process = tg::Process::spawn(handle, arg).await?;
 	let output = process.output(handle).await?;
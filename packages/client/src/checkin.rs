@@ -28,6 +28,9 @@ pub struct Arg {
 
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub updates: Vec<tg::tag::Pattern>,
+
+	#[serde(default, skip_serializing_if = "is_false")]
+	pub watch: bool,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
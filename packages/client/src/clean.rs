@@ -2,17 +2,69 @@ use crate as tg;
 use futures::{Stream, TryStreamExt as _, future};
 use tangram_http::{request::builder::Ext as _, response::Ext as _};
 
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Arg {
+	/// Report what would be removed without removing anything.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub dry_run: bool,
+
+	/// Include the ids of the removed items in the output.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub ids: bool,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Output {
+	pub cache_entries: u64,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ids: Option<Ids>,
+
+	pub objects: u64,
+
+	pub pipes: u64,
+
+	pub processes: u64,
+
+	pub ptys: u64,
+
+	/// The total size in bytes of the removed objects.
+	pub size: u64,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Ids {
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub cache_entries: Vec<tg::artifact::Id>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub objects: Vec<tg::object::Id>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub pipes: Vec<tg::pipe::Id>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub processes: Vec<tg::process::Id>,
+
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub ptys: Vec<tg::pty::Id>,
+}
+
 impl tg::Client {
 	pub async fn clean(
 		&self,
-	) -> tg::Result<impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static> {
+		arg: tg::clean::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
+	> {
 		let method = http::Method::POST;
 		let uri = "/clean";
 		let request = http::request::Builder::default()
 			.method(method)
 			.uri(uri)
 			.header(http::header::ACCEPT, mime::TEXT_EVENT_STREAM.to_string())
-			.empty()
+			.json(arg)
+			.map_err(|source| tg::error!(!source, "failed to serialize the arg"))?
 			.unwrap();
 		let response = self.send(request).await?;
 		if !response.status().is_success() {
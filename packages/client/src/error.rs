@@ -34,6 +34,15 @@ pub struct Error {
 pub enum Code {
 	#[display("cancellation")]
 	Cancellation,
+
+	#[display("forbidden")]
+	Forbidden,
+
+	#[display("timeout")]
+	Timeout,
+
+	#[display("unauthorized")]
+	Unauthorized,
 }
 
 /// An error location.
@@ -0,0 +1,106 @@
+use crate as tg;
+use futures::{Stream, TryStreamExt as _, future};
+use tangram_http::{request::builder::Ext as _, response::Ext as _};
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Arg {
+	/// Repair the discrepancies that are found.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub repair: bool,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Output {
+	/// Objects whose stored bytes do not match their id.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub corrupt: Vec<tg::object::Id>,
+
+	/// Objects in the index that are missing from the store.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub missing: Vec<tg::object::Id>,
+
+	/// Children referenced by objects in the index that are missing from the store.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub missing_children: Vec<MissingChild>,
+
+	/// Objects whose reference count in the index is incorrect.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub reference_counts: Vec<ReferenceCount>,
+
+	/// Whether the discrepancies were repaired.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub repaired: bool,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct MissingChild {
+	pub child: tg::object::Id,
+	pub object: tg::object::Id,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct ReferenceCount {
+	pub actual: u64,
+	pub expected: u64,
+	pub id: tg::object::Id,
+}
+
+impl Output {
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.corrupt.is_empty()
+			&& self.missing.is_empty()
+			&& self.missing_children.is_empty()
+			&& self.reference_counts.is_empty()
+	}
+}
+
+impl tg::Client {
+	pub async fn fsck(
+		&self,
+		arg: tg::fsck::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+	> {
+		let method = http::Method::POST;
+		let uri = "/fsck";
+		let request = http::request::Builder::default()
+			.method(method)
+			.uri(uri)
+			.header(http::header::ACCEPT, mime::TEXT_EVENT_STREAM.to_string())
+			.json(arg)
+			.map_err(|source| tg::error!(!source, "failed to serialize the arg"))?
+			.unwrap();
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			let error = response.json().await?;
+			return Err(error);
+		}
+		let content_type = response
+			.parse_header::<mime::Mime, _>(http::header::CONTENT_TYPE)
+			.transpose()?;
+		if !matches!(
+			content_type
+				.as_ref()
+				.map(|content_type| (content_type.type_(), content_type.subtype())),
+			Some((mime::TEXT, mime::EVENT_STREAM)),
+		) {
+			return Err(tg::error!(?content_type, "invalid content type"));
+		}
+		let stream = response
+			.sse()
+			.map_err(|source| tg::error!(!source, "failed to read an event"))
+			.and_then(|event| {
+				future::ready(
+					if event.event.as_deref().is_some_and(|event| event == "error") {
+						match event.try_into() {
+							Ok(error) | Err(error) => Err(error),
+						}
+					} else {
+						event.try_into()
+					},
+				)
+			});
+		Ok(stream)
+	}
+}
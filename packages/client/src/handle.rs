@@ -9,7 +9,7 @@ mod ext;
 pub use self::ext::Ext;
 
 pub trait Handle:
-	Object + Process + Pipe + Pty + Remote + Tag + User + Clone + Unpin + Send + Sync + 'static
+	Object + Process + Pipe + Pty + Remote + Tag + Token + User + Clone + Unpin + Send + Sync + 'static
 {
 	fn cache(
 		&self,
@@ -45,9 +45,10 @@ pub trait Handle:
 
 	fn clean(
 		&self,
+		arg: tg::clean::Arg,
 	) -> impl Future<
 		Output = tg::Result<
-			impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static,
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
 		>,
 	> + Send;
 
@@ -66,6 +67,15 @@ pub trait Handle:
 
 	fn format(&self, arg: tg::format::Arg) -> impl Future<Output = tg::Result<()>> + Send;
 
+	fn fsck(
+		&self,
+		arg: tg::fsck::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+		>,
+	> + Send;
+
 	fn health(&self) -> impl Future<Output = tg::Result<tg::Health>> + Send;
 
 	fn import(
@@ -84,12 +94,25 @@ pub trait Handle:
 		>,
 	> + Send;
 
+	fn rebuild_index(
+		&self,
+		arg: tg::index::rebuild::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>>
+			+ Send
+			+ 'static,
+		>,
+	> + Send;
+
 	fn lsp(
 		&self,
 		input: impl AsyncBufRead + Send + Unpin + 'static,
 		output: impl AsyncWrite + Send + Unpin + 'static,
 	) -> impl Future<Output = tg::Result<()>> + Send;
 
+	fn metrics(&self) -> impl Future<Output = tg::Result<String>> + Send;
+
 	fn pull(
 		&self,
 		arg: tg::pull::Arg,
@@ -385,6 +408,20 @@ pub trait Tag {
 	fn delete_tag(&self, tag: &tg::Tag) -> impl Future<Output = tg::Result<()>> + Send;
 }
 
+pub trait Token {
+	fn create_token(
+		&self,
+		arg: tg::token::create::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::create::Output>> + Send;
+
+	fn list_tokens(
+		&self,
+		arg: tg::token::list::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::list::Output>> + Send;
+
+	fn delete_token(&self, id: &tg::token::Id) -> impl Future<Output = tg::Result<()>> + Send;
+}
+
 pub trait User {
 	fn get_user(&self, token: &str) -> impl Future<Output = tg::Result<Option<tg::User>>> + Send;
 }
@@ -81,18 +81,19 @@ where
 
 	fn clean(
 		&self,
+		arg: tg::clean::Arg,
 	) -> impl Future<
 		Output = tg::Result<
-			impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static,
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
 		>,
 	> + Send {
 		match self {
 			Either::Left(s) => s
-				.clean()
+				.clean(arg)
 				.map(|result| result.map(futures::StreamExt::left_stream))
 				.left_future(),
 			Either::Right(s) => s
-				.clean()
+				.clean(arg)
 				.map(|result| result.map(futures::StreamExt::right_stream))
 				.right_future(),
 		}
@@ -134,6 +135,26 @@ where
 		}
 	}
 
+	fn fsck(
+		&self,
+		arg: tg::fsck::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+		>,
+	> + Send {
+		match self {
+			Either::Left(s) => s
+				.fsck(arg)
+				.map(|result| result.map(futures::StreamExt::left_stream))
+				.left_future(),
+			Either::Right(s) => s
+				.fsck(arg)
+				.map(|result| result.map(futures::StreamExt::right_stream))
+				.right_future(),
+		}
+	}
+
 	fn health(&self) -> impl Future<Output = tg::Result<tg::Health>> {
 		match self {
 			Either::Left(s) => s.health().left_future(),
@@ -179,6 +200,28 @@ where
 		}
 	}
 
+	fn rebuild_index(
+		&self,
+		arg: tg::index::rebuild::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>>
+			+ Send
+			+ 'static,
+		>,
+	> + Send {
+		match self {
+			Either::Left(s) => s
+				.rebuild_index(arg)
+				.map(|result| result.map(futures::StreamExt::left_stream))
+				.left_future(),
+			Either::Right(s) => s
+				.rebuild_index(arg)
+				.map(|result| result.map(futures::StreamExt::right_stream))
+				.right_future(),
+		}
+	}
+
 	fn lsp(
 		&self,
 		input: impl AsyncBufRead + Send + Unpin + 'static,
@@ -190,6 +233,13 @@ where
 		}
 	}
 
+	fn metrics(&self) -> impl Future<Output = tg::Result<String>> {
+		match self {
+			Either::Left(s) => s.metrics().left_future(),
+			Either::Right(s) => s.metrics().right_future(),
+		}
+	}
+
 	fn pull(
 		&self,
 		arg: tg::pull::Arg,
@@ -788,6 +838,39 @@ where
 	}
 }
 
+impl<L, R> tg::handle::Token for Either<L, R>
+where
+	L: tg::handle::Token,
+	R: tg::handle::Token,
+{
+	fn create_token(
+		&self,
+		arg: tg::token::create::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::create::Output>> {
+		match self {
+			Either::Left(s) => s.create_token(arg).left_future(),
+			Either::Right(s) => s.create_token(arg).right_future(),
+		}
+	}
+
+	fn list_tokens(
+		&self,
+		arg: tg::token::list::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::list::Output>> {
+		match self {
+			Either::Left(s) => s.list_tokens(arg).left_future(),
+			Either::Right(s) => s.list_tokens(arg).right_future(),
+		}
+	}
+
+	fn delete_token(&self, id: &tg::token::Id) -> impl Future<Output = tg::Result<()>> {
+		match self {
+			Either::Left(s) => s.delete_token(id).left_future(),
+			Either::Right(s) => s.delete_token(id).right_future(),
+		}
+	}
+}
+
 impl<L, R> tg::handle::User for Either<L, R>
 where
 	L: tg::handle::User,
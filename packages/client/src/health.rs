@@ -1,4 +1,5 @@
 use crate as tg;
+use std::collections::BTreeMap;
 use tangram_http::{request::builder::Ext as _, response::Ext as _};
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -9,11 +10,77 @@ pub struct Health {
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub diagnostics: Vec<tg::Diagnostic>,
 
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub index: Option<Index>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub processes: Option<Processes>,
 
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub remotes: BTreeMap<String, Remote>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub runner: Option<Runner>,
+
+	#[serde(default)]
+	pub status: Status,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub store: Option<Store>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub version: Option<String>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub vfs: Option<Vfs>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+	#[default]
+	Healthy,
+	Degraded,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Index {
+	/// The number of messages in the index stream that have not been handled.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub backlog: Option<u64>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Remote {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+
+	pub reachable: bool,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Runner {
+	pub available_permits: u64,
+
+	pub concurrency: u64,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Store {
+	pub backend: String,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+
+	pub reachable: bool,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Vfs {
+	pub mounted: bool,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -33,6 +100,9 @@ pub struct Processes {
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub struct Database {
 	pub available_connections: u64,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
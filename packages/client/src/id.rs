@@ -31,6 +31,7 @@ pub enum Kind {
 	Pty,
 	User,
 	Request,
+	Token,
 }
 
 #[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -100,6 +101,7 @@ impl Id {
 			Kind::Pty => 8,
 			Kind::User => 9,
 			Kind::Request => 10,
+			Kind::Token => 11,
 		};
 		writer
 			.write_u8(kind)
@@ -152,6 +154,7 @@ impl Id {
 			8 => Kind::Pty,
 			9 => Kind::User,
 			10 => Kind::Request,
+			11 => Kind::Token,
 			_ => return Err(tg::error!(%kind, "invalid kind")),
 		};
 
@@ -278,6 +281,7 @@ impl std::fmt::Display for Kind {
 			Self::Pty => "pty",
 			Self::User => "usr",
 			Self::Request => "req",
+			Self::Token => "tok",
 		};
 		write!(f, "{kind}")?;
 		Ok(())
@@ -300,6 +304,7 @@ impl std::str::FromStr for Kind {
 			"pty" => Self::Pty,
 			"usr" | "user" => Self::User,
 			"req" | "request" => Self::Request,
+			"tok" | "token" => Self::Token,
 			_ => {
 				return Err(tg::error!(%s, "invalid kind"));
 			},
@@ -2,6 +2,8 @@ use crate as tg;
 use futures::{Stream, TryStreamExt as _, future};
 use tangram_http::{request::builder::Ext as _, response::Ext as _};
 
+pub mod rebuild;
+
 impl tg::Client {
 	pub async fn index(
 		&self,
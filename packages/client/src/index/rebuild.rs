@@ -0,0 +1,75 @@
+use crate as tg;
+use futures::{Stream, TryStreamExt as _, future};
+use tangram_http::{request::builder::Ext as _, response::Ext as _};
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Arg {
+	/// Remove every row from the index before publishing, so that rows which are incorrect are replaced rather than updated.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub truncate: bool,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Output {
+	/// The number of cache entries that were published.
+	pub cache_entries: u64,
+
+	/// The number of objects that were published.
+	pub objects: u64,
+
+	/// The number of processes that were published.
+	pub processes: u64,
+
+	/// The number of tags that were published.
+	pub tags: u64,
+}
+
+impl tg::Client {
+	pub async fn rebuild_index(
+		&self,
+		arg: tg::index::rebuild::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>> + Send + 'static,
+	> {
+		let method = http::Method::POST;
+		let uri = "/index/rebuild";
+		let request = http::request::Builder::default()
+			.method(method)
+			.uri(uri)
+			.header(http::header::ACCEPT, mime::TEXT_EVENT_STREAM.to_string())
+			.json(arg)
+			.map_err(|source| tg::error!(!source, "failed to serialize the arg"))?
+			.unwrap();
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			let error = response.json().await?;
+			return Err(error);
+		}
+		let content_type = response
+			.parse_header::<mime::Mime, _>(http::header::CONTENT_TYPE)
+			.transpose()?;
+		if !matches!(
+			content_type
+				.as_ref()
+				.map(|content_type| (content_type.type_(), content_type.subtype())),
+			Some((mime::TEXT, mime::EVENT_STREAM)),
+		) {
+			return Err(tg::error!(?content_type, "invalid content type"));
+		}
+		let stream = response
+			.sse()
+			.map_err(|source| tg::error!(!source, "failed to read an event"))
+			.and_then(|event| {
+				future::ready(
+					if event.event.as_deref().is_some_and(|event| event == "error") {
+						match event.try_into() {
+							Ok(error) | Err(error) => Err(error),
+						}
+					} else {
+						event.try_into()
+					},
+				)
+			});
+		Ok(stream)
+	}
+}
@@ -72,6 +72,7 @@ pub mod error;
 pub mod export;
 pub mod file;
 pub mod format;
+pub mod fsck;
 pub mod get;
 pub mod graph;
 pub mod handle;
@@ -81,6 +82,7 @@ pub mod import;
 pub mod index;
 pub mod location;
 pub mod lockfile;
+pub mod metrics;
 pub mod module;
 pub mod mutation;
 pub mod object;
@@ -100,6 +102,7 @@ pub mod run;
 pub mod symlink;
 pub mod tag;
 pub mod template;
+pub mod token;
 pub mod user;
 pub mod util;
 pub mod value;
@@ -107,7 +110,7 @@ pub mod value;
 pub mod prelude {
 	pub use super::handle::{
 		Ext as _, Handle as _, Object as _, Pipe as _, Process as _, Pty as _, Remote as _,
-		Tag as _, User as _,
+		Tag as _, Token as _, User as _,
 	};
 }
 
@@ -126,7 +129,7 @@ type Service = BoxCloneSyncService<http::Request<Body>, http::Response<Body>, tg
 
 impl Client {
 	#[must_use]
-	pub fn new(url: Url, version: Option<String>) -> Self {
+	pub fn new(url: Url, version: Option<String>, token: Option<String>) -> Self {
 		let version = version.unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_owned());
 		let sender = Arc::new(tokio::sync::Mutex::<
 			Option<hyper::client::conn::http2::SendRequest<Body>>,
@@ -169,6 +172,12 @@ impl Client {
 				http::HeaderName::from_str("x-tg-version").unwrap(),
 				http::HeaderValue::from_str(&version).unwrap(),
 			)
+			.option_layer(token.map(|token| {
+				tower_http::set_header::SetRequestHeaderLayer::if_not_present(
+					http::header::AUTHORIZATION,
+					http::HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+				)
+			}))
 			.layer(
 				tangram_http::layer::compression::RequestCompressionLayer::new(|parts, _| {
 					let has_content_length =
@@ -215,7 +224,8 @@ impl Client {
 					"failed to parse a URL from the TANGRAM_URL environment variable"
 				)
 			})?;
-		Ok(Self::new(url, None))
+		let token = std::env::var("TANGRAM_TOKEN").ok();
+		Ok(Self::new(url, None, token))
 	}
 
 	#[must_use]
@@ -691,12 +701,13 @@ impl tg::Handle for Client {
 
 	fn clean(
 		&self,
+		arg: tg::clean::Arg,
 	) -> impl Future<
 		Output = tg::Result<
-			impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static,
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
 		>,
 	> + Send {
-		self.clean()
+		self.clean(arg)
 	}
 
 	fn document(
@@ -720,6 +731,17 @@ impl tg::Handle for Client {
 		self.format(arg)
 	}
 
+	fn fsck(
+		&self,
+		arg: tg::fsck::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+		>,
+	> + Send {
+		self.fsck(arg)
+	}
+
 	fn health(&self) -> impl Future<Output = tg::Result<tg::Health>> {
 		self.health()
 	}
@@ -744,6 +766,19 @@ impl tg::Handle for Client {
 		self.index()
 	}
 
+	fn rebuild_index(
+		&self,
+		arg: tg::index::rebuild::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>>
+			+ Send
+			+ 'static,
+		>,
+	> + Send {
+		self.rebuild_index(arg)
+	}
+
 	fn lsp(
 		&self,
 		input: impl AsyncBufRead + Send + Unpin + 'static,
@@ -752,6 +787,10 @@ impl tg::Handle for Client {
 		self.lsp(input, output)
 	}
 
+	fn metrics(&self) -> impl Future<Output = tg::Result<String>> {
+		self.metrics()
+	}
+
 	fn pull(
 		&self,
 		arg: tg::pull::Arg,
@@ -1131,6 +1170,26 @@ impl tg::handle::Tag for Client {
 	}
 }
 
+impl tg::handle::Token for Client {
+	fn create_token(
+		&self,
+		arg: tg::token::create::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::create::Output>> {
+		self.create_token(arg)
+	}
+
+	fn list_tokens(
+		&self,
+		arg: tg::token::list::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::list::Output>> {
+		self.list_tokens(arg)
+	}
+
+	fn delete_token(&self, id: &tg::token::Id) -> impl Future<Output = tg::Result<()>> {
+		self.delete_token(id)
+	}
+}
+
 impl tg::handle::User for Client {
 	fn get_user(&self, token: &str) -> impl Future<Output = tg::Result<Option<tg::User>>> {
 		self.get_user(token)
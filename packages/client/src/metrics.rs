@@ -0,0 +1,21 @@
+use crate as tg;
+use tangram_http::{request::builder::Ext as _, response::Ext as _};
+
+impl tg::Client {
+	pub async fn metrics(&self) -> tg::Result<String> {
+		let method = http::Method::GET;
+		let uri = "/metrics";
+		let request = http::request::Builder::default()
+			.method(method)
+			.uri(uri)
+			.empty()
+			.unwrap();
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			let error = response.json().await?;
+			return Err(error);
+		}
+		let output = response.text().await?;
+		Ok(output)
+	}
+}
@@ -5,8 +5,8 @@ use std::{
 };
 
 pub use self::{
-	data::Data, id::Id, metadata::Metadata, mount::Mount, signal::Signal, state::State,
-	status::Status, stdio::Stdio, wait::Wait,
+	data::Data, id::Id, limits::Limits, metadata::Metadata, mount::Mount, signal::Signal,
+	state::State, status::Status, stdio::Stdio, usage::Usage, wait::Wait,
 };
 
 pub mod cancel;
@@ -17,6 +17,7 @@ pub mod finish;
 pub mod get;
 pub mod heartbeat;
 pub mod id;
+pub mod limits;
 pub mod log;
 pub mod metadata;
 pub mod mount;
@@ -28,6 +29,7 @@ pub mod state;
 pub mod status;
 pub mod stdio;
 pub mod touch;
+pub mod usage;
 pub mod wait;
 
 static CURRENT: Mutex<Option<tg::Process>> = Mutex::new(None);
@@ -137,6 +139,30 @@ impl Process {
 		Ok(self.load(handle).await?.map(|state| &state.command))
 	}
 
+	pub async fn labels<H>(&self, handle: &H) -> tg::Result<impl Deref<Target = Vec<String>>>
+	where
+		H: tg::Handle,
+	{
+		Ok(self.load(handle).await?.map(|state| &state.labels))
+	}
+
+	pub async fn limits<H>(
+		&self,
+		handle: &H,
+	) -> tg::Result<impl Deref<Target = Option<tg::process::Limits>> + use<H>>
+	where
+		H: tg::Handle,
+	{
+		Ok(self.load(handle).await?.map(|state| &state.limits))
+	}
+
+	pub async fn priority<H>(&self, handle: &H) -> tg::Result<impl Deref<Target = Option<i64>>>
+	where
+		H: tg::Handle,
+	{
+		Ok(self.load(handle).await?.map(|state| &state.priority))
+	}
+
 	pub async fn retry<H>(&self, handle: &H) -> tg::Result<impl Deref<Target = bool>>
 	where
 		H: tg::Handle,
@@ -144,6 +170,13 @@ impl Process {
 		Ok(self.load(handle).await?.map(|state| &state.retry))
 	}
 
+	pub async fn timeout<H>(&self, handle: &H) -> tg::Result<impl Deref<Target = Option<u64>>>
+	where
+		H: tg::Handle,
+	{
+		Ok(self.load(handle).await?.map(|state| &state.timeout))
+	}
+
 	pub async fn spawn<H>(handle: &H, arg: tg::process::spawn::Arg) -> tg::Result<tg::Process>
 	where
 		H: tg::Handle,
@@ -40,6 +40,12 @@ pub struct Data {
 
 	pub host: String,
 
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub labels: Vec<String>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub limits: Option<tg::process::Limits>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub log: Option<tg::blob::Id>,
 
@@ -56,6 +62,9 @@ pub struct Data {
 	)]
 	pub output: Option<tg::value::Data>,
 
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub priority: Option<i64>,
+
 	#[serde(default, skip_serializing_if = "is_false")]
 	pub retry: bool,
 
@@ -72,6 +81,12 @@ pub struct Data {
 
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub stdout: Option<tg::process::Stdio>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timeout: Option<u64>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub usage: Option<tg::process::Usage>,
 }
 
 impl Data {
@@ -4,7 +4,15 @@ use tangram_futures::stream::TryExt as _;
 use tangram_http::{request::builder::Ext as _, response::Ext as _};
 
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
-pub struct Arg {}
+pub struct Arg {
+	/// The hosts the runner can run processes for. If this is not set, then processes for any host may be dequeued.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub hosts: Option<Vec<String>>,
+
+	/// The runner's labels. Only processes whose labels are all present will be dequeued.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub labels: Vec<String>,
+}
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub struct Output {
@@ -19,6 +19,9 @@ pub struct Arg {
 
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub remote: Option<String>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub usage: Option<tg::process::Usage>,
 }
 
 impl tg::Process {
@@ -0,0 +1,28 @@
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Limits {
+	/// The CPU quota in millicores, where 1000 is one core.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cpu: Option<u64>,
+
+	/// The maximum memory in bytes.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub memory: Option<u64>,
+
+	/// The maximum number of open files.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub open_files: Option<u64>,
+
+	/// The maximum number of processes and threads.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub pids: Option<u64>,
+}
+
+impl Limits {
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.cpu.is_none()
+			&& self.memory.is_none()
+			&& self.open_files.is_none()
+			&& self.pids.is_none()
+	}
+}
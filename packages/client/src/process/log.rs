@@ -4,7 +4,9 @@ use std::{fmt, str::FromStr};
 pub mod get;
 pub mod post;
 
-#[derive(Clone, Copy, Debug, serde_with::DeserializeFromStr, serde_with::SerializeDisplay)]
+#[derive(
+	Clone, Copy, Debug, Eq, PartialEq, serde_with::DeserializeFromStr, serde_with::SerializeDisplay,
+)]
 pub enum Stream {
 	Stderr,
 	Stdout,
@@ -21,8 +21,19 @@ pub struct Arg {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub remote: Option<String>,
 
+	/// Only get the bytes written at or after this time, in nanoseconds since the Unix epoch.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub since: Option<i64>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub size: Option<u64>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub stream: Option<tg::process::log::Stream>,
+
+	/// Only get the bytes written before this time, in nanoseconds since the Unix epoch.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub until: Option<i64>,
 }
 
 #[derive(Clone, Debug)]
@@ -36,7 +47,15 @@ pub enum Event {
 pub struct Chunk {
 	#[serde_as(as = "BytesBase64")]
 	pub bytes: Bytes,
+
 	pub position: u64,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub stream: Option<tg::process::log::Stream>,
+
+	/// The time the bytes were written, in nanoseconds since the Unix epoch.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timestamp: Option<i64>,
 }
 
 impl tg::Process {
@@ -12,6 +12,12 @@ pub struct Arg {
 
 	pub command: Option<tg::command::Id>,
 
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub labels: Vec<String>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub limits: Option<tg::process::Limits>,
+
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub mounts: Vec<tg::process::data::Mount>,
 
@@ -24,6 +30,9 @@ pub struct Arg {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub path: Option<PathBuf>,
 
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub priority: Option<i64>,
+
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub remote: Option<String>,
 
@@ -41,6 +50,9 @@ pub struct Arg {
 
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub tag: Option<tg::Tag>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timeout: Option<u64>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
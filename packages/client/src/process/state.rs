@@ -13,16 +13,21 @@ pub struct State {
 	pub exit: Option<u8>,
 	pub expected_checksum: Option<tg::Checksum>,
 	pub finished_at: Option<i64>,
+	pub labels: Vec<String>,
+	pub limits: Option<tg::process::Limits>,
 	pub log: Option<tg::Blob>,
 	pub mounts: Vec<tg::process::Mount>,
 	pub network: bool,
 	pub output: Option<tg::Value>,
+	pub priority: Option<i64>,
 	pub retry: bool,
 	pub started_at: Option<i64>,
 	pub status: tg::process::Status,
 	pub stderr: Option<tg::process::Stdio>,
 	pub stdin: Option<tg::process::Stdio>,
 	pub stdout: Option<tg::process::Stdio>,
+	pub timeout: Option<u64>,
+	pub usage: Option<tg::process::Usage>,
 }
 
 impl TryFrom<tg::process::Data> for tg::process::State {
@@ -45,16 +50,21 @@ impl TryFrom<tg::process::Data> for tg::process::State {
 		let exit = value.exit;
 		let expected_checksum = value.expected_checksum;
 		let finished_at = value.finished_at;
+		let labels = value.labels;
+		let limits = value.limits;
 		let log = value.log.map(tg::Blob::with_id);
 		let mounts = value.mounts.into_iter().map(Into::into).collect();
 		let network = value.network;
 		let output = value.output.map(tg::Value::try_from).transpose()?;
+		let priority = value.priority;
 		let retry = value.retry;
 		let started_at = value.started_at;
 		let status = value.status;
 		let stderr = value.stderr;
 		let stdin = value.stdin;
 		let stdout = value.stdout;
+		let timeout = value.timeout;
+		let usage = value.usage;
 		Ok(State {
 			actual_checksum,
 			cacheable,
@@ -67,16 +77,21 @@ impl TryFrom<tg::process::Data> for tg::process::State {
 			exit,
 			expected_checksum,
 			finished_at,
+			labels,
+			limits,
 			log,
 			mounts,
 			network,
 			output,
+			priority,
 			retry,
 			started_at,
 			status,
 			stderr,
 			stdin,
 			stdout,
+			timeout,
+			usage,
 		})
 	}
 }
@@ -0,0 +1,27 @@
+/// The resources used by a process. Only the Linux runtime records usage, so processes run by the builtin, js, and darwin runtimes have none.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Usage {
+	/// The CPU time spent in kernel mode in microseconds.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cpu_system: Option<u64>,
+
+	/// The CPU time spent in user mode in microseconds.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cpu_user: Option<u64>,
+
+	/// The peak resident set size in bytes.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_rss: Option<u64>,
+
+	/// The number of bytes read from storage.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub read_bytes: Option<u64>,
+
+	/// The wall-clock time in milliseconds.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub wall_time: Option<u64>,
+
+	/// The number of bytes written to storage.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub written_bytes: Option<u64>,
+}
@@ -4,6 +4,9 @@ use url::Url;
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub struct Arg {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub token: Option<String>,
+
 	pub url: Url,
 }
 
@@ -11,16 +11,20 @@ pub struct Arg {
 	pub env: tg::value::Map,
 	pub executable: Option<tg::command::Executable>,
 	pub host: Option<String>,
+	pub labels: Option<Vec<String>>,
+	pub limits: Option<tg::process::Limits>,
 	pub mounts: Option<Vec<Either<tg::process::Mount, tg::command::Mount>>>,
 	pub network: Option<bool>,
 	pub parent: Option<tg::process::Id>,
 	pub path: Option<PathBuf>,
+	pub priority: Option<i64>,
 	pub remote: Option<String>,
 	pub retry: bool,
 	pub stderr: Option<Option<tg::process::Stdio>>,
 	pub stdin: Option<Option<Either<tg::process::Stdio, tg::Blob>>>,
 	pub stdout: Option<Option<tg::process::Stdio>>,
 	pub tag: Option<tg::Tag>,
+	pub timeout: Option<u64>,
 	pub user: Option<String>,
 }
 
@@ -97,10 +101,20 @@ where
 	let command = builder.build();
 	let command_id = command.store(handle).await?;
 	let checksum = arg.checksum;
+	let labels = arg
+		.labels
+		.or_else(|| state.as_ref().map(|state| state.labels.clone()))
+		.unwrap_or_default();
+	let limits = arg
+		.limits
+		.or_else(|| state.as_ref().and_then(|state| state.limits.clone()));
 	let network = arg
 		.network
 		.or(state.as_ref().map(|state| state.network))
 		.unwrap_or_default();
+	let priority = arg
+		.priority
+		.or_else(|| state.as_ref().and_then(|state| state.priority));
 	let stderr = arg
 		.stderr
 		.unwrap_or_else(|| state.as_ref().and_then(|state| state.stderr.clone()));
@@ -117,6 +131,9 @@ where
 	let stdout = arg
 		.stdout
 		.unwrap_or_else(|| state.as_ref().and_then(|state| state.stdout.clone()));
+	let timeout = arg
+		.timeout
+		.or_else(|| state.as_ref().and_then(|state| state.timeout));
 	if network && checksum.is_none() {
 		return Err(tg::error!(
 			"a checksum is required to build with network enabled"
@@ -126,16 +143,20 @@ where
 		cached: arg.cached,
 		checksum,
 		command: Some(command_id),
+		labels,
+		limits,
 		mounts: process_mounts,
 		network,
 		parent: arg.parent,
 		path: arg.path,
+		priority,
 		remote: arg.remote,
 		retry: arg.retry,
 		stderr,
 		stdin,
 		stdout,
 		tag: arg.tag,
+		timeout,
 	};
 	let process = tg::Process::spawn(handle, arg).await?;
 	let output = process.output(handle).await?;
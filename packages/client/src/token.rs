@@ -0,0 +1,125 @@
+use crate as tg;
+
+pub mod create;
+pub mod delete;
+pub mod list;
+
+#[derive(
+	Clone,
+	Debug,
+	Eq,
+	Hash,
+	Ord,
+	PartialEq,
+	PartialOrd,
+	derive_more::Display,
+	serde::Deserialize,
+	serde::Serialize,
+)]
+#[serde(into = "crate::Id", try_from = "crate::Id")]
+pub struct Id(crate::Id);
+
+impl Id {
+	#[allow(clippy::new_without_default)]
+	#[must_use]
+	pub fn new() -> Self {
+		Self(crate::Id::new_uuidv7(tg::id::Kind::Token))
+	}
+}
+
+impl From<Id> for crate::Id {
+	fn from(value: Id) -> Self {
+		value.0
+	}
+}
+
+impl TryFrom<crate::Id> for Id {
+	type Error = tg::Error;
+
+	fn try_from(value: crate::Id) -> tg::Result<Self, Self::Error> {
+		if value.kind() != tg::id::Kind::Token {
+			return Err(tg::error!(%value, "invalid kind"));
+		}
+		Ok(Self(value))
+	}
+}
+
+impl std::str::FromStr for Id {
+	type Err = tg::Error;
+
+	fn from_str(s: &str) -> tg::Result<Self, Self::Err> {
+		s.parse::<crate::Id>()?.try_into()
+	}
+}
+
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	Eq,
+	Hash,
+	Ord,
+	PartialEq,
+	PartialOrd,
+	serde_with::DeserializeFromStr,
+	serde_with::SerializeDisplay,
+)]
+pub enum Scope {
+	ReadObjects,
+	WriteObjects,
+	SpawnProcesses,
+	WriteTags,
+	ManageRemotes,
+	ManageTokens,
+	ReadMetrics,
+	Administer,
+}
+
+impl Scope {
+	#[must_use]
+	pub fn all() -> Vec<Self> {
+		vec![
+			Self::ReadObjects,
+			Self::WriteObjects,
+			Self::SpawnProcesses,
+			Self::WriteTags,
+			Self::ManageRemotes,
+			Self::ManageTokens,
+			Self::ReadMetrics,
+			Self::Administer,
+		]
+	}
+}
+
+impl std::fmt::Display for Scope {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::ReadObjects => write!(f, "read_objects"),
+			Self::WriteObjects => write!(f, "write_objects"),
+			Self::SpawnProcesses => write!(f, "spawn_processes"),
+			Self::WriteTags => write!(f, "write_tags"),
+			Self::ManageRemotes => write!(f, "manage_remotes"),
+			Self::ManageTokens => write!(f, "manage_tokens"),
+			Self::ReadMetrics => write!(f, "read_metrics"),
+			Self::Administer => write!(f, "administer"),
+		}
+	}
+}
+
+impl std::str::FromStr for Scope {
+	type Err = tg::Error;
+
+	fn from_str(s: &str) -> tg::Result<Self, Self::Err> {
+		match s {
+			"read_objects" => Ok(Self::ReadObjects),
+			"write_objects" => Ok(Self::WriteObjects),
+			"spawn_processes" => Ok(Self::SpawnProcesses),
+			"write_tags" => Ok(Self::WriteTags),
+			"manage_remotes" => Ok(Self::ManageRemotes),
+			"manage_tokens" => Ok(Self::ManageTokens),
+			"read_metrics" => Ok(Self::ReadMetrics),
+			"administer" => Ok(Self::Administer),
+			scope => Err(tg::error!(%scope, "invalid value")),
+		}
+	}
+}
@@ -0,0 +1,42 @@
+use crate as tg;
+use tangram_http::{request::builder::Ext as _, response::Ext as _};
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Arg {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expires_at: Option<i64>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	pub scopes: Vec<tg::token::Scope>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Output {
+	pub id: tg::token::Id,
+	pub token: String,
+}
+
+impl tg::Client {
+	pub async fn create_token(
+		&self,
+		arg: tg::token::create::Arg,
+	) -> tg::Result<tg::token::create::Output> {
+		let method = http::Method::POST;
+		let uri = "/tokens";
+		let request = http::request::Builder::default()
+			.method(method)
+			.uri(uri)
+			.json(arg)
+			.map_err(|source| tg::error!(!source, "failed to serialize the arg"))?
+			.unwrap();
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			let error = response.json().await?;
+			return Err(error);
+		}
+		let output = response.json().await?;
+		Ok(output)
+	}
+}
@@ -0,0 +1,20 @@
+use crate as tg;
+use tangram_http::{request::builder::Ext as _, response::Ext as _};
+
+impl tg::Client {
+	pub async fn delete_token(&self, id: &tg::token::Id) -> tg::Result<()> {
+		let method = http::Method::DELETE;
+		let uri = format!("/tokens/{id}");
+		let request = http::request::Builder::default()
+			.method(method)
+			.uri(uri)
+			.empty()
+			.unwrap();
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			let error = response.json().await?;
+			return Err(error);
+		}
+		Ok(())
+	}
+}
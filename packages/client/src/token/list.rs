@@ -0,0 +1,46 @@
+use crate as tg;
+use tangram_http::{request::builder::Ext as _, response::Ext as _};
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct Arg {}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+pub struct Output {
+	pub data: Vec<Item>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Item {
+	pub id: tg::token::Id,
+
+	pub created_at: i64,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expires_at: Option<i64>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	pub scopes: Vec<tg::token::Scope>,
+}
+
+impl tg::Client {
+	pub async fn list_tokens(
+		&self,
+		arg: tg::token::list::Arg,
+	) -> tg::Result<tg::token::list::Output> {
+		let method = http::Method::GET;
+		let query = serde_urlencoded::to_string(&arg).unwrap();
+		let uri = format!("/tokens?{query}");
+		let request = http::request::Builder::default().method(method).uri(uri);
+		let request = request.empty().unwrap();
+		let response = self.send(request).await?;
+		if !response.status().is_success() {
+			let error = response.json().await?;
+			return Err(error);
+		}
+		let output = response.json().await?;
+		Ok(output)
+	}
+}
@@ -139,6 +139,7 @@ fn id_kind(input: &mut Input) -> ModalResult<tg::id::Kind> {
 		alt(("pcs", "process")).value(tg::id::Kind::Process),
 		alt(("usr", "user")).value(tg::id::Kind::User),
 		alt(("req", "request")).value(tg::id::Kind::Request),
+		alt(("tok", "token")).value(tg::id::Kind::Token),
 	))
 	.parse_next(input)
 }
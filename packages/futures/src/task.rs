@@ -139,6 +139,11 @@ where
 			.clone()
 	}
 
+	#[must_use]
+	pub fn keys(&self) -> Vec<K> {
+		self.map.iter().map(|entry| entry.key().clone()).collect()
+	}
+
 	pub fn get_task_id(&self, key: &K) -> Option<tokio::task::Id> {
 		self.map.get(key).map(|task| task.abort.id())
 	}
@@ -5,6 +5,7 @@ pub use self::acker::Acker;
 
 pub mod acker;
 pub mod either;
+pub mod local;
 pub mod memory;
 #[cfg(feature = "nats")]
 pub mod nats;
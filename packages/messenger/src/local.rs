@@ -0,0 +1,907 @@
+use crate::{
+	Acker, BatchConfig, ConsumerConfig, ConsumerInfo, DeliverPolicy, DiscardPolicy, Error, Message,
+	RetentionPolicy, StreamConfig, StreamInfo,
+};
+use async_broadcast as broadcast;
+use bytes::Bytes;
+use dashmap::DashMap;
+use futures::{FutureExt as _, StreamExt as _, TryStreamExt as _, future, stream};
+use num::ToPrimitive as _;
+use rusqlite::{self as sqlite, OptionalExtension as _};
+use std::{
+	ops::Deref,
+	path::Path,
+	pin::pin,
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicBool, Ordering},
+	},
+};
+use tokio::sync::Notify;
+
+#[cfg(test)]
+mod tests;
+
+const SCHEMA: &str = "
+	create table if not exists streams (
+		name text primary key,
+		discard text not null,
+		max_bytes integer,
+		max_messages integer,
+		retention text not null,
+		last_sequence integer not null default 0,
+		messages integer not null default 0,
+		bytes integer not null default 0
+	);
+
+	create table if not exists messages (
+		stream text not null,
+		sequence integer not null,
+		payload blob not null,
+		primary key (stream, sequence)
+	);
+
+	create table if not exists consumers (
+		stream text not null,
+		name text not null,
+		sequence integer not null,
+		primary key (stream, name)
+	);
+
+	create table if not exists acks (
+		stream text not null,
+		consumer text not null,
+		sequence integer not null,
+		primary key (stream, consumer, sequence)
+	);
+";
+
+/// A messenger whose streams are persisted to a sqlite database, so that messages which have been published but not yet acknowledged survive a restart.
+pub struct Messenger {
+	database: Database,
+	receiver: broadcast::InactiveReceiver<(String, Bytes)>,
+	sender: broadcast::Sender<(String, Bytes)>,
+	streams: DashMap<String, Stream>,
+}
+
+type Database = Arc<Mutex<sqlite::Connection>>;
+
+#[derive(Clone)]
+pub struct Stream {
+	inner: Arc<StreamInner>,
+}
+
+pub struct StreamInner {
+	closed: AtomicBool,
+	config: StreamConfig,
+	database: Database,
+	name: String,
+	notify: Notify,
+}
+
+#[derive(Clone)]
+pub struct Consumer {
+	inner: Arc<ConsumerInner>,
+}
+
+pub struct ConsumerInner {
+	name: String,
+	stream: Stream,
+}
+
+impl Messenger {
+	pub fn new(path: &Path) -> Result<Self, Error> {
+		let connection = sqlite::Connection::open(path).map_err(Error::other)?;
+		connection
+			.pragma_update(None, "journal_mode", "wal")
+			.map_err(Error::other)?;
+		connection
+			.pragma_update(None, "synchronous", "full")
+			.map_err(Error::other)?;
+		connection.execute_batch(SCHEMA).map_err(Error::other)?;
+		let database = Arc::new(Mutex::new(connection));
+		let (mut sender, receiver) = async_broadcast::broadcast(1_000_000);
+		let receiver = receiver.deactivate();
+		sender.set_overflow(true);
+		sender.set_await_active(false);
+		let streams = DashMap::new();
+		Ok(Self {
+			database,
+			receiver,
+			sender,
+			streams,
+		})
+	}
+
+	async fn publish(&self, subject: String, payload: Bytes) -> Result<(), Error> {
+		self.sender.try_broadcast((subject, payload)).ok();
+		Ok(())
+	}
+
+	async fn subscribe(
+		&self,
+		subject: String,
+		_group: Option<String>,
+	) -> Result<impl futures::Stream<Item = Message> + Send + 'static, Error> {
+		let stream = self
+			.receiver
+			.activate_cloned()
+			.filter_map(move |(subject_, payload)| {
+				future::ready({
+					(subject_ == subject).then(|| Message {
+						subject: subject_,
+						payload,
+						acker: Acker::default(),
+					})
+				})
+			});
+		Ok(stream)
+	}
+
+	async fn get_stream(&self, name: String) -> Result<Stream, Error> {
+		if let Some(stream) = self.streams.get(&name) {
+			return Ok(stream.clone());
+		}
+		let config = run(&self.database, {
+			let name = name.clone();
+			move |connection| {
+				let statement = "
+					select discard, max_bytes, max_messages, retention
+					from streams
+					where name = ?1;
+				";
+				connection
+					.query_row(statement, [&name], |row| {
+						Ok(StreamConfig {
+							discard: discard_from_str(&row.get::<_, String>(0)?),
+							max_bytes: row.get(1)?,
+							max_messages: row.get(2)?,
+							retention: retention_from_str(&row.get::<_, String>(3)?),
+						})
+					})
+					.optional()
+			}
+		})
+		.await?
+		.ok_or(Error::NotFound)?;
+		let stream = self
+			.streams
+			.entry(name.clone())
+			.or_insert_with(|| Stream::new(self.database.clone(), name, config))
+			.value()
+			.clone();
+		Ok(stream)
+	}
+
+	async fn create_stream(&self, name: String, config: StreamConfig) -> Result<Stream, Error> {
+		self.delete_stream(name.clone()).await?;
+		self.insert_stream(name.clone(), config.clone()).await?;
+		let stream = Stream::new(self.database.clone(), name.clone(), config);
+		self.streams.insert(name, stream.clone());
+		Ok(stream)
+	}
+
+	async fn get_or_create_stream(
+		&self,
+		name: String,
+		config: StreamConfig,
+	) -> Result<Stream, Error> {
+		match self.get_stream(name.clone()).await {
+			Ok(stream) => Ok(stream),
+			Err(Error::NotFound) => {
+				self.insert_stream(name.clone(), config).await?;
+				self.get_stream(name).await
+			},
+			Err(error) => Err(error),
+		}
+	}
+
+	async fn insert_stream(&self, name: String, config: StreamConfig) -> Result<(), Error> {
+		run(&self.database, move |connection| {
+			let statement = "
+				insert into streams (name, discard, max_bytes, max_messages, retention)
+				values (?1, ?2, ?3, ?4, ?5)
+				on conflict (name) do nothing;
+			";
+			let params = sqlite::params![
+				name,
+				discard_to_str(&config.discard),
+				config.max_bytes,
+				config.max_messages,
+				retention_to_str(&config.retention),
+			];
+			connection.execute(statement, params)?;
+			Ok(())
+		})
+		.await
+	}
+
+	async fn delete_stream(&self, name: String) -> Result<(), Error> {
+		if let Some((_, stream)) = self.streams.remove(&name) {
+			stream.close();
+		}
+		run(&self.database, move |connection| {
+			let transaction = connection.transaction()?;
+			transaction.execute("delete from streams where name = ?1;", [&name])?;
+			transaction.execute("delete from messages where stream = ?1;", [&name])?;
+			transaction.execute("delete from consumers where stream = ?1;", [&name])?;
+			transaction.execute("delete from acks where stream = ?1;", [&name])?;
+			transaction.commit()?;
+			Ok(())
+		})
+		.await
+	}
+
+	async fn stream_publish(
+		&self,
+		name: String,
+		payload: Bytes,
+	) -> Result<impl Future<Output = Result<u64, Error>>, Error> {
+		let stream = self.get_stream(name).await?;
+		let future = tokio::spawn(async move { stream.publish(payload).await })
+			.map(|result| result.unwrap());
+		Ok(future)
+	}
+
+	async fn stream_batch_publish(
+		&self,
+		name: String,
+		payloads: Vec<Bytes>,
+	) -> Result<impl Future<Output = Result<Vec<u64>, Error>>, Error> {
+		let stream = self.get_stream(name).await?;
+		let future = tokio::spawn(async move { stream.batch_publish(payloads).await })
+			.map(|result| result.unwrap());
+		Ok(future)
+	}
+}
+
+impl Stream {
+	fn new(database: Database, name: String, config: StreamConfig) -> Self {
+		let inner = Arc::new(StreamInner {
+			closed: AtomicBool::new(false),
+			config,
+			database,
+			name,
+			notify: Notify::new(),
+		});
+		Self { inner }
+	}
+
+	fn close(&self) {
+		self.closed.store(true, Ordering::SeqCst);
+		self.notify.notify_waiters();
+	}
+
+	async fn info(&self) -> Result<StreamInfo, Error> {
+		let name = self.name.clone();
+		let (first, last_sequence) = run(&self.database, move |connection| {
+			let statement = "
+				select
+					(select min(sequence) from messages where stream = ?1),
+					last_sequence
+				from streams
+				where name = ?1;
+			";
+			connection
+				.query_row(statement, [&name], |row| {
+					Ok((row.get::<_, Option<u64>>(0)?, row.get::<_, u64>(1)?))
+				})
+				.optional()
+		})
+		.await?
+		.ok_or(Error::NotFound)?;
+		let first_sequence = first.unwrap_or(if last_sequence == 0 {
+			0
+		} else {
+			last_sequence + 1
+		});
+		let info = StreamInfo {
+			first_sequence,
+			last_sequence,
+		};
+		Ok(info)
+	}
+
+	async fn get_consumer(&self, name: String) -> Result<Consumer, Error> {
+		let exists = run(&self.database, {
+			let stream = self.name.clone();
+			let name = name.clone();
+			move |connection| {
+				let statement = "
+					select count(*) from consumers where stream = ?1 and name = ?2;
+				";
+				connection.query_row(statement, [&stream, &name], |row| row.get::<_, u64>(0))
+			}
+		})
+		.await?;
+		if exists == 0 {
+			return Err(Error::NotFound);
+		}
+		Ok(Consumer::new(name, self.clone()))
+	}
+
+	async fn create_consumer(
+		&self,
+		name: String,
+		config: ConsumerConfig,
+	) -> Result<Consumer, Error> {
+		self.insert_consumer(name.clone(), config, true).await?;
+		Ok(Consumer::new(name, self.clone()))
+	}
+
+	async fn get_or_create_consumer(
+		&self,
+		name: String,
+		config: ConsumerConfig,
+	) -> Result<Consumer, Error> {
+		self.insert_consumer(name.clone(), config, false).await?;
+		Ok(Consumer::new(name, self.clone()))
+	}
+
+	async fn insert_consumer(
+		&self,
+		name: String,
+		config: ConsumerConfig,
+		replace: bool,
+	) -> Result<(), Error> {
+		let stream = self.name.clone();
+		run(&self.database, move |connection| {
+			let transaction = connection.transaction()?;
+			if replace {
+				transaction.execute(
+					"delete from consumers where stream = ?1 and name = ?2;",
+					[&stream, &name],
+				)?;
+				transaction.execute(
+					"delete from acks where stream = ?1 and consumer = ?2;",
+					[&stream, &name],
+				)?;
+			}
+
+			// A consumer's sequence is the sequence of the last message it acknowledged.
+			let sequence = match config.deliver {
+				DeliverPolicy::All => 0,
+				DeliverPolicy::New => transaction.query_row(
+					"select last_sequence from streams where name = ?1;",
+					[&stream],
+					|row| row.get::<_, u64>(0),
+				)?,
+			};
+			let statement = "
+				insert into consumers (stream, name, sequence)
+				values (?1, ?2, ?3)
+				on conflict (stream, name) do nothing;
+			";
+			transaction.execute(statement, sqlite::params![stream, name, sequence])?;
+			transaction.commit()?;
+			Ok(())
+		})
+		.await
+	}
+
+	async fn delete_consumer(&self, name: String) -> Result<(), Error> {
+		let stream = self.name.clone();
+		run(&self.database, move |connection| {
+			let transaction = connection.transaction()?;
+			transaction.execute(
+				"delete from consumers where stream = ?1 and name = ?2;",
+				[&stream, &name],
+			)?;
+			transaction.execute(
+				"delete from acks where stream = ?1 and consumer = ?2;",
+				[&stream, &name],
+			)?;
+			transaction.commit()?;
+			Ok(())
+		})
+		.await
+	}
+
+	async fn publish(&self, payload: Bytes) -> Result<u64, Error> {
+		let mut infos = self.batch_publish(vec![payload]).await?;
+		let info = infos.pop().ok_or_else(|| Error::MaxMessages)?;
+		Ok(info)
+	}
+
+	async fn batch_publish(&self, payloads: Vec<Bytes>) -> Result<Vec<u64>, Error> {
+		let stream = self.name.clone();
+		let config = self.config.clone();
+		let ids = run(&self.database, move |connection| {
+			let transaction = connection.transaction()?;
+
+			// Get the stream's current size and last sequence.
+			let statement = "
+				select messages, bytes, last_sequence
+				from streams
+				where name = ?1;
+			";
+			let (mut messages, mut bytes, mut sequence) =
+				transaction.query_row(statement, [&stream], |row| {
+					Ok((
+						row.get::<_, u64>(0)?,
+						row.get::<_, u64>(1)?,
+						row.get::<_, u64>(2)?,
+					))
+				})?;
+
+			// Publish as many messages as possible.
+			let mut ids = Vec::new();
+			for payload in payloads {
+				let length = payload.len().to_u64().unwrap();
+				let exceeds = |messages: u64, bytes: u64| {
+					config
+						.max_messages
+						.is_some_and(|max_messages| messages + 1 > max_messages)
+						|| config
+							.max_bytes
+							.is_some_and(|max_bytes| bytes + length > max_bytes)
+				};
+
+				// Discard the oldest messages to make room if the discard policy allows it.
+				while matches!(config.discard, DiscardPolicy::Old)
+					&& messages > 0 && exceeds(messages, bytes)
+				{
+					let statement = "
+						delete from messages
+						where stream = ?1 and sequence = (
+							select min(sequence) from messages where stream = ?1
+						)
+						returning length(payload);
+					";
+					let removed =
+						transaction.query_row(statement, [&stream], |row| row.get::<_, u64>(0))?;
+					messages -= 1;
+					bytes -= removed;
+				}
+				if exceeds(messages, bytes) {
+					break;
+				}
+
+				sequence += 1;
+				let statement = "
+					insert into messages (stream, sequence, payload)
+					values (?1, ?2, ?3);
+				";
+				transaction.execute(
+					statement,
+					sqlite::params![stream, sequence, payload.as_ref()],
+				)?;
+				messages += 1;
+				bytes += length;
+				ids.push(sequence);
+			}
+
+			let statement = "
+				update streams
+				set last_sequence = ?1, messages = ?2, bytes = ?3
+				where name = ?4;
+			";
+			transaction.execute(
+				statement,
+				sqlite::params![sequence, messages, bytes, stream],
+			)?;
+			transaction.commit()?;
+			Ok(ids)
+		})
+		.await?;
+
+		// Notify consumers.
+		self.notify.notify_waiters();
+
+		Ok(ids)
+	}
+}
+
+impl Consumer {
+	fn new(name: String, stream: Stream) -> Self {
+		let inner = Arc::new(ConsumerInner { name, stream });
+		Self { inner }
+	}
+
+	async fn info(&self) -> Result<ConsumerInfo, Error> {
+		let stream = self.stream.name.clone();
+		let name = self.name.clone();
+		let sequence = run(&self.stream.database, move |connection| {
+			let statement = "
+				select sequence from consumers where stream = ?1 and name = ?2;
+			";
+			connection
+				.query_row(statement, [&stream, &name], |row| row.get::<_, u64>(0))
+				.optional()
+		})
+		.await?
+		.ok_or(Error::NotFound)?;
+		let info = ConsumerInfo { sequence };
+		Ok(info)
+	}
+
+	async fn subscribe(
+		&self,
+	) -> Result<impl futures::Stream<Item = Result<Message, Error>> + Send + 'static, Error> {
+		let config = BatchConfig {
+			max_bytes: None,
+			max_messages: None,
+			timeout: None,
+		};
+		self.batch_subscribe(config).await
+	}
+
+	async fn batch_subscribe(
+		&self,
+		config: BatchConfig,
+	) -> Result<impl futures::Stream<Item = Result<Message, Error>> + Send + 'static, Error> {
+		// Start after the last acknowledged message, so that messages which were delivered but not acknowledged before a restart are delivered again.
+		let info = self.info().await?;
+
+		// Create the state.
+		struct State {
+			config: BatchConfig,
+			consumer: Consumer,
+			cursor: u64,
+		}
+		let state = State {
+			config,
+			consumer: self.clone(),
+			cursor: info.sequence,
+		};
+
+		// Create the stream.
+		let stream = stream::try_unfold(state, move |mut state| async move {
+			let stream = state.consumer.stream.clone();
+			let deadline = state
+				.config
+				.timeout
+				.map(|timeout| tokio::time::Instant::now() + timeout);
+			let mut messages = Vec::new();
+			let mut bytes = 0;
+			loop {
+				if stream.closed.load(Ordering::SeqCst) {
+					if messages.is_empty() {
+						return Ok(None);
+					}
+					break;
+				}
+
+				// Enable the notification before reading so that a publish cannot be missed.
+				let notified = stream.notify.notified();
+				let mut notified = pin!(notified);
+				notified.as_mut().enable();
+
+				// Get the messages after the cursor that have not been acknowledged.
+				let rows = run(&stream.database, {
+					let stream = stream.name.clone();
+					let consumer = state.consumer.name.clone();
+					let cursor = state.cursor;
+					let limit = state.config.max_messages.map_or(-1, |max_messages| {
+						max_messages
+							.saturating_sub(messages.len().to_u64().unwrap())
+							.to_i64()
+							.unwrap()
+					});
+					move |connection| {
+						let statement = "
+							select sequence, payload
+							from messages
+							where stream = ?1 and sequence > ?3 and sequence not in (
+								select sequence from acks where stream = ?1 and consumer = ?2
+							)
+							order by sequence
+							limit ?4;
+						";
+						let mut statement = connection.prepare_cached(statement)?;
+						let params = sqlite::params![stream, consumer, cursor, limit];
+						statement
+							.query_map(params, |row| {
+								Ok((
+									row.get::<_, u64>(0)?,
+									Bytes::from(row.get::<_, Vec<u8>>(1)?),
+								))
+							})?
+							.collect::<sqlite::Result<Vec<_>>>()
+					}
+				})
+				.await?;
+
+				// Add the messages to the batch until it is full, and advance the cursor past them. A batch always includes at least one message, even if it exceeds the max bytes.
+				let mut full = state.config.max_messages.is_some_and(|max_messages| {
+					messages.len().to_u64().unwrap() + rows.len().to_u64().unwrap() >= max_messages
+				});
+				for (sequence, payload) in rows {
+					let length = payload.len().to_u64().unwrap();
+					if !messages.is_empty()
+						&& state
+							.config
+							.max_bytes
+							.is_some_and(|max_bytes| bytes + length > max_bytes)
+					{
+						full = true;
+						break;
+					}
+					state.cursor = sequence;
+					bytes += length;
+					messages.push((sequence, payload));
+				}
+				if full {
+					break;
+				}
+
+				// Wait for more messages. A partial batch is returned immediately if there is no timeout, or once the timeout elapses.
+				if messages.is_empty() {
+					notified.await;
+				} else {
+					let Some(deadline) = deadline else {
+						break;
+					};
+					if tokio::time::timeout_at(deadline, notified).await.is_err() {
+						break;
+					}
+				}
+			}
+
+			// Create the messages.
+			let messages = messages
+				.into_iter()
+				.map(|(sequence, payload)| {
+					let acker = Acker::new(state.consumer.clone().ack(sequence));
+					Ok(Message {
+						acker,
+						payload,
+						subject: stream.name.clone(),
+					})
+				})
+				.collect::<Vec<_>>();
+
+			Ok::<_, Error>(Some((messages, state)))
+		})
+		.map_ok(stream::iter)
+		.try_flatten();
+
+		Ok(stream)
+	}
+
+	async fn ack(self, sequence: u64) -> Result<(), Error> {
+		let stream = self.stream.name.clone();
+		let name = self.name.clone();
+		let retention = self.stream.config.retention.clone();
+		run(&self.stream.database, move |connection| {
+			let transaction = connection.transaction()?;
+
+			// Record the acknowledgement.
+			let statement = "
+				insert into acks (stream, consumer, sequence)
+				values (?1, ?2, ?3)
+				on conflict do nothing;
+			";
+			transaction.execute(statement, sqlite::params![stream, name, sequence])?;
+
+			// Advance the consumer's sequence past every contiguously acknowledged message.
+			let statement = "
+				update consumers
+				set sequence = coalesce(
+					(
+						select min(sequence) - 1
+						from messages
+						where stream = ?1 and sequence > consumers.sequence and sequence not in (
+							select sequence from acks where stream = ?1 and consumer = ?2
+						)
+					),
+					(
+						select max(sequence)
+						from acks
+						where stream = ?1 and consumer = ?2
+					),
+					sequence
+				)
+				where stream = ?1 and name = ?2;
+			";
+			transaction.execute(statement, [&stream, &name])?;
+			let statement = "
+				delete from acks
+				where stream = ?1 and consumer = ?2 and sequence <= (
+					select sequence from consumers where stream = ?1 and name = ?2
+				);
+			";
+			transaction.execute(statement, [&stream, &name])?;
+
+			// Remove the messages that every consumer has acknowledged.
+			if matches!(
+				retention,
+				RetentionPolicy::Interest | RetentionPolicy::WorkQueue
+			) {
+				let statement = "
+					delete from messages
+					where stream = ?1 and sequence <= (
+						select min(sequence) from consumers where stream = ?1
+					)
+					returning length(payload);
+				";
+				let (messages, bytes) = transaction
+					.prepare(statement)?
+					.query_map([&stream], |row| row.get::<_, u64>(0))?
+					.try_fold((0u64, 0u64), |(messages, bytes), length| {
+						Ok::<_, sqlite::Error>((messages + 1, bytes + length?))
+					})?;
+				if messages > 0 {
+					let statement = "
+						update streams
+						set messages = messages - ?1, bytes = bytes - ?2
+						where name = ?3;
+					";
+					transaction.execute(statement, sqlite::params![messages, bytes, stream])?;
+				}
+			}
+
+			transaction.commit()?;
+			Ok(())
+		})
+		.await
+	}
+}
+
+async fn run<T, F>(database: &Database, f: F) -> Result<T, Error>
+where
+	T: Send + 'static,
+	F: FnOnce(&mut sqlite::Connection) -> sqlite::Result<T> + Send + 'static,
+{
+	let database = database.clone();
+	tokio::task::spawn_blocking(move || {
+		let mut connection = database.lock().unwrap();
+		f(&mut connection)
+	})
+	.await
+	.unwrap()
+	.map_err(Error::other)
+}
+
+fn discard_to_str(discard: &DiscardPolicy) -> &'static str {
+	match discard {
+		DiscardPolicy::Old => "old",
+		DiscardPolicy::New => "new",
+	}
+}
+
+fn discard_from_str(discard: &str) -> DiscardPolicy {
+	match discard {
+		"new" => DiscardPolicy::New,
+		_ => DiscardPolicy::Old,
+	}
+}
+
+fn retention_to_str(retention: &RetentionPolicy) -> &'static str {
+	match retention {
+		RetentionPolicy::Limits => "limits",
+		RetentionPolicy::Interest => "interest",
+		RetentionPolicy::WorkQueue => "work_queue",
+	}
+}
+
+fn retention_from_str(retention: &str) -> RetentionPolicy {
+	match retention {
+		"interest" => RetentionPolicy::Interest,
+		"work_queue" => RetentionPolicy::WorkQueue,
+		_ => RetentionPolicy::Limits,
+	}
+}
+
+impl Deref for Stream {
+	type Target = StreamInner;
+
+	fn deref(&self) -> &Self::Target {
+		&self.inner
+	}
+}
+
+impl Deref for Consumer {
+	type Target = ConsumerInner;
+
+	fn deref(&self) -> &Self::Target {
+		&self.inner
+	}
+}
+
+impl crate::Messenger for Messenger {
+	type Stream = Stream;
+
+	async fn publish(&self, subject: String, payload: Bytes) -> Result<(), Error> {
+		self.publish(subject, payload).await
+	}
+
+	async fn subscribe(
+		&self,
+		subject: String,
+		group: Option<String>,
+	) -> Result<impl futures::Stream<Item = Message> + 'static, Error> {
+		self.subscribe(subject, group).await
+	}
+
+	async fn get_stream(&self, name: String) -> Result<Self::Stream, Error> {
+		self.get_stream(name).await
+	}
+
+	async fn create_stream(
+		&self,
+		name: String,
+		config: StreamConfig,
+	) -> Result<Self::Stream, Error> {
+		self.create_stream(name, config).await
+	}
+
+	async fn get_or_create_stream(
+		&self,
+		name: String,
+		config: StreamConfig,
+	) -> Result<Self::Stream, Error> {
+		self.get_or_create_stream(name, config).await
+	}
+
+	async fn delete_stream(&self, name: String) -> Result<(), Error> {
+		self.delete_stream(name).await
+	}
+
+	async fn stream_publish(
+		&self,
+		name: String,
+		payload: Bytes,
+	) -> Result<impl Future<Output = Result<u64, Error>>, Error> {
+		self.stream_publish(name, payload).await
+	}
+
+	async fn stream_batch_publish(
+		&self,
+		name: String,
+		payloads: Vec<Bytes>,
+	) -> Result<impl Future<Output = Result<Vec<u64>, Error>>, Error> {
+		self.stream_batch_publish(name, payloads).await
+	}
+}
+
+impl crate::Stream for Stream {
+	type Consumer = Consumer;
+
+	async fn info(&self) -> Result<StreamInfo, Error> {
+		self.info().await
+	}
+
+	async fn get_consumer(&self, name: String) -> Result<Self::Consumer, Error> {
+		self.get_consumer(name).await
+	}
+
+	async fn create_consumer(
+		&self,
+		name: String,
+		config: ConsumerConfig,
+	) -> Result<Self::Consumer, Error> {
+		self.create_consumer(name, config).await
+	}
+
+	async fn get_or_create_consumer(
+		&self,
+		name: String,
+		config: ConsumerConfig,
+	) -> Result<Self::Consumer, Error> {
+		self.get_or_create_consumer(name, config).await
+	}
+
+	async fn delete_consumer(&self, name: String) -> Result<(), Error> {
+		self.delete_consumer(name).await
+	}
+}
+
+impl crate::Consumer for Consumer {
+	async fn info(&self) -> Result<ConsumerInfo, Error> {
+		self.info().await
+	}
+
+	async fn subscribe(
+		&self,
+	) -> Result<impl futures::Stream<Item = Result<Message, Error>> + Send + 'static, Error> {
+		self.subscribe().await
+	}
+
+	async fn batch_subscribe(
+		&self,
+		config: BatchConfig,
+	) -> Result<impl futures::Stream<Item = Result<Message, Error>> + Send + 'static, Error> {
+		self.batch_subscribe(config).await
+	}
+}
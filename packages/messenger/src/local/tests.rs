@@ -0,0 +1,162 @@
+use super::Messenger;
+use crate::{ConsumerConfig, DiscardPolicy, Error, Message, RetentionPolicy, StreamConfig};
+use bytes::Bytes;
+use futures::{StreamExt as _, TryStreamExt as _};
+use tangram_temp::Temp;
+
+/// Test that messages which were delivered but not acknowledged are delivered again after a restart.
+#[tokio::test]
+async fn redelivery_after_restart() {
+	let temp = Temp::new();
+	std::fs::create_dir_all(temp.path()).unwrap();
+	let path = temp.path().join("messenger");
+	let config = StreamConfig {
+		retention: RetentionPolicy::WorkQueue,
+		..Default::default()
+	};
+
+	// Publish three messages and acknowledge only the first.
+	{
+		let messenger = Messenger::new(&path).unwrap();
+		let stream = messenger
+			.get_or_create_stream("stream".to_owned(), config.clone())
+			.await
+			.unwrap();
+		let consumer = stream
+			.get_or_create_consumer("consumer".to_owned(), ConsumerConfig::default())
+			.await
+			.unwrap();
+		let payloads = ["a", "b", "c"].map(Bytes::from).to_vec();
+		stream.batch_publish(payloads).await.unwrap();
+		let messages = receive(&consumer, 3).await;
+		assert_eq!(payloads_of(&messages), ["a", "b", "c"]);
+		let mut messages = messages.into_iter();
+		messages.next().unwrap().acker.ack().await.unwrap();
+	}
+
+	// Reopen the messenger and verify that the unacknowledged messages are delivered again.
+	let messenger = Messenger::new(&path).unwrap();
+	let stream = messenger.get_stream("stream".to_owned()).await.unwrap();
+	let consumer = stream.get_consumer("consumer".to_owned()).await.unwrap();
+	assert_eq!(consumer.info().await.unwrap().sequence, 1);
+	let messages = receive(&consumer, 2).await;
+	assert_eq!(payloads_of(&messages), ["b", "c"]);
+}
+
+/// Test that a consumer's sequence advances only past contiguously acknowledged messages, and that acknowledged messages are removed from a work queue.
+#[tokio::test]
+async fn ack_advancement() {
+	let temp = Temp::new();
+	std::fs::create_dir_all(temp.path()).unwrap();
+	let messenger = Messenger::new(&temp.path().join("messenger")).unwrap();
+	let config = StreamConfig {
+		retention: RetentionPolicy::WorkQueue,
+		..Default::default()
+	};
+	let stream = messenger
+		.create_stream("stream".to_owned(), config)
+		.await
+		.unwrap();
+	let consumer = stream
+		.create_consumer("consumer".to_owned(), ConsumerConfig::default())
+		.await
+		.unwrap();
+	let payloads = ["a", "b", "c"].map(Bytes::from).to_vec();
+	stream.batch_publish(payloads).await.unwrap();
+	let mut messages = receive(&consumer, 3).await.into_iter();
+	let first = messages.next().unwrap();
+	let second = messages.next().unwrap();
+	let third = messages.next().unwrap();
+
+	// Acknowledge the last two messages. The sequence must not advance past the first.
+	third.acker.ack().await.unwrap();
+	second.acker.ack().await.unwrap();
+	assert_eq!(consumer.info().await.unwrap().sequence, 0);
+	assert_eq!(stream.info().await.unwrap().first_sequence, 1);
+
+	// Acknowledge the first message. The sequence must advance past all three.
+	first.acker.ack().await.unwrap();
+	assert_eq!(consumer.info().await.unwrap().sequence, 3);
+	let info = stream.info().await.unwrap();
+	assert_eq!(info.first_sequence, 4);
+	assert_eq!(info.last_sequence, 3);
+
+	// Verify that the removed messages were subtracted from the stream's size.
+	let (messages, bytes) = size(&messenger, "stream").await;
+	assert_eq!((messages, bytes), (0, 0));
+}
+
+/// Test the discard policies when a stream reaches its limits.
+#[tokio::test]
+async fn discard_policy() {
+	let temp = Temp::new();
+	std::fs::create_dir_all(temp.path()).unwrap();
+	let messenger = Messenger::new(&temp.path().join("messenger")).unwrap();
+
+	// Verify that the oldest messages are discarded to make room with the old discard policy.
+	let config = StreamConfig {
+		discard: DiscardPolicy::Old,
+		max_messages: Some(2),
+		..Default::default()
+	};
+	let stream = messenger
+		.create_stream("old".to_owned(), config)
+		.await
+		.unwrap();
+	let payloads = ["a", "b", "c"].map(Bytes::from).to_vec();
+	let ids = stream.batch_publish(payloads).await.unwrap();
+	assert_eq!(ids, [1, 2, 3]);
+	let info = stream.info().await.unwrap();
+	assert_eq!((info.first_sequence, info.last_sequence), (2, 3));
+	assert_eq!(size(&messenger, "old").await, (2, 2));
+
+	// Verify that new messages are rejected with the new discard policy.
+	let config = StreamConfig {
+		discard: DiscardPolicy::New,
+		max_bytes: Some(4),
+		..Default::default()
+	};
+	let stream = messenger
+		.create_stream("new".to_owned(), config)
+		.await
+		.unwrap();
+	let payloads = ["ab", "cd", "ef"].map(Bytes::from).to_vec();
+	let ids = stream.batch_publish(payloads).await.unwrap();
+	assert_eq!(ids, [1, 2]);
+	let result = stream.publish(Bytes::from_static(b"g")).await;
+	assert!(matches!(result, Err(Error::MaxMessages)));
+	let info = stream.info().await.unwrap();
+	assert_eq!((info.first_sequence, info.last_sequence), (1, 2));
+	assert_eq!(size(&messenger, "new").await, (2, 4));
+}
+
+async fn receive(consumer: &super::Consumer, count: usize) -> Vec<Message> {
+	consumer
+		.subscribe()
+		.await
+		.unwrap()
+		.take(count)
+		.try_collect()
+		.await
+		.unwrap()
+}
+
+fn payloads_of(messages: &[Message]) -> Vec<&str> {
+	messages
+		.iter()
+		.map(|message| std::str::from_utf8(&message.payload).unwrap())
+		.collect()
+}
+
+async fn size(messenger: &Messenger, stream: &str) -> (u64, u64) {
+	let stream = stream.to_owned();
+	super::run(&messenger.database, move |connection| {
+		connection.query_row(
+			"select messages, bytes from streams where name = ?1;",
+			[&stream],
+			|row| Ok((row.get::<_, u64>(0)?, row.get::<_, u64>(1)?)),
+		)
+	})
+	.await
+	.unwrap()
+}
@@ -12,10 +12,18 @@ mod linux;
 #[derive(Debug, Clone, clap::Args)]
 #[allow(dead_code)]
 pub struct Command {
+	/// The delegated cgroup to create the sandbox's cgroup in. This is required to limit cpu, memory, or pids.
+	#[arg(long)]
+	pub cgroup: Option<PathBuf>,
+
 	/// Provide a path for the chroot.
 	#[arg(long)]
 	pub chroot: Option<PathBuf>,
 
+	/// Limit the CPU quota in millicores, where 1000 is one core.
+	#[arg(long)]
+	pub cpu: Option<u64>,
+
 	/// Change the working directory prior to spawn.
 	#[arg(short = 'C', long)]
 	pub cwd: Option<PathBuf>,
@@ -32,6 +40,10 @@ pub struct Command {
 	#[arg(long)]
 	pub hostname: Option<String>,
 
+	/// Limit the memory in bytes.
+	#[arg(long)]
+	pub memory: Option<u64>,
+
 	/// File systems to be mounted.
 	#[arg(short, long = "mount", num_args = 1, value_parser = parse_mount, action = clap::ArgAction::Append)]
 	pub mounts: Vec<Mount>,
@@ -40,6 +52,18 @@ pub struct Command {
 	#[arg(long)]
 	pub network: bool,
 
+	/// Limit the number of open files.
+	#[arg(long)]
+	pub open_files: Option<u64>,
+
+	/// Limit the number of processes and threads.
+	#[arg(long)]
+	pub pids: Option<u64>,
+
+	/// A path to write the name of the limit that was exceeded to, if any.
+	#[arg(long)]
+	pub report: Option<PathBuf>,
+
 	#[arg(index = 2, trailing_var_arg = true)]
 	pub trailing: Vec<String>,
 
@@ -9,9 +9,13 @@ use std::{
 	io::Write,
 };
 
+mod cgroup;
 mod guest;
 mod root;
 
+// The clone3 flag to spawn a process directly into a cgroup.
+const CLONE_INTO_CGROUP: u64 = 0x2_0000_0000;
+
 #[derive(Debug)]
 struct Mount {
 	source: Option<CString>,
@@ -30,6 +34,7 @@ struct Context {
 	root: Option<CString>,
 	mounts: Vec<Mount>,
 	network: bool,
+	open_files: Option<u64>,
 	socket: std::os::unix::net::UnixStream,
 }
 
@@ -106,14 +111,22 @@ pub fn spawn(mut command: Command) -> std::io::Result<std::process::ExitCode> {
 		root,
 		mounts,
 		network: command.network,
+		open_files: command.open_files,
 		socket: child_socket,
 	};
 
+	// Create the cgroup if necessary.
+	let cgroup = cgroup::Cgroup::new(&command)?;
+
 	// Fork.
+	let mut flags: u64 = (libc::CLONE_NEWUSER | libc::CLONE_NEWPID)
+		.try_into()
+		.unwrap();
+	if cgroup.is_some() {
+		flags |= CLONE_INTO_CGROUP;
+	}
 	let mut clone_args: libc::clone_args = libc::clone_args {
-		flags: (libc::CLONE_NEWUSER | libc::CLONE_NEWPID)
-			.try_into()
-			.unwrap(),
+		flags,
 		stack: 0,
 		stack_size: 0,
 		pidfd: 0,
@@ -123,7 +136,9 @@ pub fn spawn(mut command: Command) -> std::io::Result<std::process::ExitCode> {
 		tls: 0,
 		set_tid: 0,
 		set_tid_size: 0,
-		cgroup: 0,
+		cgroup: cgroup
+			.as_ref()
+			.map_or(0, |cgroup| cgroup.fd().try_into().unwrap()),
 	};
 	let root_pid = unsafe {
 		libc::syscall(
@@ -159,12 +174,28 @@ pub fn spawn(mut command: Command) -> std::io::Result<std::process::ExitCode> {
 
 	// Wait for the root process to exit.
 	let mut status: libc::c_int = 0;
-	let ret = unsafe { libc::waitpid(pid, std::ptr::addr_of_mut!(status), libc::__WALL) };
+	let mut rusage = unsafe { std::mem::zeroed::<libc::rusage>() };
+	let ret = unsafe {
+		libc::wait4(
+			pid,
+			std::ptr::addr_of_mut!(status),
+			libc::__WALL,
+			std::ptr::addr_of_mut!(rusage),
+		)
+	};
 	if ret == -1 {
 		eprintln!("wait failed");
 		return Err(std::io::Error::last_os_error());
 	}
 
+	// Write the report.
+	if let Some(path) = &command.report {
+		let exceeded = cgroup.as_ref().and_then(cgroup::Cgroup::exceeded);
+		let report = report(&rusage, cgroup.as_ref(), exceeded);
+		std::fs::write(path, report).inspect_err(|_| eprintln!("failed to write the report"))?;
+	}
+	drop(cgroup);
+
 	let status = if libc::WIFEXITED(status) {
 		libc::WEXITSTATUS(status).to_u8().unwrap().into()
 	} else if libc::WIFSIGNALED(status) {
@@ -215,6 +246,34 @@ fn try_start(
 	Ok(())
 }
 
+fn report(
+	rusage: &libc::rusage,
+	cgroup: Option<&cgroup::Cgroup>,
+	exceeded: Option<&str>,
+) -> String {
+	let microseconds = |time: libc::timeval| {
+		time.tv_sec.to_u64().unwrap() * 1_000_000 + time.tv_usec.to_u64().unwrap()
+	};
+	let cpu_system = microseconds(rusage.ru_stime);
+	let cpu_user = microseconds(rusage.ru_utime);
+
+	// Prefer the cgroup's peak memory, since the max RSS is only that of the largest process.
+	let max_rss = cgroup
+		.and_then(cgroup::Cgroup::memory_peak)
+		.unwrap_or_else(|| rusage.ru_maxrss.to_u64().unwrap() * 1024);
+
+	// The block counts are in units of 512 bytes.
+	let read_bytes = rusage.ru_inblock.to_u64().unwrap() * 512;
+	let written_bytes = rusage.ru_oublock.to_u64().unwrap() * 512;
+
+	let exceeded = exceeded
+		.map(|exceeded| format!("exceeded {exceeded}\n"))
+		.unwrap_or_default();
+	format!(
+		"cpu_system {cpu_system}\ncpu_user {cpu_user}\nmax_rss {max_rss}\nread_bytes {read_bytes}\nwritten_bytes {written_bytes}\n{exceeded}"
+	)
+}
+
 fn get_user(name: Option<impl AsRef<OsStr>>) -> std::io::Result<(libc::uid_t, libc::gid_t)> {
 	let Some(name) = name else {
 		unsafe {
@@ -0,0 +1,136 @@
+use crate::Command;
+use std::{
+	os::fd::{AsRawFd as _, RawFd},
+	path::{Path, PathBuf},
+};
+
+// The period used for the CPU quota, in microseconds.
+const CPU_PERIOD: u64 = 100_000;
+
+pub struct Cgroup {
+	directory: std::fs::File,
+	path: PathBuf,
+}
+
+impl Cgroup {
+	pub fn new(command: &Command) -> std::io::Result<Option<Self>> {
+		// Only create a cgroup if a limit requires one.
+		if command.cpu.is_none() && command.memory.is_none() && command.pids.is_none() {
+			return Ok(None);
+		}
+
+		// Get the parent cgroup. It must be delegated and contain no processes, because cgroup v2 does not allow enabling controllers for the children of a cgroup that contains processes.
+		let parent = command.cgroup.clone().ok_or_else(|| {
+			eprintln!("a delegated cgroup is required to limit cpu, memory, or pids");
+			std::io::Error::other("expected a cgroup")
+		})?;
+
+		// Enable the controllers for the parent cgroup's children.
+		let mut controllers = Vec::new();
+		if command.cpu.is_some() {
+			controllers.push("cpu");
+		}
+		if command.memory.is_some() {
+			controllers.push("memory");
+		}
+		if command.pids.is_some() {
+			controllers.push("pids");
+		}
+		enable_controllers(&parent, &controllers)?;
+
+		// Create the cgroup.
+		let path = parent.join(format!("tangram-sandbox-{}", std::process::id()));
+		std::fs::create_dir(&path).inspect_err(|_| eprintln!("failed to create the cgroup"))?;
+		let directory = std::fs::File::open(&path)
+			.inspect_err(|_| eprintln!("failed to open the cgroup directory"))?;
+		let cgroup = Self { directory, path };
+
+		// Set the CPU quota.
+		if let Some(cpu) = command.cpu {
+			let quota = (cpu * CPU_PERIOD / 1000).max(1000);
+			std::fs::write(cgroup.path.join("cpu.max"), format!("{quota} {CPU_PERIOD}"))
+				.inspect_err(|_| eprintln!("failed to set the cpu limit"))?;
+		}
+
+		// Set the memory limit, disable swap, and kill the whole cgroup if the limit is exceeded.
+		if let Some(memory) = command.memory {
+			std::fs::write(cgroup.path.join("memory.max"), memory.to_string())
+				.inspect_err(|_| eprintln!("failed to set the memory limit"))?;
+			std::fs::write(cgroup.path.join("memory.swap.max"), "0").ok();
+			std::fs::write(cgroup.path.join("memory.oom.group"), "1").ok();
+		}
+
+		// Set the pids limit.
+		if let Some(pids) = command.pids {
+			std::fs::write(cgroup.path.join("pids.max"), pids.to_string())
+				.inspect_err(|_| eprintln!("failed to set the pids limit"))?;
+		}
+
+		Ok(Some(cgroup))
+	}
+
+	pub fn fd(&self) -> RawFd {
+		self.directory.as_raw_fd()
+	}
+
+	/// Get the name of the limit that was hit, if any. A memory limit kills the cgroup, but a pids limit only causes a fork to fail, so the caller must decide whether the process failed because of it.
+	pub fn exceeded(&self) -> Option<&'static str> {
+		if read_event(&self.path.join("memory.events"), "oom_kill").is_some_and(|n| n > 0) {
+			return Some("memory");
+		}
+		if read_event(&self.path.join("pids.events"), "max").is_some_and(|n| n > 0) {
+			return Some("pids");
+		}
+		None
+	}
+
+	/// Get the peak memory usage in bytes, if the memory controller is enabled.
+	pub fn memory_peak(&self) -> Option<u64> {
+		let contents = std::fs::read_to_string(self.path.join("memory.peak")).ok()?;
+		contents.trim().parse().ok()
+	}
+}
+
+impl Drop for Cgroup {
+	fn drop(&mut self) {
+		// Kill any remaining processes and remove the cgroup.
+		std::fs::write(self.path.join("cgroup.kill"), "1").ok();
+		for _ in 0..100 {
+			if std::fs::remove_dir(&self.path).is_ok() {
+				break;
+			}
+			std::thread::sleep(std::time::Duration::from_millis(10));
+		}
+	}
+}
+
+fn enable_controllers(parent: &Path, controllers: &[&str]) -> std::io::Result<()> {
+	// Skip the controllers that are already enabled.
+	let enabled = std::fs::read_to_string(parent.join("cgroup.subtree_control"))
+		.inspect_err(|_| eprintln!("failed to read the cgroup's subtree control"))?;
+	let enabled = enabled.split_whitespace().collect::<Vec<_>>();
+	let controllers = controllers
+		.iter()
+		.filter(|controller| !enabled.contains(controller))
+		.map(|controller| format!("+{controller}"))
+		.collect::<Vec<_>>();
+	if controllers.is_empty() {
+		return Ok(());
+	}
+	std::fs::write(parent.join("cgroup.subtree_control"), controllers.join(" ")).inspect_err(
+		|_| {
+			eprintln!(
+				"failed to enable the cgroup controllers, the parent cgroup must be delegated and must not contain processes"
+			);
+		},
+	)?;
+	Ok(())
+}
+
+fn read_event(path: &Path, name: &str) -> Option<u64> {
+	let contents = std::fs::read_to_string(path).ok()?;
+	contents.lines().find_map(|line| {
+		let (key, value) = line.split_once(' ')?;
+		(key == name).then(|| value.trim().parse().ok())?
+	})
+}
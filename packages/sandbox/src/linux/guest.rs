@@ -37,6 +37,18 @@ pub fn main(mut context: Context) -> ! {
 			abort_errno!("failed to set the working directory");
 		}
 
+		// Limit the number of open files.
+		if let Some(open_files) = context.open_files {
+			let limit = libc::rlimit {
+				rlim_cur: open_files,
+				rlim_max: open_files,
+			};
+			let ret = libc::setrlimit(libc::RLIMIT_NOFILE, std::ptr::addr_of!(limit));
+			if ret == -1 {
+				abort_errno!("failed to limit the number of open files");
+			}
+		}
+
 		// Finally, exec the process.
 		libc::execve(
 			context.executable.as_ptr(),
@@ -0,0 +1,149 @@
+use crate::Server;
+use indoc::formatdoc;
+use itertools::Itertools as _;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_http::{Body, request::Ext as _};
+
+impl Server {
+	pub(crate) async fn authorize(&self, request: &http::Request<Body>) -> tg::Result<()> {
+		// Allow every request if authorization is not enabled.
+		let Some(config) = &self.config.authorization else {
+			return Ok(());
+		};
+
+		// Allow the request if it does not require a scope.
+		let Some(scope) = Self::get_request_scope(request)? else {
+			return Ok(());
+		};
+
+		// Allow the request if anonymous requests are granted the scope.
+		if config.anonymous_scopes.contains(&scope) {
+			return Ok(());
+		}
+
+		// Get the token.
+		let Some(token) = request.token(None) else {
+			return Err(tg::error!(
+				code = tg::error::Code::Unauthorized,
+				%scope,
+				"a token is required"
+			));
+		};
+
+		// Get the token's scopes.
+		let Some(scopes) = self.try_get_token_scopes(token).await? else {
+			return Err(tg::error!(
+				code = tg::error::Code::Unauthorized,
+				"the token is invalid"
+			));
+		};
+
+		// Verify that the token has the scope.
+		if !scopes.contains(&scope) {
+			return Err(tg::error!(
+				code = tg::error::Code::Forbidden,
+				%scope,
+				"the token does not have the required scope"
+			));
+		}
+
+		Ok(())
+	}
+
+	async fn try_get_token_scopes(&self, token: &str) -> tg::Result<Option<Vec<tg::token::Scope>>> {
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Get the token's scopes.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			scopes: db::value::Json<Vec<tg::token::Scope>>,
+		}
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select scopes
+				from tokens
+				where hash = {p}1 and (expires_at is null or expires_at > {p}2);
+			"
+		);
+		let hash = Self::hash_token(token);
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let params = db::params![hash, now];
+		let row = connection
+			.query_optional_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
+		// Drop the database connection.
+		drop(connection);
+
+		let scopes = row.map(|row| row.scopes.0);
+
+		Ok(scopes)
+	}
+
+	fn get_request_scope(request: &http::Request<Body>) -> tg::Result<Option<tg::token::Scope>> {
+		let method = request.method().clone();
+		let path = request.uri().path();
+		let path_components = path.split('/').skip(1).collect_vec();
+		let scope = match (method, path_components.as_slice()) {
+			// Requests that are allowed without a scope.
+			(http::Method::GET, ["health" | "user"]) => return Ok(None),
+
+			// Requests that read objects, processes, and tags.
+			(http::Method::POST, ["check" | "checkout" | "document" | "export" | "lsp"])
+			| (http::Method::GET, ["_" | "blobs" | "objects" | "tags", ..])
+			| (http::Method::GET, ["processes", _, ..])
+			| (http::Method::POST, ["processes", _, "wait"]) => tg::token::Scope::ReadObjects,
+
+			// Requests that write objects.
+			(
+				http::Method::POST,
+				["blobs" | "cache" | "checkin" | "format" | "import" | "index" | "pull"],
+			)
+			| (http::Method::PUT, ["objects" | "processes", _])
+			| (http::Method::POST, ["objects", _, "touch"])
+			| (http::Method::POST, ["processes", _, "touch"]) => tg::token::Scope::WriteObjects,
+
+			// Requests that spawn and run processes.
+			(http::Method::POST, ["processes" | "pipes" | "ptys", ..])
+			| (http::Method::GET, ["pipes" | "ptys", ..]) => tg::token::Scope::SpawnProcesses,
+
+			// Requests that put or delete tags.
+			(http::Method::PUT | http::Method::DELETE, ["tags", ..]) => tg::token::Scope::WriteTags,
+
+			// Requests that manage remotes or send objects to them with the remotes' tokens.
+			(http::Method::GET | http::Method::PUT | http::Method::DELETE, ["remotes", ..])
+			| (http::Method::POST, ["push"]) => tg::token::Scope::ManageRemotes,
+
+			// Requests that clean, repair, or rebuild the store and index.
+			(http::Method::POST, ["clean" | "fsck"])
+			| (http::Method::POST, ["index", "rebuild"]) => tg::token::Scope::Administer,
+
+			// Requests that read metrics.
+			(http::Method::GET, ["metrics"]) => tg::token::Scope::ReadMetrics,
+
+			// Requests that manage tokens.
+			(http::Method::GET | http::Method::POST | http::Method::DELETE, ["tokens", ..]) => {
+				tg::token::Scope::ManageTokens
+			},
+
+			// Deny any other request.
+			(method, _) => {
+				return Err(tg::error!(
+					code = tg::error::Code::Forbidden,
+					%method,
+					%path,
+					"the request is not permitted"
+				));
+			},
+		};
+		Ok(Some(scope))
+	}
+}
@@ -7,7 +7,7 @@ use std::{
 	panic::AssertUnwindSafe,
 	path::{Path, PathBuf},
 	sync::Arc,
-	time::Instant,
+	time::{Instant, SystemTime},
 };
 use tangram_client as tg;
 use tangram_either::Either;
@@ -18,9 +18,11 @@ use tokio_util::task::AbortOnDropHandle;
 
 mod input;
 mod lockfile;
+mod metadata;
 mod object;
 mod output;
 mod unify;
+mod watch;
 
 struct State {
 	arg: tg::checkin::Arg,
@@ -31,6 +33,7 @@ struct State {
 	lockfile: Option<Lockfile>,
 	ignorer: Option<ignore::Ignorer>,
 	progress: crate::progress::Handle<tg::checkin::Output>,
+	watch: Option<watch::Watch>,
 }
 
 struct GraphObject {
@@ -110,12 +113,17 @@ impl Server {
 	) -> tg::Result<
 		impl Stream<Item = tg::Result<tg::progress::Event<tg::checkin::Output>>> + Send + 'static,
 	> {
+		// If this is a watch, then check in the artifact each time it changes.
+		if arg.watch {
+			return Ok(self.checkin_watch(arg).left_stream());
+		}
+
 		let progress = crate::progress::Handle::new();
 		let task = AbortOnDropHandle::new(tokio::spawn({
 			let server = self.clone();
 			let progress = progress.clone();
 			async move {
-				let result = AssertUnwindSafe(server.checkin_inner(arg, &progress))
+				let result = AssertUnwindSafe(server.checkin_inner(arg, None, &progress))
 					.catch_unwind()
 					.await;
 				match result {
@@ -135,7 +143,7 @@ impl Server {
 				}
 			}
 		}));
-		let stream = progress.stream().attach(task);
+		let stream = progress.stream().attach(task).right_stream();
 		Ok(stream)
 	}
 
@@ -143,6 +151,7 @@ impl Server {
 	async fn checkin_inner(
 		&self,
 		mut arg: tg::checkin::Arg,
+		watch: Option<&mut watch::Watch>,
 		progress: &crate::progress::Handle<tg::checkin::Output>,
 	) -> tg::Result<tg::checkin::Output> {
 		// Validate the arg.
@@ -188,12 +197,13 @@ impl Server {
 			return Ok(output);
 		}
 
-		self.checkin_new(arg, progress).await
+		self.checkin_new(arg, watch, progress).await
 	}
 
 	async fn checkin_new(
 		&self,
 		arg: tg::checkin::Arg,
+		watch: Option<&mut watch::Watch>,
 		progress: &crate::progress::Handle<tg::checkin::Output>,
 	) -> tg::Result<tg::checkin::Output> {
 		// Create the ignorer if necessary.
@@ -243,6 +253,7 @@ impl Server {
 			lockfile,
 			ignorer,
 			progress: progress.clone(),
+			watch: watch.as_deref().cloned(),
 		};
 
 		// Spawn the fixup task.
@@ -251,6 +262,7 @@ impl Server {
 				.map(|result| result.unwrap());
 
 		// Collect input.
+		let started_at = SystemTime::now();
 		let start = Instant::now();
 		let mut state = tokio::task::spawn_blocking({
 			let server = self.clone();
@@ -270,6 +282,12 @@ impl Server {
 		// Remove the ignorer.
 		state.ignorer.take();
 
+		// Remove the watch.
+		state.watch.take();
+
+		// If this is a watch, then keep the input graph so that the next checkin only reads the paths that changed.
+		let input = watch.is_some().then(|| state.graph.clone());
+
 		// Unify.
 		if !(state.arg.deterministic || state.arg.locked) {
 			let start = Instant::now();
@@ -360,6 +378,40 @@ impl Server {
 
 		let state = Arc::into_inner(state).unwrap();
 
+		// Record the blobs of the files that were read so that later checkins can reuse them.
+		if !state.arg.destructive {
+			self.checkin_put_blobs_by_metadata(&state, started_at)
+				.await
+				.inspect_err(|error| tracing::warn!(?error, "failed to record the file blobs"))
+				.ok();
+		}
+
+		// If this is a watch, then keep the input graph with the files' blobs.
+		if let (Some(watch), Some(mut graph)) = (watch, input) {
+			for node in graph.nodes.iter_mut() {
+				let path = node.path.clone();
+				let Ok(file) = node.variant.try_unwrap_file_mut() else {
+					continue;
+				};
+				let blob = path
+					.and_then(|path| state.graph.paths.get(&*path).copied())
+					.and_then(|index| {
+						state.graph.nodes[index]
+							.variant
+							.try_unwrap_file_ref()
+							.ok()?
+							.blob
+							.as_ref()
+					});
+				file.blob = blob.map(|blob| match blob {
+					Blob::Create(blob) => Blob::Id(blob.id.clone()),
+					Blob::Id(id) => Blob::Id(id.clone()),
+				});
+			}
+			watch.graph.replace(graph);
+			watch.changed.clear();
+		}
+
 		// Find the desired item in the graph.
 		let node = state
 			.graph
@@ -22,6 +22,16 @@ impl Server {
 			return Ok(Some(*index));
 		}
 
+		// If this is a watch and the path is unchanged since the last checkin, then reuse its node.
+		if let Some(node) = state
+			.watch
+			.as_ref()
+			.and_then(|watch| watch.try_get_node(&path))
+			.cloned()
+		{
+			return self.checkin_visit_unchanged(state, path, node);
+		}
+
 		// Get the object ID if this is under the artifacts directory.
 		let id = path
 			.strip_prefix(&state.artifacts_path)
@@ -140,6 +150,15 @@ impl Server {
 		names.sort_unstable();
 
 		// Visit the children.
+		self.checkin_visit_directory_entries(state, index, names)
+	}
+
+	fn checkin_visit_directory_entries(
+		&self,
+		state: &mut State,
+		index: usize,
+		names: Vec<String>,
+	) -> tg::Result<()> {
 		for name in names {
 			let path = state.graph.nodes[index].path().join(&name);
 			let Some(child_index) = self.checkin_visit(state, path)? else {
@@ -343,6 +362,142 @@ impl Server {
 		Ok(())
 	}
 
+	fn checkin_visit_unchanged(
+		&self,
+		state: &mut State,
+		path: PathBuf,
+		previous: Node,
+	) -> tg::Result<Option<usize>> {
+		let metadata = previous
+			.metadata
+			.ok_or_else(|| tg::error!(%path = path.display(), "expected the metadata"))?;
+
+		// Skip ignored files.
+		if state.ignorer.as_mut().is_some_and(|ignorer| {
+			ignorer
+				.matches(&path, Some(metadata.is_dir()))
+				.unwrap_or_default()
+		}) {
+			return Ok(None);
+		}
+
+		// Create the variant without its edges.
+		let variant = match &previous.variant {
+			Variant::Directory(_) => Variant::Directory(Directory {
+				entries: Vec::new(),
+			}),
+			Variant::File(file) => Variant::File(File {
+				blob: file.blob.clone(),
+				dependencies: Vec::new(),
+				executable: file.executable,
+			}),
+			Variant::Symlink(symlink) => Variant::Symlink(Symlink {
+				artifact: None,
+				path: symlink.path.clone(),
+			}),
+			Variant::Object => return Err(tg::error!("unreachable")),
+		};
+
+		// Send the path to the fixup task.
+		if let Some(fixup_sender) = &state.fixup_sender {
+			fixup_sender.send((path.clone(), metadata.clone())).ok();
+		}
+
+		// Get the node index.
+		let index = state.graph.nodes.len();
+
+		// Update the path.
+		state.graph.paths.insert(path.clone(), index);
+
+		// Lookup the lockfile node.
+		let lockfile_node = state
+			.lockfile
+			.as_ref()
+			.and_then(|lockfile| lockfile.get_node_for_path(&path).ok());
+
+		// Create the node.
+		let node = Node {
+			id: previous.id,
+			lockfile_index: lockfile_node,
+			variant,
+			metadata: Some(metadata),
+			object: None,
+			path: Some(Arc::new(path)),
+			parent: None,
+			root: None,
+			tag: None,
+		};
+		state.graph.nodes.push_back(node);
+
+		// Visit the edges, whose indices refer to the last checkin's graph.
+		match previous.variant {
+			Variant::Directory(directory) => {
+				let names = directory
+					.entries
+					.into_iter()
+					.map(|(name, _)| name)
+					.collect();
+				self.checkin_visit_directory_entries(state, index, names)?;
+			},
+			Variant::File(file) => {
+				let mut dependencies = Vec::with_capacity(file.dependencies.len());
+				for (reference, referent) in file.dependencies {
+					let referent = match referent {
+						Some(tg::Referent {
+							item: Either::Right(item),
+							path,
+							tag,
+						}) => self.checkin_visit_unchanged_edge(state, item)?.map(|item| {
+							tg::Referent {
+								item: Either::Right(item),
+								path,
+								tag,
+							}
+						}),
+						referent => referent,
+					};
+					dependencies.push((reference, referent));
+				}
+				state.graph.nodes[index]
+					.variant
+					.unwrap_file_mut()
+					.dependencies = dependencies;
+			},
+			Variant::Symlink(symlink) => {
+				let artifact = match symlink.artifact {
+					Some(Either::Right(artifact)) => {
+						let artifact = self
+							.checkin_visit_unchanged_edge(state, artifact)?
+							.ok_or_else(|| tg::error!("failed to visit dependency"))?;
+						Some(Either::Right(artifact))
+					},
+					artifact => artifact,
+				};
+				state.graph.nodes[index]
+					.variant
+					.unwrap_symlink_mut()
+					.artifact = artifact;
+			},
+			Variant::Object => return Err(tg::error!("unreachable")),
+		}
+
+		Ok(Some(index))
+	}
+
+	fn checkin_visit_unchanged_edge(
+		&self,
+		state: &mut State,
+		index: usize,
+	) -> tg::Result<Option<usize>> {
+		let path = state
+			.watch
+			.as_ref()
+			.and_then(|watch| watch.try_get_path(index))
+			.ok_or_else(|| tg::error!("failed to get the path of the dependency"))?
+			.to_owned();
+		self.checkin_visit(state, path)
+	}
+
 	fn checkin_find_roots(state: &mut State) {
 		let mut visited = vec![false; state.graph.nodes.len()];
 		let mut stack = vec![(0, None::<usize>)];
@@ -494,6 +649,7 @@ mod tests {
 					locked: false,
 					lockfile: true,
 					updates: Vec::new(),
+					watch: false,
 				},
 				artifacts_path: server.artifacts_path(),
 				fixup_sender: None,
@@ -502,6 +658,7 @@ mod tests {
 				lockfile: None,
 				ignorer: None,
 				progress,
+				watch: None,
 			};
 
 			let state = tokio::task::spawn_blocking(move || {
@@ -0,0 +1,233 @@
+use super::{Blob, State};
+use crate::Server;
+use indoc::formatdoc;
+use itertools::Itertools as _;
+use std::{collections::HashMap, os::unix::fs::MetadataExt as _, time::SystemTime};
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+
+#[cfg(test)]
+mod tests;
+
+/// The maximum number of files to look up in one statement.
+const BATCH_SIZE: usize = 256;
+
+/// The metadata that identifies an unchanged file.
+#[derive(Debug, PartialEq, Eq)]
+struct Key {
+	device: i64,
+	inode: i64,
+	size: i64,
+	mtime: i64,
+	ctime: i64,
+	mode: i64,
+}
+
+impl Key {
+	fn new(metadata: &std::fs::Metadata) -> Self {
+		Self {
+			device: metadata.dev().cast_signed(),
+			inode: metadata.ino().cast_signed(),
+			size: metadata.size().cast_signed(),
+			mtime: metadata.mtime() * 1_000_000_000 + metadata.mtime_nsec(),
+			ctime: metadata.ctime() * 1_000_000_000 + metadata.ctime_nsec(),
+			mode: metadata.mode().into(),
+		}
+	}
+
+	/// Whether the file was changed too recently for its metadata to be trusted. A file that is written again within the timestamp granularity of its file system would keep the same metadata, so only files that were last changed at least a second before the checkin started are recorded.
+	fn is_racy(&self, started_at: SystemTime) -> bool {
+		let Ok(started_at) = started_at.duration_since(SystemTime::UNIX_EPOCH) else {
+			return true;
+		};
+		let started_at = i64::try_from(started_at.as_nanos()).unwrap_or(i64::MAX);
+		self.mtime.max(self.ctime) > started_at - 1_000_000_000
+	}
+}
+
+impl Server {
+	pub(super) async fn checkin_try_get_blobs_by_metadata(
+		&self,
+		files: &[(usize, &std::fs::Metadata)],
+	) -> tg::Result<HashMap<usize, tg::blob::Id, fnv::FnvBuildHasher>> {
+		// Get the blobs of the files whose metadata is unchanged.
+		let mut candidates = Vec::new();
+		for files in files.chunks(BATCH_SIZE) {
+			let keys = files
+				.iter()
+				.map(|(index, metadata)| (*index, Key::new(metadata)))
+				.collect::<Vec<_>>();
+			let rows = self
+				.checkin_get_files_batch(&keys)
+				.await?
+				.into_iter()
+				.map(|(key, blob)| ((key.device, key.inode), (key, blob)))
+				.collect::<HashMap<_, _, fnv::FnvBuildHasher>>();
+			for (index, key) in keys {
+				let blob = rows
+					.get(&(key.device, key.inode))
+					.and_then(|(key_, blob)| (*key_ == key).then(|| blob.clone()));
+				if let Some(blob) = blob {
+					candidates.push((index, blob));
+				}
+			}
+		}
+		if candidates.is_empty() {
+			return Ok(HashMap::default());
+		}
+
+		// Only use the blobs that are complete, so that a blob whose leaves were removed is not reused.
+		let ids = candidates
+			.iter()
+			.map(|(_, blob)| blob.clone().into())
+			.collect::<Vec<tg::object::Id>>();
+		let completes = self.try_get_object_complete_batch(&ids).await?;
+		let blobs = candidates
+			.into_iter()
+			.zip(completes)
+			.filter_map(|(candidate, complete)| complete.unwrap_or(false).then_some(candidate))
+			.collect();
+
+		Ok(blobs)
+	}
+
+	async fn checkin_get_files_batch(
+		&self,
+		keys: &[(usize, Key)],
+	) -> tg::Result<Vec<(Key, tg::blob::Id)>> {
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Get the files.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			device: i64,
+			inode: i64,
+			size: i64,
+			mtime: i64,
+			ctime: i64,
+			mode: i64,
+			blob: tg::blob::Id,
+		}
+		let p = connection.p();
+		let values = (0..keys.len())
+			.map(|index| format!("({p}{}, {p}{})", 2 * index + 1, 2 * index + 2))
+			.join(", ");
+		let statement = formatdoc!(
+			"
+				select device, inode, size, mtime, ctime, mode, blob
+				from checkin_files
+				where (device, inode) in (values {values});
+			"
+		);
+		let params = keys
+			.iter()
+			.flat_map(|(_, key)| db::params![key.device, key.inode])
+			.collect();
+		let rows = connection
+			.query_all_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
+		// Drop the database connection.
+		drop(connection);
+
+		let files = rows
+			.into_iter()
+			.map(|row| {
+				let key = Key {
+					device: row.device,
+					inode: row.inode,
+					size: row.size,
+					mtime: row.mtime,
+					ctime: row.ctime,
+					mode: row.mode,
+				};
+				(key, row.blob)
+			})
+			.collect();
+
+		Ok(files)
+	}
+
+	pub(super) async fn checkin_put_blobs_by_metadata(
+		&self,
+		state: &State,
+		started_at: SystemTime,
+	) -> tg::Result<()> {
+		// Collect the files whose blobs were created by this checkin.
+		let entries = state
+			.graph
+			.nodes
+			.iter()
+			.filter_map(|node| {
+				let file = node.variant.try_unwrap_file_ref().ok()?;
+				let Some(Blob::Create(blob)) = &file.blob else {
+					return None;
+				};
+				let key = Key::new(node.metadata.as_ref()?);
+				if key.is_racy(started_at) {
+					return None;
+				}
+				Some((key, blob.id.clone()))
+			})
+			.unique_by(|(key, _)| (key.device, key.inode))
+			.collect::<Vec<_>>();
+		if entries.is_empty() {
+			return Ok(());
+		}
+
+		// Get a database connection.
+		let connection = self
+			.database
+			.write_connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Insert the entries.
+		let p = connection.p();
+		for entries in entries.chunks(BATCH_SIZE) {
+			let values = (0..entries.len())
+				.map(|index| {
+					let placeholders = (1..=7)
+						.map(|column| format!("{p}{}", 7 * index + column))
+						.join(", ");
+					format!("({placeholders})")
+				})
+				.join(", ");
+			let statement = formatdoc!(
+				"
+					insert into checkin_files (device, inode, size, mtime, ctime, mode, blob)
+					values {values}
+					on conflict (device, inode) do update set
+						size = excluded.size,
+						mtime = excluded.mtime,
+						ctime = excluded.ctime,
+						mode = excluded.mode,
+						blob = excluded.blob;
+				"
+			);
+			let params = entries
+				.iter()
+				.flat_map(|(key, blob)| {
+					db::params![
+						key.device, key.inode, key.size, key.mtime, key.ctime, key.mode, blob,
+					]
+				})
+				.collect();
+			connection
+				.execute(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+		}
+
+		// Drop the database connection.
+		drop(connection);
+
+		Ok(())
+	}
+}
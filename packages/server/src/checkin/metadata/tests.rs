@@ -0,0 +1,112 @@
+use super::Key;
+use crate::{
+	Server,
+	test::{index, test, update_object},
+};
+use indoc::formatdoc;
+use std::{
+	path::Path,
+	time::{Duration, SystemTime},
+};
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_temp::Temp;
+
+/// Test that files changed within a second of the start of a checkin are racy.
+#[test]
+fn racy() {
+	let started_at = SystemTime::now();
+	let now = i64::try_from(
+		started_at
+			.duration_since(SystemTime::UNIX_EPOCH)
+			.unwrap()
+			.as_nanos(),
+	)
+	.unwrap();
+	let key = |mtime: i64, ctime: i64| Key {
+		device: 0,
+		inode: 0,
+		size: 0,
+		mtime,
+		ctime,
+		mode: 0,
+	};
+	let old = now - 2_000_000_000;
+	assert!(!key(old, old).is_racy(started_at));
+	assert!(key(now, old).is_racy(started_at));
+	assert!(key(old, now).is_racy(started_at));
+	assert!(key(now - 500_000_000, old).is_racy(started_at));
+}
+
+/// Test that a file's blob is reused when its metadata is unchanged, but only if the blob is complete.
+#[tokio::test]
+async fn reuse() {
+	test(async move |context| {
+		let server = context.start_server().await;
+		let temp = Temp::new();
+		tokio::fs::create_dir_all(temp.path()).await.unwrap();
+		let path = temp.path().join("file");
+		tokio::fs::write(&path, "hello").await.unwrap();
+
+		// Wait so that the file is not racy.
+		tokio::time::sleep(Duration::from_millis(1100)).await;
+
+		// Check in the file and confirm that its blob was recorded.
+		let original = checkin(&server, &path).await;
+		assert_eq!(get(&server).await, vec![original.clone()]);
+
+		// Record a different blob for the file and confirm that it is reused.
+		let file = tg::file!("other");
+		file.store(&server).await.unwrap();
+		let other = file.contents(&server).await.unwrap().id();
+		set(&server, &other).await;
+		index(&server).await;
+		assert_eq!(checkin(&server, &path).await, other);
+
+		// Mark the blob incomplete and confirm that it is not reused.
+		update_object(&server, &other.into(), "complete = 0").await;
+		assert_eq!(checkin(&server, &path).await, original);
+	})
+	.await;
+}
+
+async fn checkin(server: &Server, path: &Path) -> tg::blob::Id {
+	let arg = tg::checkin::Arg {
+		destructive: false,
+		deterministic: false,
+		ignore: false,
+		locked: false,
+		lockfile: false,
+		path: path.to_owned(),
+		updates: Vec::new(),
+		watch: false,
+	};
+	let artifact = tg::checkin::checkin(server, arg).await.unwrap();
+	let tg::Artifact::File(file) = artifact else {
+		panic!("expected a file");
+	};
+	file.contents(server).await.unwrap().id()
+}
+
+async fn get(server: &Server) -> Vec<tg::blob::Id> {
+	let connection = server.database.connection().await.unwrap();
+	let statement = "select blob from checkin_files;".to_owned();
+	let params = db::params![];
+	connection
+		.query_all_value_into(statement.into(), params)
+		.await
+		.unwrap()
+}
+
+async fn set(server: &Server, blob: &tg::blob::Id) {
+	let connection = server.database.write_connection().await.unwrap();
+	let p = connection.p();
+	let statement = formatdoc!(
+		"
+			update checkin_files
+			set blob = {p}1;
+		"
+	);
+	let params = db::params![blob];
+	connection.execute(statement.into(), params).await.unwrap();
+}
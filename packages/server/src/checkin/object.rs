@@ -1,6 +1,6 @@
 use super::{Blob, GraphObject, Object, State, Variant};
 use crate::Server;
-use futures::{StreamExt, TryStreamExt as _, stream};
+use futures::{StreamExt, TryStreamExt as _, future, stream};
 use itertools::Itertools as _;
 use std::collections::BTreeMap;
 use tangram_client as tg;
@@ -9,21 +9,45 @@ use tangram_either::Either;
 impl Server {
 	pub(super) async fn checkin_create_blobs(&self, state: &mut State) -> tg::Result<()> {
 		let server = self.clone();
+
+		// Get the files that do not have a blob, which the input reuses from the last checkin of a watch.
 		let nodes = state
 			.graph
 			.nodes
 			.iter()
 			.enumerate()
 			.filter_map(|(index, node)| {
-				if !node.variant.is_file() {
+				let file = node.variant.try_unwrap_file_ref().ok()?;
+				if file.blob.is_some() {
 					return None;
 				}
 				let path = node.path.clone()?;
-				Some((index, path))
+				let metadata = node.metadata.clone();
+				Some((index, path, metadata))
+			})
+			.collect::<Vec<_>>();
+
+		// Reuse the blobs from previous checkins for the files whose metadata is unchanged.
+		let metadata = nodes
+			.iter()
+			.filter_map(|(index, _, metadata)| Some((*index, metadata.as_ref()?)))
+			.collect::<Vec<_>>();
+		let reused = self
+			.checkin_try_get_blobs_by_metadata(&metadata)
+			.await
+			.inspect_err(|error| {
+				tracing::warn!(?error, "failed to get the blobs by metadata");
 			})
+			.unwrap_or_default();
+		let mut blobs = reused
+			.iter()
+			.map(|(index, blob)| (*index, Blob::Id(blob.clone())))
 			.collect::<Vec<_>>();
-		let blobs = stream::iter(nodes)
-			.map(|(index, path)| {
+
+		// Create the blobs for the remaining files.
+		let created = stream::iter(nodes)
+			.filter(|(index, _, _)| future::ready(!reused.contains_key(index)))
+			.map(|(index, path, _)| {
 				let server = server.clone();
 				async move {
 					let mut file = tokio::fs::File::open(path.as_ref()).await.map_err(
@@ -32,18 +56,19 @@ impl Server {
 					let blob = server.create_blob_inner(&mut file, None).await.map_err(
 						|source| tg::error!(!source, %path = path.display(), "failed to create the blob"),
 					)?;
-					Ok::<_, tg::Error>((index, blob))
+					Ok::<_, tg::Error>((index, Blob::Create(blob)))
 				}
 			})
 			.buffer_unordered(8)
 			.try_collect::<Vec<_>>()
 			.await?;
+		blobs.extend(created);
 		for (index, blob) in blobs {
 			state.graph.nodes[index]
 				.variant
 				.unwrap_file_mut()
 				.blob
-				.replace(Blob::Create(blob));
+				.replace(blob);
 		}
 		Ok(())
 	}
@@ -0,0 +1,256 @@
+use super::{Graph, Node};
+use crate::Server;
+use futures::{Stream, StreamExt as _, future};
+use notify::Watcher as _;
+use std::{
+	collections::BTreeSet,
+	path::{Path, PathBuf},
+	pin::pin,
+	time::Duration,
+};
+use tangram_client as tg;
+use tangram_futures::stream::Ext as _;
+use tangram_ignore as ignore;
+use tokio_util::task::AbortOnDropHandle;
+
+#[cfg(test)]
+mod tests;
+
+type Sender = async_channel::Sender<tg::Result<tg::progress::Event<tg::checkin::Output>>>;
+
+type Receiver = tokio::sync::mpsc::UnboundedReceiver<notify::Result<notify::Event>>;
+
+/// The state that is kept between the checkins of a watch.
+#[derive(Clone, Default)]
+pub(super) struct Watch {
+	/// The paths that changed since the last successful checkin.
+	pub(super) changed: BTreeSet<PathBuf>,
+
+	/// The input graph from the last successful checkin, with the files' blobs.
+	pub(super) graph: Option<Graph>,
+}
+
+impl Watch {
+	/// Get a node from the last successful checkin if neither its path nor any of its ancestors or descendants changed since.
+	pub(super) fn try_get_node(&self, path: &Path) -> Option<&Node> {
+		let graph = self.graph.as_ref()?;
+		if path
+			.ancestors()
+			.any(|ancestor| self.changed.contains(ancestor))
+		{
+			return None;
+		}
+		if self
+			.changed
+			.range(path.to_owned()..)
+			.next()
+			.is_some_and(|changed| changed.starts_with(path))
+		{
+			return None;
+		}
+		let index = *graph.paths.get(&path.to_owned())?;
+		Some(&graph.nodes[index])
+	}
+
+	/// Get the path of a node from the last successful checkin.
+	pub(super) fn try_get_path(&self, index: usize) -> Option<&Path> {
+		let graph = self.graph.as_ref()?;
+		graph
+			.nodes
+			.get(index)?
+			.path
+			.as_deref()
+			.map(PathBuf::as_path)
+	}
+}
+
+impl Server {
+	pub(super) fn checkin_watch(
+		&self,
+		arg: tg::checkin::Arg,
+	) -> impl Stream<Item = tg::Result<tg::progress::Event<tg::checkin::Output>>> + Send + 'static
+	{
+		let (sender, receiver) = async_channel::unbounded();
+		let task = AbortOnDropHandle::new(tokio::spawn({
+			let server = self.clone();
+			async move {
+				let result = server.checkin_watch_task(arg, &sender).await;
+				if let Err(error) = result {
+					sender.try_send(Err(error)).ok();
+				}
+			}
+		}));
+		receiver.attach(task)
+	}
+
+	async fn checkin_watch_task(
+		&self,
+		mut arg: tg::checkin::Arg,
+		sender: &Sender,
+	) -> tg::Result<()> {
+		// Validate the arg.
+		if arg.destructive {
+			return Err(tg::error!("watch is forbidden for destructive checkins"));
+		}
+
+		// Canonicalize the path's parent.
+		arg.path = crate::util::fs::canonicalize_parent(&arg.path)
+			.await
+			.map_err(|source| tg::error!(!source, %path = &arg.path.display(), "failed to canonicalize the path's parent"))?;
+
+		// Create the watcher.
+		let (event_sender, mut event_receiver) = tokio::sync::mpsc::unbounded_channel();
+		let mut watcher = notify::recommended_watcher(move |event| {
+			event_sender.send(event).ok();
+		})
+		.map_err(|source| tg::error!(!source, "failed to create the watcher"))?;
+		let mut watched = BTreeSet::new();
+
+		// Watch the package that contains the path.
+		let root_path = tg::package::try_get_nearest_package_path_for_path(&arg.path)?
+			.unwrap_or(&arg.path)
+			.to_owned();
+		watcher
+			.watch(&root_path, notify::RecursiveMode::Recursive)
+			.map_err(
+				|source| tg::error!(!source, %path = root_path.display(), "failed to watch the path"),
+			)?;
+		watched.insert(root_path);
+
+		let mut watch = Watch::default();
+		let mut current = None;
+		loop {
+			// Check in the artifact, forwarding its progress.
+			let progress = crate::progress::Handle::new();
+			let result = {
+				let checkin = pin!(self.checkin_inner(arg.clone(), Some(&mut watch), &progress));
+				let forward = pin!(progress.stream().for_each(|event| {
+					sender.try_send(event).ok();
+					future::ready(())
+				}));
+				match future::select(checkin, forward).await {
+					future::Either::Left((result, _)) => result,
+					future::Either::Right(((), checkin)) => checkin.await,
+				}
+			};
+
+			match result {
+				Ok(output) => {
+					// Watch the paths of the graph's roots, which include path dependencies outside the package.
+					if let Some(graph) = &watch.graph {
+						let paths = graph
+							.roots
+							.keys()
+							.filter_map(|root| graph.nodes[*root].path.as_deref())
+							.filter(|path| !watched.contains(*path))
+							.cloned()
+							.collect::<Vec<_>>();
+						for path in paths {
+							watcher
+								.watch(&path, notify::RecursiveMode::Recursive)
+								.map_err(
+									|source| tg::error!(!source, %path = path.display(), "failed to watch the path"),
+								)?;
+							watched.insert(path);
+						}
+					}
+
+					// Send the output if the artifact changed.
+					if current.as_ref() != Some(&output.referent.item) {
+						current.replace(output.referent.item.clone());
+						sender
+							.try_send(Ok(tg::progress::Event::Output(output)))
+							.ok();
+					}
+				},
+
+				// A checkin can fail while files are being edited, so report the error and wait for the next change.
+				Err(error) => {
+					let log = tg::progress::Log {
+						level: Some(tg::progress::Level::Error),
+						message: error.to_string(),
+					};
+					sender.try_send(Ok(tg::progress::Event::Log(log))).ok();
+				},
+			}
+
+			// Wait for changes.
+			if !Self::checkin_watch_wait(&arg, &mut event_receiver, &mut watch).await {
+				return Ok(());
+			}
+		}
+	}
+
+	/// Wait for changes and add the changed paths to the watch. Returns false if the watcher stopped.
+	async fn checkin_watch_wait(
+		arg: &tg::checkin::Arg,
+		receiver: &mut Receiver,
+		watch: &mut Watch,
+	) -> bool {
+		// Wait until no events have been received for this long, so that a burst of writes causes one checkin.
+		const DEBOUNCE: Duration = Duration::from_millis(100);
+
+		let mut ignorer = if arg.ignore {
+			Self::checkin_create_ignorer()
+				.inspect_err(|error| tracing::warn!(?error, "failed to create the ignorer"))
+				.ok()
+		} else {
+			None
+		};
+		let mut changed = false;
+		loop {
+			let event = if changed {
+				let Ok(event) = tokio::time::timeout(DEBOUNCE, receiver.recv()).await else {
+					return true;
+				};
+				event
+			} else {
+				receiver.recv().await
+			};
+			let Some(event) = event else {
+				return false;
+			};
+			match event {
+				Ok(event) => {
+					// Ignore accesses, because the checkin itself reads every file.
+					if event.kind.is_access() {
+						continue;
+					}
+
+					// If events were dropped, then the graph can no longer be trusted.
+					if event.need_rescan() {
+						watch.graph.take();
+						changed = true;
+					}
+
+					for path in event.paths {
+						if Self::checkin_watch_is_ignored(&path, ignorer.as_mut()) {
+							continue;
+						}
+						watch.changed.insert(path);
+						changed = true;
+					}
+				},
+				Err(error) => {
+					tracing::warn!(?error, "the watcher failed");
+					watch.graph.take();
+					changed = true;
+				},
+			}
+		}
+	}
+
+	fn checkin_watch_is_ignored(path: &Path, ignorer: Option<&mut ignore::Ignorer>) -> bool {
+		// Ignore the lockfile and the artifacts directory, because the checkin itself writes them.
+		if path
+			.file_name()
+			.is_some_and(|name| name == tg::package::LOCKFILE_FILE_NAME)
+			|| path
+				.components()
+				.any(|component| component.as_os_str() == ".tangram")
+		{
+			return true;
+		}
+		ignorer.is_some_and(|ignorer| ignorer.matches(path, None).unwrap_or(false))
+	}
+}
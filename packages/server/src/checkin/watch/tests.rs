@@ -0,0 +1,140 @@
+use super::Watch;
+use crate::{Server, test::test};
+use futures::{Stream, TryStreamExt as _};
+use indoc::indoc;
+use std::{path::Path, pin::pin, time::Duration};
+use tangram_client as tg;
+use tangram_temp::Temp;
+
+/// Test that a checkin of a watch reads only the paths that changed since the last checkin.
+#[tokio::test]
+async fn incremental() {
+	test(async move |context| {
+		let server = context.start_server().await;
+		let temp = Temp::new();
+		let path = temp.path().to_owned();
+		tokio::fs::create_dir_all(&path).await.unwrap();
+		tokio::fs::write(
+			path.join("tangram.ts"),
+			indoc!(
+				r#"
+					import foo from "./foo.tg.ts";
+					export default () => foo();
+				"#
+			),
+		)
+		.await
+		.unwrap();
+		tokio::fs::write(path.join("foo.tg.ts"), "export default () => 1;")
+			.await
+			.unwrap();
+		tokio::fs::write(path.join("a.txt"), "a").await.unwrap();
+
+		// Check in the directory and confirm that the graph is kept.
+		let mut watch = Watch::default();
+		let first = checkin(&server, &path, Some(&mut watch)).await;
+		assert_eq!(first, checkin(&server, &path, None).await);
+		assert!(watch.graph.is_some());
+		assert!(watch.changed.is_empty());
+
+		// Change a file without marking it changed and confirm that the last checkin's node is reused.
+		tokio::fs::write(path.join("a.txt"), "changed")
+			.await
+			.unwrap();
+		assert_eq!(checkin(&server, &path, Some(&mut watch)).await, first);
+
+		// Mark the file changed and confirm that it is read again.
+		watch.changed.insert(path.join("a.txt"));
+		let second = checkin(&server, &path, Some(&mut watch)).await;
+		assert_ne!(second, first);
+		assert_eq!(second, checkin(&server, &path, None).await);
+		assert!(watch.changed.is_empty());
+
+		// Add a file and confirm that its directory is read again.
+		tokio::fs::write(path.join("b.txt"), "b").await.unwrap();
+		watch.changed.insert(path.join("b.txt"));
+		let third = checkin(&server, &path, Some(&mut watch)).await;
+		assert_ne!(third, second);
+		assert_eq!(third, checkin(&server, &path, None).await);
+
+		// Change the dependency and confirm that the module that imports it is checked in again.
+		tokio::fs::write(path.join("foo.tg.ts"), "export default () => 2;")
+			.await
+			.unwrap();
+		watch.changed.insert(path.join("foo.tg.ts"));
+		let fourth = checkin(&server, &path, Some(&mut watch)).await;
+		assert_ne!(fourth, third);
+		assert_eq!(fourth, checkin(&server, &path, None).await);
+	})
+	.await;
+}
+
+/// Test that a watch checks in the path again when it changes.
+#[tokio::test]
+async fn stream() {
+	test(async move |context| {
+		let server = context.start_server().await;
+		let temp = Temp::new();
+		let path = temp.path().to_owned();
+		tokio::fs::create_dir_all(&path).await.unwrap();
+		tokio::fs::write(path.join("a.txt"), "a").await.unwrap();
+
+		// Watch the directory.
+		let arg = tg::checkin::Arg {
+			destructive: false,
+			deterministic: false,
+			ignore: false,
+			locked: false,
+			lockfile: false,
+			path: path.clone(),
+			updates: Vec::new(),
+			watch: true,
+		};
+		let stream = server.checkin(arg).await.unwrap();
+		let mut stream = pin!(stream);
+		let first = output(&mut stream).await;
+		assert_eq!(first, checkin(&server, &path, None).await);
+
+		// Change a file and confirm that the directory is checked in again.
+		tokio::fs::write(path.join("a.txt"), "changed")
+			.await
+			.unwrap();
+		let second = output(&mut stream).await;
+		assert_ne!(second, first);
+		assert_eq!(second, checkin(&server, &path, None).await);
+	})
+	.await;
+}
+
+async fn checkin(server: &Server, path: &Path, watch: Option<&mut Watch>) -> tg::artifact::Id {
+	let arg = tg::checkin::Arg {
+		destructive: false,
+		deterministic: false,
+		ignore: false,
+		locked: false,
+		lockfile: false,
+		path: path.to_owned(),
+		updates: Vec::new(),
+		watch: false,
+	};
+	let progress = crate::progress::Handle::new();
+	let output = server.checkin_inner(arg, watch, &progress).await.unwrap();
+	output.referent.item
+}
+
+async fn output<S>(stream: &mut S) -> tg::artifact::Id
+where
+	S: Stream<Item = tg::Result<tg::progress::Event<tg::checkin::Output>>> + Unpin,
+{
+	let future = async {
+		loop {
+			let event = stream.try_next().await.unwrap().unwrap();
+			if let tg::progress::Event::Output(output) = event {
+				return output.referent.item;
+			}
+		}
+	};
+	tokio::time::timeout(Duration::from_secs(10), future)
+		.await
+		.unwrap()
+}
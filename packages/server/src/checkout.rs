@@ -319,13 +319,19 @@ impl Server {
 			Either::Left((graph, node)) => {
 				if !state.graphs.contains_key(&graph) {
 					#[allow(clippy::match_wildcard_for_single_variants)]
-					let data = match &self.store {
-						crate::Store::Lmdb(store) => {
+					let data = match &self.store.backend {
+						crate::store::Backend::Fs(store) => {
 							store.try_get_object_data_sync(&graph.clone().into())?
 						},
-						crate::Store::Memory(store) => {
+						crate::store::Backend::Lmdb(store) => {
+							store.try_get_object_data_sync(&graph.clone().into())?
+						},
+						crate::store::Backend::Memory(store) => {
 							store.try_get_object_data(&graph.clone().into())?
 						},
+						crate::store::Backend::Tiered(store) => {
+							store.try_get_object_data_sync(&graph.clone().into())?
+						},
 						_ => return Err(tg::error!("not yet implemented")),
 					}
 					.ok_or_else(|| tg::error!("failed to get the value"))?
@@ -339,9 +345,19 @@ impl Server {
 			// Otherwise, get the artifact's data.
 			Either::Right(id) => {
 				#[allow(clippy::match_wildcard_for_single_variants)]
-				let data = match &self.store {
-					crate::Store::Lmdb(store) => store.try_get_object_data_sync(&id.into())?,
-					crate::Store::Memory(store) => store.try_get_object_data(&id.into())?,
+				let data = match &self.store.backend {
+					crate::store::Backend::Fs(store) => {
+						store.try_get_object_data_sync(&id.into())?
+					},
+					crate::store::Backend::Lmdb(store) => {
+						store.try_get_object_data_sync(&id.into())?
+					},
+					crate::store::Backend::Memory(store) => {
+						store.try_get_object_data(&id.into())?
+					},
+					crate::store::Backend::Tiered(store) => {
+						store.try_get_object_data_sync(&id.into())?
+					},
 					_ => return Err(tg::error!("not yet implemented")),
 				}
 				.ok_or_else(|| tg::error!("failed to get the value"))?;
@@ -1,8 +1,12 @@
 use super::Server;
 use futures::{Stream, StreamExt as _};
 use indoc::{formatdoc, indoc};
+use itertools::Itertools as _;
 use num::ToPrimitive as _;
-use std::time::Duration;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	time::Duration,
+};
 use tangram_client as tg;
 use tangram_database::{self as db, prelude::*};
 use tangram_futures::{stream::Ext as _, task::Stop};
@@ -10,12 +14,48 @@ use tangram_http::{Body, request::Ext as _};
 use tangram_messenger::prelude::*;
 use tokio_util::task::AbortOnDropHandle;
 
+#[cfg(test)]
+mod tests;
+
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60);
+
 struct InnerOutput {
 	cache_entries: Vec<tg::artifact::Id>,
 	objects: Vec<tg::object::Id>,
 	pipes: Vec<tg::pipe::Id>,
 	processes: Vec<tg::process::Id>,
 	ptys: Vec<tg::pty::Id>,
+	size: u64,
+}
+
+/// The state of a dry run. Items are identified by their ids' strings, because a tag's item may be a cache entry, an object, or a process.
+#[derive(Default)]
+struct DryRunState {
+	cache_entry_references: BTreeMap<String, u64>,
+	dereferenced_cache_entries: BTreeSet<String>,
+	dereferenced_objects: BTreeSet<String>,
+	dereferenced_processes: BTreeSet<String>,
+	object_references: BTreeMap<String, u64>,
+	objects: Vec<String>,
+	process_references: BTreeMap<String, u64>,
+	processes: Vec<String>,
+	removed_cache_entries: BTreeSet<String>,
+	removed_objects: BTreeSet<String>,
+	removed_processes: BTreeSet<String>,
+	size: u64,
+}
+
+/// The state of the eviction of objects when the store exceeds its maximum size.
+struct Eviction {
+	max_store_size: u64,
+	min_age: Duration,
+	store_size: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct ObjectRow {
+	id: tg::object::Id,
+	size: u64,
 }
 
 struct Count {
@@ -29,13 +69,32 @@ struct Count {
 impl Server {
 	pub async fn clean(
 		&self,
-	) -> tg::Result<impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static> {
+		arg: tg::clean::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
+	> {
 		let progress = crate::progress::Handle::new();
 
 		let task = AbortOnDropHandle::new(tokio::spawn({
 			let progress = progress.clone();
 			let server = self.clone();
 			async move {
+				// Report what would be removed if this is a dry run.
+				if arg.dry_run {
+					progress.spinner("clean", "cleaning");
+					let result = server.clean_dry_run(&arg).await;
+					progress.finish_all();
+					match result {
+						Ok(output) => {
+							progress.output(output);
+						},
+						Err(error) => {
+							progress.error(error);
+						},
+					}
+					return Ok(());
+				}
+
 				// Clean the temporary directory.
 				crate::util::fs::remove(server.temp_path())
 					.await
@@ -48,6 +107,11 @@ impl Server {
 						tg::error!(source = error, "failed to recreate the temporary directory")
 					})?;
 
+				// Apply the retention policies.
+				if let Some(config) = &server.config.cleaner {
+					server.cleaner_apply_retention(&config.retention).await?;
+				}
+
 				let count = server.count_items().await?;
 
 				// Clean until there are no more items to remove.
@@ -97,6 +161,8 @@ impl Server {
 					);
 				}
 
+				let mut output = tg::clean::Output::default();
+				let mut ids = tg::clean::Ids::default();
 				loop {
 					let now = time::OffsetDateTime::now_utc().unix_timestamp();
 					let ttl = Duration::from_secs(0);
@@ -105,25 +171,40 @@ impl Server {
 						.cleaner
 						.as_ref()
 						.map_or(1024, |config| config.batch_size);
-					let output = match server.cleaner_task_inner(now, ttl, batch_size).await {
-						Ok(output) => output,
+					let inner = match server.cleaner_task_inner(now, ttl, batch_size, None).await {
+						Ok(inner) => inner,
 						Err(error) => {
 							progress.error(error);
-							break;
+							return Ok(());
 						},
 					};
-					progress.increment("cache", output.cache_entries.len().to_u64().unwrap());
-					progress.increment("objects", output.objects.len().to_u64().unwrap());
-					progress.increment("processes", output.processes.len().to_u64().unwrap());
-					progress.increment("pipes", output.pipes.len().to_u64().unwrap());
-					progress.increment("ptys", output.ptys.len().to_u64().unwrap());
-					let n =
-						output.processes.len() + output.objects.len() + output.cache_entries.len();
+					progress.increment("cache", inner.cache_entries.len().to_u64().unwrap());
+					progress.increment("objects", inner.objects.len().to_u64().unwrap());
+					progress.increment("processes", inner.processes.len().to_u64().unwrap());
+					progress.increment("pipes", inner.pipes.len().to_u64().unwrap());
+					progress.increment("ptys", inner.ptys.len().to_u64().unwrap());
+					output.cache_entries += inner.cache_entries.len().to_u64().unwrap();
+					output.objects += inner.objects.len().to_u64().unwrap();
+					output.pipes += inner.pipes.len().to_u64().unwrap();
+					output.processes += inner.processes.len().to_u64().unwrap();
+					output.ptys += inner.ptys.len().to_u64().unwrap();
+					output.size += inner.size;
+					let n = inner.processes.len() + inner.objects.len() + inner.cache_entries.len();
+					if arg.ids {
+						ids.cache_entries.extend(inner.cache_entries);
+						ids.objects.extend(inner.objects);
+						ids.pipes.extend(inner.pipes);
+						ids.processes.extend(inner.processes);
+						ids.ptys.extend(inner.ptys);
+					}
 					if n == 0 {
 						break;
 					}
 				}
-				progress.output(());
+				if arg.ids {
+					output.ids = Some(ids);
+				}
+				progress.output(output);
 				Ok::<_, tg::Error>(())
 			}
 		}));
@@ -132,11 +213,47 @@ impl Server {
 	}
 
 	pub(crate) async fn cleaner_task(&self, config: &crate::config::Cleaner) -> tg::Result<()> {
+		let mut retention_applied_at: Option<tokio::time::Instant> = None;
+		let mut store_size: Option<u64> = None;
 		loop {
+			// Apply the retention policies periodically.
+			if retention_applied_at.is_none_or(|instant| instant.elapsed() >= RETENTION_INTERVAL) {
+				self.cleaner_apply_retention(&config.retention)
+					.await
+					.inspect_err(|error| {
+						tracing::error!(?error, "failed to apply the retention policies");
+					})
+					.ok();
+				retention_applied_at = Some(tokio::time::Instant::now());
+			}
+
 			let now = time::OffsetDateTime::now_utc().unix_timestamp();
 			let ttl = config.ttl;
 			let batch_size = config.batch_size;
-			let result = self.cleaner_task_inner(now, ttl, batch_size).await;
+
+			// Get the store size once per pass and keep a running total, rather than summing the sizes of all objects on every iteration.
+			let mut eviction = None;
+			if let Some(max_store_size) = config.max_store_size {
+				if store_size.is_none() {
+					match self.cleaner_get_store_size().await {
+						Ok(size) => {
+							store_size = Some(size);
+						},
+						Err(error) => {
+							tracing::error!(?error, "failed to get the store size");
+						},
+					}
+				}
+				eviction = store_size.map(|store_size| Eviction {
+					max_store_size,
+					min_age: config.min_eviction_age,
+					store_size,
+				});
+			}
+
+			let result = self
+				.cleaner_task_inner(now, ttl, batch_size, eviction)
+				.await;
 			match result {
 				Ok(output) => {
 					let n = output.processes.len()
@@ -145,17 +262,488 @@ impl Server {
 						+ output.pipes.len()
 						+ output.ptys.len();
 					if n == 0 {
+						store_size = None;
 						tokio::time::sleep(Duration::from_secs(1)).await;
+					} else {
+						store_size = store_size.map(|size| size.saturating_sub(output.size));
 					}
 				},
 				Err(error) => {
 					tracing::error!(?error, "failed to clean");
+					store_size = None;
 					tokio::time::sleep(Duration::from_secs(1)).await;
 				},
 			}
 		}
 	}
 
+	async fn cleaner_apply_retention(
+		&self,
+		policies: &[crate::config::CleanerRetention],
+	) -> tg::Result<()> {
+		let expired = self.cleaner_get_expired_tags(policies).await?;
+		for tag in &expired {
+			self.delete_tag(tag).await?;
+		}
+		Ok(())
+	}
+
+	async fn cleaner_get_expired_tags(
+		&self,
+		policies: &[crate::config::CleanerRetention],
+	) -> tg::Result<Vec<tg::Tag>> {
+		if policies.iter().all(|policy| policy.keep.is_none()) {
+			return Ok(Vec::new());
+		}
+
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Get the tags, from least to most recently updated.
+		let statement = indoc!(
+			"
+				select tag from tags order by updated_at, tag;
+			"
+		);
+		let params = db::params![];
+		let tags = connection
+			.query_all_value_into::<tg::Tag>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the tags"))?;
+
+		// Drop the connection.
+		drop(connection);
+
+		// Tags matching a policy without a limit are never deleted.
+		let protected = tags
+			.iter()
+			.filter(|tag| {
+				policies
+					.iter()
+					.any(|policy| policy.keep.is_none() && policy.pattern.matches(tag))
+			})
+			.collect::<BTreeSet<_>>();
+
+		// Expire all but the most recently updated matching tags for each policy with a limit.
+		let mut expired = BTreeSet::new();
+		for policy in policies {
+			let Some(keep) = policy.keep else {
+				continue;
+			};
+			let matches = tags
+				.iter()
+				.filter(|tag| policy.pattern.matches(tag))
+				.collect::<Vec<_>>();
+			let n = matches.len().saturating_sub(keep);
+			expired.extend(
+				matches
+					.into_iter()
+					.take(n)
+					.filter(|tag| !protected.contains(tag)),
+			);
+		}
+
+		Ok(expired.into_iter().cloned().collect())
+	}
+
+	async fn clean_dry_run(&self, arg: &tg::clean::Arg) -> tg::Result<tg::clean::Output> {
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let batch_size = self
+			.config
+			.cleaner
+			.as_ref()
+			.map_or(1024, |config| config.batch_size);
+		let mut output = tg::clean::Output::default();
+		let mut ids = tg::clean::Ids::default();
+
+		// Get the tags that the retention policies would delete.
+		let expired = if let Some(config) = &self.config.cleaner {
+			self.cleaner_get_expired_tags(&config.retention).await?
+		} else {
+			Vec::new()
+		};
+
+		// Compute what would be removed with read-only queries, so that the index is not locked. Items that would become unreferenced as others are removed are found by counting the references that would be removed.
+		let mut state = DryRunState::default();
+
+		// Remove the references from the expired tags.
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select item from tags
+				where tag = {p}1;
+			"
+		);
+		for tag in &expired {
+			let params = db::params![tag];
+			let item = connection
+				.query_optional_value_into::<String>(statement.clone().into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the tag"))?;
+			if let Some(item) = item {
+				state.dereference_cache_entry(&item);
+				state.dereference_object(&item);
+				state.dereference_process(&item);
+			}
+		}
+		drop(connection);
+
+		// Get the unreferenced items.
+		self.clean_dry_run_unreferenced(now, batch_size, &mut state)
+			.await?;
+
+		// Remove the references from the removed items until no more items become unreferenced.
+		while !state.is_done() {
+			self.clean_dry_run_step(now, batch_size, &mut state).await?;
+		}
+
+		output.cache_entries = state.removed_cache_entries.len().to_u64().unwrap();
+		output.objects = state.removed_objects.len().to_u64().unwrap();
+		output.processes = state.removed_processes.len().to_u64().unwrap();
+		output.size = state.size;
+		if arg.ids {
+			ids.cache_entries = state
+				.removed_cache_entries
+				.iter()
+				.map(|id| id.parse())
+				.collect::<tg::Result<_>>()?;
+			ids.objects = state
+				.removed_objects
+				.iter()
+				.map(|id| id.parse())
+				.collect::<tg::Result<_>>()?;
+			ids.processes = state
+				.removed_processes
+				.iter()
+				.map(|id| id.parse())
+				.collect::<tg::Result<_>>()?;
+		}
+
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Get the pipes and ptys.
+		let max_created_at = now.saturating_sub(24 * 60 * 60);
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select id from pipes
+				where created_at <= {p}1;
+			"
+		);
+		let params = db::params![max_created_at];
+		let pipes = connection
+			.query_all_value_into::<tg::pipe::Id>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the pipes"))?;
+		let statement = formatdoc!(
+			"
+				select id from ptys
+				where created_at <= {p}1;
+			"
+		);
+		let params = db::params![max_created_at];
+		let ptys = connection
+			.query_all_value_into::<tg::pty::Id>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the ptys"))?;
+		drop(connection);
+		output.pipes = pipes.len().to_u64().unwrap();
+		output.ptys = ptys.len().to_u64().unwrap();
+		if arg.ids {
+			ids.pipes = pipes;
+			ids.ptys = ptys;
+			output.ids = Some(ids);
+		}
+
+		Ok(output)
+	}
+
+	async fn clean_dry_run_unreferenced(
+		&self,
+		now: i64,
+		batch_size: usize,
+		state: &mut DryRunState,
+	) -> tg::Result<()> {
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+		let p = connection.p();
+
+		// Get the unreferenced cache entries.
+		let statement = formatdoc!(
+			"
+				select id from cache_entries
+				where reference_count = 0 and touched_at <= {p}1 and id > {p}2
+				order by id
+				limit {p}3;
+			"
+		);
+		let mut after = String::new();
+		loop {
+			let params = db::params![now, &after, batch_size];
+			let rows = connection
+				.query_all_value_into::<String>(statement.clone().into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the cache entries"))?;
+			let Some(last) = rows.last().cloned() else {
+				break;
+			};
+			state.removed_cache_entries.extend(rows);
+			after = last;
+		}
+
+		// Get the unreferenced objects.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			id: String,
+			size: u64,
+		}
+		let statement = formatdoc!(
+			"
+				select id, size from objects
+				where reference_count = 0 and touched_at <= {p}1 and id > {p}2
+				order by id
+				limit {p}3;
+			"
+		);
+		let mut after = String::new();
+		loop {
+			let params = db::params![now, &after, batch_size];
+			let rows = connection
+				.query_all_into::<Row>(statement.clone().into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+			let Some(last) = rows.last().map(|row| row.id.clone()) else {
+				break;
+			};
+			for row in rows {
+				state.size += row.size;
+				state.removed_objects.insert(row.id.clone());
+				state.objects.push(row.id);
+			}
+			after = last;
+		}
+
+		// Get the unreferenced processes.
+		let statement = formatdoc!(
+			"
+				select id from processes
+				where reference_count = 0 and touched_at <= {p}1 and id > {p}2
+				order by id
+				limit {p}3;
+			"
+		);
+		let mut after = String::new();
+		loop {
+			let params = db::params![now, &after, batch_size];
+			let rows = connection
+				.query_all_value_into::<String>(statement.clone().into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the processes"))?;
+			let Some(last) = rows.last().cloned() else {
+				break;
+			};
+			for id in rows {
+				state.removed_processes.insert(id.clone());
+				state.processes.push(id);
+			}
+			after = last;
+		}
+
+		drop(connection);
+
+		Ok(())
+	}
+
+	async fn clean_dry_run_step(
+		&self,
+		now: i64,
+		batch_size: usize,
+		state: &mut DryRunState,
+	) -> tg::Result<()> {
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+		let p = connection.p();
+		let placeholders = |n: usize| (0..n).map(|i| format!("{p}{}", i + 1)).join(", ");
+
+		// Remove the references from the removed objects.
+		let objects = std::mem::take(&mut state.objects);
+		for ids in objects.chunks(batch_size) {
+			let placeholders = placeholders(ids.len());
+			let statement = formatdoc!(
+				"
+					select child from object_children
+					where object in ({placeholders});
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let children = connection
+				.query_all_value_into::<String>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the object children"))?;
+			for child in &children {
+				state.dereference_object(child);
+			}
+			let statement = formatdoc!(
+				"
+					select cache_reference from objects
+					where id in ({placeholders}) and cache_reference is not null;
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let cache_references = connection
+				.query_all_value_into::<String>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the cache references"))?;
+			for cache_reference in &cache_references {
+				state.dereference_cache_entry(cache_reference);
+			}
+		}
+
+		// Remove the references from the removed processes.
+		let processes = std::mem::take(&mut state.processes);
+		for ids in processes.chunks(batch_size) {
+			let placeholders = placeholders(ids.len());
+			let statement = formatdoc!(
+				"
+					select child from process_children
+					where process in ({placeholders});
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let children = connection
+				.query_all_value_into::<String>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the process children"))?;
+			for child in &children {
+				state.dereference_process(child);
+			}
+			let statement = formatdoc!(
+				"
+					select object from process_objects
+					where process in ({placeholders});
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let objects = connection
+				.query_all_value_into::<String>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the process objects"))?;
+			for object in &objects {
+				state.dereference_object(object);
+			}
+		}
+
+		// Remove the items whose references would all be removed.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			id: String,
+			reference_count: Option<u64>,
+			size: Option<u64>,
+			touched_at: Option<i64>,
+		}
+		let unreferenced = |row: &Row, removed: u64| {
+			row.reference_count.is_some_and(|count| count <= removed)
+				&& row.touched_at.is_some_and(|touched_at| touched_at <= now)
+		};
+		let cache_entries = std::mem::take(&mut state.dereferenced_cache_entries)
+			.into_iter()
+			.collect::<Vec<_>>();
+		for ids in cache_entries.chunks(batch_size) {
+			let placeholders = placeholders(ids.len());
+			let statement = formatdoc!(
+				"
+					select id, reference_count, null as size, touched_at from cache_entries
+					where id in ({placeholders});
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the cache entries"))?;
+			for row in rows {
+				let removed = state.cache_entry_references[&row.id];
+				if unreferenced(&row, removed) {
+					state.removed_cache_entries.insert(row.id);
+				}
+			}
+		}
+		let objects = std::mem::take(&mut state.dereferenced_objects)
+			.into_iter()
+			.collect::<Vec<_>>();
+		for ids in objects.chunks(batch_size) {
+			let placeholders = placeholders(ids.len());
+			let statement = formatdoc!(
+				"
+					select id, reference_count, size, touched_at from objects
+					where id in ({placeholders});
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+			for row in rows {
+				let removed = state.object_references[&row.id];
+				if !unreferenced(&row, removed) {
+					continue;
+				}
+				state.size += row.size.unwrap_or_default();
+				state.removed_objects.insert(row.id.clone());
+				state.objects.push(row.id);
+			}
+		}
+		let processes = std::mem::take(&mut state.dereferenced_processes)
+			.into_iter()
+			.collect::<Vec<_>>();
+		for ids in processes.chunks(batch_size) {
+			let placeholders = placeholders(ids.len());
+			let statement = formatdoc!(
+				"
+					select id, reference_count, null as size, touched_at from processes
+					where id in ({placeholders});
+				"
+			);
+			let params = ids.iter().flat_map(|id| db::params![id]).collect();
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the processes"))?;
+			for row in rows {
+				let removed = state.process_references[&row.id];
+				if unreferenced(&row, removed) {
+					state.removed_processes.insert(row.id.clone());
+					state.processes.push(row.id);
+				}
+			}
+		}
+
+		drop(connection);
+
+		Ok(())
+	}
+
 	async fn count_items(&self) -> tg::Result<Count> {
 		// Get an index connection.
 		let connection = self
@@ -241,11 +829,31 @@ impl Server {
 		})
 	}
 
+	async fn cleaner_get_store_size(&self) -> tg::Result<u64> {
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+		let statement = indoc!(
+			"
+				select coalesce(sum(size), 0) from objects;
+			"
+		);
+		let params = db::params![];
+		let store_size = connection
+			.query_one_value_into::<u64>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the store size"))?;
+		Ok(store_size)
+	}
+
 	async fn cleaner_task_inner(
 		&self,
 		now: i64,
 		ttl: std::time::Duration,
 		batch_size: usize,
+		eviction: Option<Eviction>,
 	) -> tg::Result<InnerOutput> {
 		let max_touched_at = now - ttl.as_secs().to_i64().unwrap();
 
@@ -285,14 +893,66 @@ impl Server {
 					where reference_count = 0 and touched_at <= {p}1
 					limit {p}2
 				)
-				returning id;
+				returning id, size;
 			"
 		);
 		let params = db::params![max_touched_at, batch_size];
-		let objects = connection
-			.query_all_value_into(statement.into(), params)
+		let rows = connection
+			.query_all_into::<ObjectRow>(statement.into(), params)
 			.await
 			.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+		let mut size = rows.iter().map(|row| row.size).sum::<u64>();
+		let mut objects = rows.into_iter().map(|row| row.id).collect::<Vec<_>>();
+
+		// If the store exceeds its maximum size, then delete the least recently used objects that are older than the minimum eviction age from the index regardless of the ttl.
+		let mut evicted = Vec::new();
+		if let Some(eviction) = eviction {
+			let store_size = eviction.store_size.saturating_sub(size);
+			if store_size > eviction.max_store_size {
+				let excess = store_size - eviction.max_store_size;
+				let max_touched_at = now - eviction.min_age.as_secs().to_i64().unwrap();
+				let p = connection.p();
+				let statement = formatdoc!(
+					"
+						select id, size
+						from objects
+						where reference_count = 0 and touched_at <= {p}1
+						order by touched_at
+						limit {p}2;
+					"
+				);
+				let params = db::params![max_touched_at, batch_size];
+				let rows = connection
+					.query_all_into::<ObjectRow>(statement.into(), params)
+					.await
+					.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+				// Check the reference count again when deleting, because the object may have been referenced since it was selected.
+				let statement = formatdoc!(
+					"
+						delete from objects
+						where id = {p}1 and reference_count = 0
+						returning id, size;
+					"
+				);
+				let mut reclaimed = 0;
+				for row in rows {
+					if reclaimed >= excess {
+						break;
+					}
+					let params = db::params![&row.id];
+					let Some(row) = connection
+						.query_optional_into::<ObjectRow>(statement.clone().into(), params)
+						.await
+						.map_err(|source| tg::error!(!source, "failed to delete the object"))?
+					else {
+						continue;
+					};
+					reclaimed += row.size;
+					evicted.push(row.id);
+				}
+				size += reclaimed;
+			}
+		}
 
 		// Delete cache entries.
 		let p = connection.p();
@@ -324,6 +984,17 @@ impl Server {
 		};
 		self.store.delete_batch(arg).await?;
 
+		// Delete evicted objects.
+		if !evicted.is_empty() {
+			let arg = crate::store::DeleteBatchArg {
+				ids: evicted.clone(),
+				now,
+				ttl: 0,
+			};
+			self.store.delete_batch(arg).await?;
+			objects.extend(evicted);
+		}
+
 		// Get a database connection.
 		let connection = self
 			.database
@@ -442,6 +1113,7 @@ impl Server {
 			pipes,
 			processes,
 			ptys,
+			size,
 		};
 
 		Ok(output)
@@ -459,11 +1131,22 @@ impl Server {
 			.parse_header::<mime::Mime, _>(http::header::ACCEPT)
 			.transpose()?;
 
+		// Get the stop signal.
+		let stop = request.extensions().get::<Stop>().cloned().unwrap();
+
+		// Get the arg. Clients that predate the arg send an empty body.
+		let bytes = request.bytes().await?;
+		let arg = if bytes.is_empty() {
+			tg::clean::Arg::default()
+		} else {
+			serde_json::from_slice(&bytes)
+				.map_err(|source| tg::error!(!source, "failed to deserialize the arg"))?
+		};
+
 		// Get the stream.
-		let stream = handle.clean().await?;
+		let stream = handle.clean(arg).await?;
 
 		// Stop the stream when the server stops.
-		let stop = request.extensions().get::<Stop>().cloned().unwrap();
 		let stop = async move {
 			stop.wait().await;
 		};
@@ -497,3 +1180,37 @@ impl Server {
 		Ok(response)
 	}
 }
+
+impl DryRunState {
+	fn dereference_cache_entry(&mut self, id: &str) {
+		if !self.removed_cache_entries.contains(id) {
+			*self
+				.cache_entry_references
+				.entry(id.to_owned())
+				.or_default() += 1;
+			self.dereferenced_cache_entries.insert(id.to_owned());
+		}
+	}
+
+	fn dereference_object(&mut self, id: &str) {
+		if !self.removed_objects.contains(id) {
+			*self.object_references.entry(id.to_owned()).or_default() += 1;
+			self.dereferenced_objects.insert(id.to_owned());
+		}
+	}
+
+	fn dereference_process(&mut self, id: &str) {
+		if !self.removed_processes.contains(id) {
+			*self.process_references.entry(id.to_owned()).or_default() += 1;
+			self.dereferenced_processes.insert(id.to_owned());
+		}
+	}
+
+	fn is_done(&self) -> bool {
+		self.objects.is_empty()
+			&& self.processes.is_empty()
+			&& self.dereferenced_cache_entries.is_empty()
+			&& self.dereferenced_objects.is_empty()
+			&& self.dereferenced_processes.is_empty()
+	}
+}
@@ -0,0 +1,109 @@
+use super::Eviction;
+use crate::{
+	Server,
+	test::{index, test},
+};
+use indoc::formatdoc;
+use std::time::Duration;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_either::Either;
+
+/// Test that retention keeps the most recently updated tags rather than the greatest tags.
+#[tokio::test]
+async fn retention_keeps_most_recently_updated() {
+	test(async move |context| {
+		let server = context.start_server().await;
+		let a = tg::file!("a").store(&server).await.unwrap();
+		let b = tg::file!("b").store(&server).await.unwrap();
+		put_tag(&server, "release/a", a.into(), 2).await;
+		put_tag(&server, "release/b", b.into(), 1).await;
+
+		// Keep one tag.
+		let policies = [crate::config::CleanerRetention {
+			keep: Some(1),
+			pattern: "release/*".parse().unwrap(),
+		}];
+		let expired = server.cleaner_get_expired_tags(&policies).await.unwrap();
+		let expected: tg::Tag = "release/b".parse().unwrap();
+		assert_eq!(expired, vec![expected]);
+	})
+	.await;
+}
+
+/// Test that eviction removes unreferenced objects older than the minimum eviction age regardless of the ttl and keeps referenced objects.
+#[tokio::test]
+async fn eviction() {
+	test(async move |context| {
+		let server = context.start_server().await;
+		let a = tg::file!("a").store(&server).await.unwrap();
+		let b = tg::file!("b").store(&server).await.unwrap();
+		put_tag(&server, "b", b.clone().into(), 0).await;
+		index(&server).await;
+
+		// Clean with a ttl that keeps every object, a maximum store size that is exceeded, and a minimum eviction age that keeps every object.
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let ttl = Duration::from_secs(86400);
+		let eviction = Eviction {
+			max_store_size: 0,
+			min_age: Duration::from_secs(86400),
+			store_size: server.cleaner_get_store_size().await.unwrap(),
+		};
+		let output = server
+			.cleaner_task_inner(now, ttl, 1024, Some(eviction))
+			.await
+			.unwrap();
+		assert!(output.objects.is_empty());
+
+		// Clean with a minimum eviction age that keeps no object.
+		let mut objects = Vec::new();
+		loop {
+			let eviction = Eviction {
+				max_store_size: 0,
+				min_age: Duration::ZERO,
+				store_size: server.cleaner_get_store_size().await.unwrap(),
+			};
+			let output = server
+				.cleaner_task_inner(now, ttl, 1024, Some(eviction))
+				.await
+				.unwrap();
+			if output.objects.is_empty() {
+				break;
+			}
+			objects.extend(output.objects);
+		}
+
+		// Confirm that the untagged file and its contents were evicted and that the tagged file was not.
+		let a: tg::object::Id = a.into();
+		let b: tg::object::Id = b.into();
+		assert!(objects.contains(&a));
+		assert!(!objects.contains(&b));
+		assert_eq!(objects.len(), 2);
+		assert!(server.store.try_get(&a).await.unwrap().is_none());
+		assert!(server.store.try_get(&b).await.unwrap().is_some());
+	})
+	.await;
+}
+
+async fn put_tag(server: &Server, tag: &str, item: tg::object::Id, updated_at: i64) {
+	let tag: tg::Tag = tag.parse().unwrap();
+	let arg = tg::tag::put::Arg {
+		force: false,
+		item: Either::Right(item),
+		remote: None,
+	};
+	server.put_tag(&tag, arg).await.unwrap();
+
+	// Set the update time, so that the order of the tags does not depend on the clock.
+	let connection = server.database.write_connection().await.unwrap();
+	let p = connection.p();
+	let statement = formatdoc!(
+		"
+			update tags
+			set updated_at = {p}1
+			where tag = {p}2;
+		"
+	);
+	let params = db::params![updated_at, &tag];
+	connection.execute(statement.into(), params).await.unwrap();
+}
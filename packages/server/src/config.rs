@@ -1,10 +1,12 @@
 use std::{collections::HashMap, path::PathBuf, time::Duration};
+use tangram_client as tg;
 use url::Url;
 
 #[derive(Clone, Debug)]
 pub struct Config {
 	pub advanced: Advanced,
 	pub authentication: Option<Authentication>,
+	pub authorization: Option<Authorization>,
 	pub cleaner: Option<Cleaner>,
 	pub database: Database,
 	pub directory: PathBuf,
@@ -23,9 +25,12 @@ pub struct Config {
 
 #[derive(Clone, Debug)]
 pub struct Advanced {
+	pub migrate: bool,
 	pub preserve_temp_directories: bool,
 	pub process_dequeue_timeout: Duration,
+	pub process_priority_aging: Duration,
 	pub shared_directory: bool,
+	pub store_compression: Option<StoreCompression>,
 	pub write_process_logs_to_stderr: bool,
 }
 
@@ -39,6 +44,11 @@ pub struct AuthenticationProviders {
 	pub github: Option<Oauth>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct Authorization {
+	pub anonymous_scopes: Vec<tg::token::Scope>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Oauth {
 	pub auth_url: String,
@@ -51,9 +61,18 @@ pub struct Oauth {
 #[derive(Clone, Debug)]
 pub struct Cleaner {
 	pub batch_size: usize,
+	pub max_store_size: Option<u64>,
+	pub min_eviction_age: Duration,
+	pub retention: Vec<CleanerRetention>,
 	pub ttl: Duration,
 }
 
+#[derive(Clone, Debug)]
+pub struct CleanerRetention {
+	pub keep: Option<usize>,
+	pub pattern: tg::tag::Pattern,
+}
+
 #[derive(Clone, Debug)]
 pub enum Database {
 	Postgres(PostgresDatabase),
@@ -97,6 +116,7 @@ pub struct SqliteIndex {
 
 #[derive(Clone, Debug)]
 pub struct Indexer {
+	pub max_backlog: Option<u64>,
 	pub message_batch_size: usize,
 	pub message_batch_timeout: Duration,
 	pub insert_batch_size: usize,
@@ -104,11 +124,17 @@ pub struct Indexer {
 
 #[derive(Clone, Debug, Default)]
 pub enum Messenger {
+	Local(LocalMessenger),
 	#[default]
 	Memory,
 	Nats(NatsMessenger),
 }
 
+#[derive(Clone, Debug)]
+pub struct LocalMessenger {
+	pub path: PathBuf,
+}
+
 #[derive(Clone, Debug)]
 pub struct NatsMessenger {
 	pub url: Url,
@@ -117,13 +143,16 @@ pub struct NatsMessenger {
 #[derive(Clone, Debug)]
 pub struct Remote {
 	pub name: String,
+	pub token: Option<String>,
 	pub url: Url,
 }
 
 #[derive(Clone, Debug)]
 pub struct Runner {
+	pub cgroup: Option<PathBuf>,
 	pub concurrency: usize,
 	pub heartbeat_interval: Duration,
+	pub labels: Vec<String>,
 	pub remotes: Vec<String>,
 }
 
@@ -143,9 +172,11 @@ pub enum RuntimeKind {
 pub enum Store {
 	#[cfg(feature = "foundationdb")]
 	Fdb(FdbStore),
+	Fs(FsStore),
 	Lmdb(LmdbStore),
 	Memory,
 	S3(S3Store),
+	Tiered(TieredStore),
 }
 
 #[cfg(feature = "foundationdb")]
@@ -154,6 +185,11 @@ pub struct FdbStore {
 	pub path: Option<PathBuf>,
 }
 
+#[derive(Clone, Debug)]
+pub struct FsStore {
+	pub path: PathBuf,
+}
+
 #[derive(Clone, Debug)]
 pub struct LmdbStore {
 	pub path: PathBuf,
@@ -163,11 +199,30 @@ pub struct LmdbStore {
 pub struct S3Store {
 	pub access_key: Option<String>,
 	pub bucket: String,
+	pub concurrency: usize,
+	pub max_retries: usize,
+	pub multipart_part_size: usize,
+	pub multipart_threshold: usize,
+	pub path_style: bool,
 	pub region: Option<String>,
 	pub secret_key: Option<String>,
 	pub url: Url,
 }
 
+#[derive(Clone, Debug)]
+pub struct StoreCompression {
+	pub level: i32,
+	pub threshold: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct TieredStore {
+	pub eviction_interval: Duration,
+	pub local: LmdbStore,
+	pub max_size: u64,
+	pub remote: S3Store,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Vfs {
 	pub cache_size: usize,
@@ -180,6 +235,7 @@ pub struct Watchdog {
 	pub batch_size: usize,
 	pub interval: Duration,
 	pub max_depth: usize,
+	pub timeout_grace_period: Duration,
 	pub ttl: Duration,
 }
 
@@ -188,6 +244,7 @@ impl Config {
 	pub fn with_directory(directory: PathBuf) -> Self {
 		let advanced = Advanced::default();
 		let authentication = None;
+		let authorization = None;
 		let cleaner = None;
 		let database = Database::Sqlite(SqliteDatabase {
 			connections: 1,
@@ -212,6 +269,7 @@ impl Config {
 		Self {
 			advanced,
 			authentication,
+			authorization,
 			cleaner,
 			database,
 			directory,
@@ -233,9 +291,12 @@ impl Config {
 impl Default for Advanced {
 	fn default() -> Self {
 		Self {
+			migrate: true,
 			process_dequeue_timeout: Duration::from_secs(3600),
+			process_priority_aging: Duration::from_secs(60),
 			preserve_temp_directories: false,
 			shared_directory: true,
+			store_compression: None,
 			write_process_logs_to_stderr: false,
 		}
 	}
@@ -245,6 +306,9 @@ impl Default for Cleaner {
 	fn default() -> Self {
 		Self {
 			batch_size: 1024,
+			max_store_size: None,
+			min_eviction_age: Duration::from_secs(3600),
+			retention: Vec::new(),
 			ttl: Duration::from_secs(86400),
 		}
 	}
@@ -263,6 +327,7 @@ impl Default for Indexer {
 	fn default() -> Self {
 		Self {
 			insert_batch_size: 1024,
+			max_backlog: Some(1_000_000),
 			message_batch_size: 1024,
 			message_batch_timeout: Duration::from_millis(100),
 		}
@@ -279,13 +344,61 @@ impl Default for NatsMessenger {
 impl Default for Runner {
 	fn default() -> Self {
 		Self {
+			cgroup: None,
 			concurrency: 1,
 			heartbeat_interval: Duration::from_secs(1),
+			labels: Vec::new(),
 			remotes: Vec::new(),
 		}
 	}
 }
 
+impl S3Store {
+	/// The minimum size in bytes of each part of a multipart upload other than the last.
+	pub const MIN_MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
+
+	pub fn validate(&self) -> tg::Result<()> {
+		if self.concurrency == 0 {
+			return Err(tg::error!(
+				"the s3 store concurrency must be greater than zero"
+			));
+		}
+		if self.multipart_part_size < Self::MIN_MULTIPART_PART_SIZE {
+			return Err(tg::error!(
+				%min = Self::MIN_MULTIPART_PART_SIZE,
+				"the s3 store multipart part size must be at least the minimum"
+			));
+		}
+		Ok(())
+	}
+}
+
+impl Default for S3Store {
+	fn default() -> Self {
+		Self {
+			access_key: None,
+			bucket: "tangram".to_owned(),
+			concurrency: 256,
+			max_retries: 3,
+			multipart_part_size: 8 * 1024 * 1024,
+			multipart_threshold: 16 * 1024 * 1024,
+			path_style: false,
+			region: None,
+			secret_key: None,
+			url: "http://localhost:9000".parse().unwrap(),
+		}
+	}
+}
+
+impl Default for StoreCompression {
+	fn default() -> Self {
+		Self {
+			level: 3,
+			threshold: 256,
+		}
+	}
+}
+
 impl Default for Vfs {
 	fn default() -> Self {
 		Self {
@@ -302,6 +415,7 @@ impl Default for Watchdog {
 			batch_size: 100,
 			interval: Duration::from_secs(1),
 			max_depth: 1024,
+			timeout_grace_period: Duration::from_secs(10),
 			ttl: Duration::from_secs(60),
 		}
 	}
@@ -7,6 +7,8 @@ use std::borrow::Cow;
 use tangram_client as tg;
 use tangram_database::{self as db, prelude::*};
 
+pub mod migration;
+
 #[derive(
 	Debug,
 	derive_more::IsVariant,
@@ -251,59 +253,229 @@ impl db::Query for Transaction<'_> {
 	}
 }
 
-pub async fn migrate(database: &Database) -> tg::Result<()> {
-	#[allow(irrefutable_let_patterns)]
-	let Database::Sqlite(database) = database else {
-		return Ok(());
-	};
-
-	let migrations = vec![migration_0000(database)];
-
-	let connection = database
-		.connection()
-		.await
-		.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-	let version =
-		connection
-			.with(|connection| {
-				connection
-					.pragma_query_value(None, "user_version", |row| {
-						Ok(row.get_unwrap::<_, usize>(0))
-					})
-					.map_err(|source| tg::error!(!source, "failed to get the version"))
-			})
-			.await?;
-	drop(connection);
-
-	// If this path is from a newer version of Tangram, then return an error.
-	if version > migrations.len() {
-		return Err(tg::error!(
-			r"The database has run migrations from a newer version of Tangram. Please run `tg self update` to update to the latest version of Tangram."
-		));
-	}
-
-	// Run all migrations and update the version.
-	let migrations = migrations.into_iter().enumerate().skip(version);
-	for (version, migration) in migrations {
-		// Run the migration.
-		migration.await?;
-
-		// Update the version.
-		let connection = database
-			.write_connection()
-			.await
-			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-		connection
-			.with(move |connection| {
-				connection
-					.pragma_update(None, "user_version", version + 1)
-					.map_err(|source| tg::error!(!source, "failed to get the version"))
-			})
-			.await?;
-	}
-
-	Ok(())
-}
+/// The database's migrations. The first migration creates the schema as of the first release with migrations. Each later migration applies one schema change, so existing databases are upgraded in order.
+pub const MIGRATIONS: &[migration::Migration] = &[
+	migration::Migration {
+		postgres: None,
+		sqlite: concat!(
+			include_str!("database/schema.sql"),
+			"\ninsert into remotes (name, url) values ('default', 'https://cloud.tangram.dev');\n",
+		),
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table remotes add column token text;
+
+				alter table tokens add column scopes text not null default '[]';
+			",
+		),
+		sqlite: "
+			alter table remotes add column token text;
+
+			alter table tokens add column scopes text not null default '[]';
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				-- Existing tokens are stored unhashed, so they are dropped and must be created again.
+				drop table tokens;
+
+				create table tokens (
+					id text primary key,
+					created_at int8 not null,
+					expires_at int8,
+					hash text not null,
+					name text,
+					scopes text not null,
+					\"user\" text
+				);
+
+				create unique index tokens_hash_index on tokens (hash);
+			",
+		),
+		sqlite: "
+			-- Existing tokens are stored unhashed, so they are dropped and must be created again.
+			drop table tokens;
+
+			create table tokens (
+				id text primary key,
+				created_at integer not null,
+				expires_at integer,
+				hash text not null,
+				name text,
+				scopes text not null,
+				\"user\" text
+			);
+
+			create unique index tokens_hash_index on tokens (hash);
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table processes add column limits text;
+			",
+		),
+		sqlite: "
+			alter table processes add column limits text;
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table processes add column usage text;
+			",
+		),
+		sqlite: "
+			alter table processes add column usage text;
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table processes add column timeout int8;
+			",
+		),
+		sqlite: "
+			alter table processes add column timeout integer;
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table processes add column labels text;
+			",
+		),
+		sqlite: "
+			alter table processes add column labels text;
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table processes add column priority int8;
+
+				alter table processes add column effective_enqueued_at int8;
+
+				update processes set effective_enqueued_at = enqueued_at;
+
+				create index processes_effective_enqueued_at_index on processes (effective_enqueued_at, id) where status = 'enqueued';
+
+				create index processes_dequeued_at_index on processes (dequeued_at) where status = 'dequeued';
+			",
+		),
+		sqlite: "
+			alter table processes add column priority integer;
+
+			alter table processes add column effective_enqueued_at integer;
+
+			update processes set effective_enqueued_at = enqueued_at;
+
+			create index processes_effective_enqueued_at_index on processes (effective_enqueued_at, id) where status = 'enqueued';
+
+			create index processes_dequeued_at_index on processes (dequeued_at) where status = 'dequeued';
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				create table process_log_entries (
+					process text not null,
+					position int8 not null,
+					length int8 not null,
+					stream text not null,
+					timestamp int8 not null
+				);
+
+				create index process_log_entries_process_position_index on process_log_entries (process, position);
+
+				create index process_log_entries_process_timestamp_index on process_log_entries (process, timestamp);
+
+				create function processes_delete_log_entries_function() returns trigger as $$
+				begin
+					delete from process_log_entries
+					where process = old.id;
+					return old;
+				end;
+				$$ language plpgsql;
+
+				create trigger processes_delete_log_entries_trigger
+				after delete on processes
+				for each row
+				execute function processes_delete_log_entries_function();
+			",
+		),
+		sqlite: "
+			create table process_log_entries (
+				process text not null,
+				position integer not null,
+				length integer not null,
+				stream text not null,
+				timestamp integer not null
+			);
+
+			create index process_log_entries_process_position_index on process_log_entries (process, position);
+
+			create index process_log_entries_process_timestamp_index on process_log_entries (process, timestamp);
+
+			drop trigger processes_delete_trigger;
+
+			create trigger processes_delete_trigger
+			after delete on processes
+			for each row
+			begin
+				delete from process_children
+				where process = old.id;
+
+				delete from process_tokens
+				where process = old.id;
+
+				delete from process_log_entries
+				where process = old.id;
+			end;
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				create table checkin_files (
+					device int8 not null,
+					inode int8 not null,
+					size int8 not null,
+					mtime int8 not null,
+					ctime int8 not null,
+					mode int8 not null,
+					blob text not null,
+					primary key (device, inode)
+				);
+			",
+		),
+		sqlite: "
+			create table checkin_files (
+				device integer not null,
+				inode integer not null,
+				size integer not null,
+				mtime integer not null,
+				ctime integer not null,
+				mode integer not null,
+				blob text not null,
+				primary key (device, inode)
+			);
+		",
+	},
+	migration::Migration {
+		postgres: Some(
+			"
+				alter table tags add column updated_at int8 not null default 0;
+			",
+		),
+		sqlite: "
+			alter table tags add column updated_at integer not null default 0;
+		",
+	},
+];
 
 pub fn initialize(connection: &sqlite::Connection) -> sqlite::Result<()> {
 	connection.pragma_update(None, "auto_vaccum", "incremental")?;
@@ -334,30 +506,3 @@ pub fn initialize(connection: &sqlite::Connection) -> sqlite::Result<()> {
 	connection.create_scalar_function("split_part", 3, flags, function)?;
 	Ok(())
 }
-
-async fn migration_0000(database: &db::sqlite::Database) -> tg::Result<()> {
-	let sql = include_str!("database/schema.sql");
-	let connection = database
-		.write_connection()
-		.await
-		.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-	connection
-		.with(move |connection| {
-			connection
-				.execute_batch(sql)
-				.map_err(|source| tg::error!(!source, "failed to execute the statements"))?;
-			Ok::<_, tg::Error>(())
-		})
-		.await?;
-	connection
-		.with(move |connection| {
-			let sql =
-				"insert into remotes (name, url) values ('default', 'https://cloud.tangram.dev');";
-			connection
-				.execute_batch(sql)
-				.map_err(|source| tg::error!(!source, "failed to execute the statements"))?;
-			Ok::<_, tg::Error>(())
-		})
-		.await?;
-	Ok(())
-}
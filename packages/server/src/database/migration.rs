@@ -0,0 +1,155 @@
+use super::Database;
+use tangram_client as tg;
+use tangram_database::prelude::*;
+
+/// A schema migration. Migrations are applied in order, and the number of applied migrations is recorded in the database.
+pub struct Migration {
+	/// The statements to execute for postgres. `None` means there is nothing to apply for postgres, because its schema is provisioned outside the server.
+	pub postgres: Option<&'static str>,
+
+	/// The statements to execute for sqlite.
+	pub sqlite: &'static str,
+}
+
+/// Get the number of migrations that have not been applied.
+pub async fn pending(
+	database: &Database,
+	name: &str,
+	migrations: &[Migration],
+) -> tg::Result<usize> {
+	let version = version(database).await?;
+
+	// If this database is from a newer version of Tangram, then return an error.
+	if version > migrations.len() {
+		return Err(tg::error!(
+			"the {name} has run migrations from a newer version of Tangram, please run `tg self update` to update to the latest version of Tangram"
+		));
+	}
+
+	Ok(migrations.len() - version)
+}
+
+/// Apply the pending migrations and return the number that were applied.
+pub async fn migrate(
+	database: &Database,
+	name: &str,
+	migrations: &[Migration],
+) -> tg::Result<usize> {
+	let pending = pending(database, name, migrations).await?;
+	let version = migrations.len() - pending;
+	for (version, migration) in migrations.iter().enumerate().skip(version) {
+		apply(database, version, migration)
+			.await
+			.map_err(|source| tg::error!(!source, %version, "failed to apply the migration"))?;
+	}
+	Ok(pending)
+}
+
+async fn version(database: &Database) -> tg::Result<usize> {
+	match database {
+		Database::Sqlite(database) => {
+			let connection = database
+				.connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			connection
+				.with(|connection| {
+					connection
+						.pragma_query_value(None, "user_version", |row| {
+							Ok(row.get_unwrap::<_, usize>(0))
+						})
+						.map_err(|source| tg::error!(!source, "failed to get the version"))
+				})
+				.await
+		},
+		#[cfg(feature = "postgres")]
+		Database::Postgres(database) => {
+			let connection = database
+				.write_connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			let statement = "
+				create table if not exists schema_version (
+					version int8 not null
+				);
+			";
+			connection
+				.inner()
+				.batch_execute(statement)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to create the version table"))?;
+			let statement = "select max(version) from schema_version;";
+			let row = connection
+				.inner()
+				.query_one(statement, &[])
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the version"))?;
+			let version = row.get::<_, Option<i64>>(0).unwrap_or_default();
+			let version = version
+				.try_into()
+				.map_err(|source| tg::error!(!source, "invalid version"))?;
+			Ok(version)
+		},
+	}
+}
+
+async fn apply(database: &Database, version: usize, migration: &Migration) -> tg::Result<()> {
+	match database {
+		Database::Sqlite(database) => {
+			let sql = migration.sqlite;
+			let connection = database
+				.write_connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			connection
+				.with(move |connection| {
+					// Apply the migration and update the version atomically.
+					let transaction = connection
+						.transaction()
+						.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+					transaction.execute_batch(sql).map_err(|source| {
+						tg::error!(!source, "failed to execute the statements")
+					})?;
+					transaction
+						.pragma_update(None, "user_version", version + 1)
+						.map_err(|source| tg::error!(!source, "failed to update the version"))?;
+					transaction.commit().map_err(|source| {
+						tg::error!(!source, "failed to commit the transaction")
+					})?;
+					Ok::<_, tg::Error>(())
+				})
+				.await?;
+		},
+		#[cfg(feature = "postgres")]
+		Database::Postgres(database) => {
+			let mut connection = database
+				.write_connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			let transaction = connection
+				.inner_mut()
+				.transaction()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+			if let Some(sql) = migration.postgres {
+				transaction
+					.batch_execute(sql)
+					.await
+					.map_err(|source| tg::error!(!source, "failed to execute the statements"))?;
+			}
+			let version = i64::try_from(version + 1).unwrap();
+			transaction
+				.execute(
+					"insert into schema_version (version) values ($1);",
+					&[&version],
+				)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to update the version"))?;
+			transaction
+				.commit()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to commit the transaction"))?;
+		},
+	}
+	Ok(())
+}
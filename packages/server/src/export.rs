@@ -133,8 +133,12 @@ impl Server {
 		// If the database, index, and store are synchronous, and all items are complete, then export synchronously.
 		if self.database.is_sqlite()
 			&& self.index.is_sqlite()
-			&& matches!(self.store, crate::Store::Lmdb(_) | crate::Store::Memory(_))
-		{
+			&& matches!(
+				self.store.backend,
+				crate::store::Backend::Fs(_)
+					| crate::store::Backend::Lmdb(_)
+					| crate::store::Backend::Memory(_)
+			) {
 			let complete = self.export_items_complete(&arg).await?;
 			if complete {
 				let (import_complete_sender, import_complete_receiver) =
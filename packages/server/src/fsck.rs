@@ -0,0 +1,472 @@
+use crate::Server;
+use futures::{Stream, StreamExt as _};
+use indoc::{formatdoc, indoc};
+use num::ToPrimitive as _;
+use std::collections::BTreeSet;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_futures::{stream::Ext as _, task::Stop};
+use tangram_http::{Body, request::Ext as _};
+use tokio_util::task::AbortOnDropHandle;
+
+const BATCH_SIZE: u64 = 1_000;
+
+/// The expression that computes an object's reference count from its references.
+const EXPECTED_REFERENCE_COUNT: &str = "(
+	(select count(*) from object_children where child = objects.id) +
+	(select count(*) from process_objects where object = objects.id) +
+	(select count(*) from tags where item = objects.id)
+)";
+
+impl Server {
+	pub async fn fsck(
+		&self,
+		arg: tg::fsck::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+	> {
+		let progress = crate::progress::Handle::new();
+		let task = AbortOnDropHandle::new(tokio::spawn({
+			let progress = progress.clone();
+			let server = self.clone();
+			async move {
+				let result = server.fsck_inner(arg, &progress).await;
+				progress.finish_all();
+				match result {
+					Ok(output) => {
+						progress.output(output);
+					},
+					Err(error) => {
+						progress.error(error);
+					},
+				}
+			}
+		}));
+		let stream = progress.stream().attach(task);
+		Ok(stream)
+	}
+
+	async fn fsck_inner(
+		&self,
+		arg: tg::fsck::Arg,
+		progress: &crate::progress::Handle<tg::fsck::Output>,
+	) -> tg::Result<tg::fsck::Output> {
+		let mut output = tg::fsck::Output {
+			repaired: arg.repair,
+			..Default::default()
+		};
+
+		// Count the objects.
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+		let statement = indoc!(
+			"
+				select count(*) from objects;
+			"
+		);
+		let params = db::params![];
+		let count = connection
+			.query_one_value_into::<u64>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to count the objects"))?;
+		drop(connection);
+
+		// Verify the objects.
+		progress.start(
+			"objects".to_owned(),
+			"objects".to_owned(),
+			tg::progress::IndicatorFormat::Normal,
+			Some(0),
+			Some(count),
+		);
+		let mut after = String::new();
+		loop {
+			let Some((last, n)) = self
+				.fsck_objects_batch(&after, arg.repair, &mut output)
+				.await?
+			else {
+				break;
+			};
+			progress.increment("objects", n);
+			after = last;
+		}
+		progress.finish("objects");
+
+		// Reconcile the reference counts. This must happen after the objects are verified, because repairing them removes rows from the index.
+		progress.start(
+			"reference_counts".to_owned(),
+			"reference counts".to_owned(),
+			tg::progress::IndicatorFormat::Normal,
+			Some(0),
+			Some(count),
+		);
+		let mut after = String::new();
+		loop {
+			let Some((last, n)) = self
+				.fsck_reference_counts_batch(&after, arg.repair, &mut output)
+				.await?
+			else {
+				break;
+			};
+			progress.increment("reference_counts", n);
+			after = last;
+		}
+		progress.finish("reference_counts");
+
+		Ok(output)
+	}
+
+	async fn fsck_objects_batch(
+		&self,
+		after: &str,
+		repair: bool,
+		output: &mut tg::fsck::Output,
+	) -> tg::Result<Option<(String, u64)>> {
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+
+		// Get a batch of objects.
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select id
+				from objects
+				where id > {p}1
+				order by id
+				limit {p}2;
+			"
+		);
+		let params = db::params![after, BATCH_SIZE];
+		let ids = connection
+			.query_all_value_into::<tg::object::Id>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+		let Some(last) = ids.last().map(ToString::to_string) else {
+			return Ok(None);
+		};
+		let n = ids.len().to_u64().unwrap();
+
+		// Get the children of the objects in this batch that are not in the index. Children that are in the index are verified when they are walked.
+		let statement = formatdoc!(
+			"
+				select object_children.object, object_children.child
+				from object_children
+				left join objects on objects.id = object_children.child
+				where object_children.object > {p}1 and object_children.object <= {p}2 and objects.id is null;
+			"
+		);
+		#[derive(serde::Deserialize)]
+		struct Row {
+			object: tg::object::Id,
+			child: tg::object::Id,
+		}
+		let params = db::params![after, &last];
+		let children = connection
+			.query_all_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the object children"))?;
+		drop(connection);
+
+		// Re-hash the objects' bytes.
+		let mut missing = Vec::new();
+		let mut corrupt = Vec::new();
+		for id in ids {
+			match self.try_get_object_local(&id).await? {
+				None => {
+					missing.push(id);
+				},
+				Some(object) => {
+					if tg::object::Id::new(id.kind(), &object.bytes) != id {
+						corrupt.push(id);
+					}
+				},
+			}
+		}
+
+		// Check that the children are present in the store.
+		let unique = children
+			.iter()
+			.map(|row| row.child.clone())
+			.collect::<BTreeSet<_>>();
+		let mut absent = BTreeSet::new();
+		for child in unique {
+			if self.try_get_object_local(&child).await?.is_none() {
+				absent.insert(child);
+			}
+		}
+		output.missing_children.extend(
+			children
+				.into_iter()
+				.filter(|row| absent.contains(&row.child))
+				.map(|row| tg::fsck::MissingChild {
+					child: row.child,
+					object: row.object,
+				}),
+		);
+
+		if repair && !(missing.is_empty() && corrupt.is_empty() && absent.is_empty()) {
+			// Remove the corrupt objects from the store.
+			let arg = crate::store::DeleteBatchArg {
+				ids: corrupt.clone(),
+				now: time::OffsetDateTime::now_utc().unix_timestamp(),
+				ttl: 0,
+			};
+			self.store.delete_batch(arg).await?;
+
+			// Remove the missing and corrupt objects from the index and reset their ancestors in one transaction, so that the index is never left with incomplete ancestors marked complete.
+			let mut connection = self
+				.index
+				.write_connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+			let transaction = connection
+				.transaction()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+			let p = transaction.p();
+			let statement = formatdoc!(
+				"
+					delete from objects
+					where id = {p}1;
+				"
+			);
+			for id in missing.iter().chain(&corrupt) {
+				let params = db::params![id];
+				transaction
+					.execute(statement.clone().into(), params)
+					.await
+					.map_err(|source| tg::error!(!source, %id, "failed to delete the object"))?;
+			}
+
+			// Mark the ancestors of the removed objects and the absent children as incomplete.
+			for id in missing.iter().chain(&corrupt).chain(&absent) {
+				Self::fsck_reset_ancestors(&transaction, id).await?;
+			}
+
+			// Commit the transaction.
+			transaction
+				.commit()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to commit the transaction"))?;
+			drop(connection);
+		}
+
+		output.missing.extend(missing);
+		output.corrupt.extend(corrupt);
+
+		Ok(Some((last, n)))
+	}
+
+	async fn fsck_reset_ancestors(
+		transaction: &crate::database::Transaction<'_>,
+		id: &tg::object::Id,
+	) -> tg::Result<()> {
+		let p = transaction.p();
+		let ancestors = formatdoc!(
+			"
+				with recursive ancestors (id) as (
+					select object
+					from object_children
+					where child = {p}1
+					union
+					select object_children.object
+					from ancestors
+					join object_children on object_children.child = ancestors.id
+				)
+			"
+		);
+
+		// Mark the ancestors as incomplete. Their incomplete children are set to a placeholder, so that the complete trigger does not recompute them before every ancestor is updated.
+		let statement = formatdoc!(
+			"
+				{ancestors}
+				update objects
+				set
+					complete = 0,
+					count = null,
+					depth = null,
+					incomplete_children = 1,
+					weight = null
+				where id in (select id from ancestors);
+			"
+		);
+		let params = db::params![id];
+		transaction
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, %id, "failed to reset the ancestors"))?;
+
+		// Recompute the ancestors' incomplete children.
+		let statement = formatdoc!(
+			"
+				{ancestors}
+				update objects
+				set incomplete_children = (
+					select count(*)
+					from object_children
+					left join objects child_objects on child_objects.id = object_children.child
+					where object_children.object = objects.id and (child_objects.complete is null or child_objects.complete = 0)
+				)
+				where id in (select id from ancestors);
+			"
+		);
+		let params = db::params![id];
+		transaction
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, %id, "failed to reset the ancestors"))?;
+
+		Ok(())
+	}
+
+	async fn fsck_reference_counts_batch(
+		&self,
+		after: &str,
+		repair: bool,
+		output: &mut tg::fsck::Output,
+	) -> tg::Result<Option<(String, u64)>> {
+		let connection = self
+			.index
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+
+		// Get a batch of objects with their stored and computed reference counts.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			id: tg::object::Id,
+			reference_count: Option<u64>,
+			expected: u64,
+		}
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select
+					id,
+					reference_count,
+					{EXPECTED_REFERENCE_COUNT} as expected
+				from objects
+				where id > {p}1
+				order by id
+				limit {p}2;
+			"
+		);
+		let params = db::params![after, BATCH_SIZE];
+		let rows = connection
+			.query_all_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+		drop(connection);
+		let Some(last) = rows.last().map(|row| row.id.to_string()) else {
+			return Ok(None);
+		};
+		let n = rows.len().to_u64().unwrap();
+
+		let mut reference_counts = rows
+			.into_iter()
+			.filter(|row| row.reference_count != Some(row.expected))
+			.map(|row| tg::fsck::ReferenceCount {
+				actual: row.reference_count.unwrap_or_default(),
+				expected: row.expected,
+				id: row.id,
+			})
+			.collect::<Vec<_>>();
+
+		// Update the reference counts. The counts are recomputed in the update, so that a reference added since they were read is not lost. Objects whose counts have since become correct are not updated or reported.
+		if repair && !reference_counts.is_empty() {
+			let connection = self
+				.index
+				.write_connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+			let p = connection.p();
+			let statement = formatdoc!(
+				"
+					update objects
+					set reference_count = {EXPECTED_REFERENCE_COUNT}
+					where id = {p}1 and reference_count is distinct from {EXPECTED_REFERENCE_COUNT}
+					returning reference_count;
+				"
+			);
+			let mut repaired = Vec::with_capacity(reference_counts.len());
+			for mut reference_count in reference_counts {
+				let params = db::params![&reference_count.id];
+				let expected = connection
+					.query_optional_value_into::<u64>(statement.clone().into(), params)
+					.await
+					.map_err(|source| tg::error!(!source, id = %reference_count.id, "failed to update the reference count"))?;
+				if let Some(expected) = expected {
+					reference_count.expected = expected;
+					repaired.push(reference_count);
+				}
+			}
+			reference_counts = repaired;
+			drop(connection);
+		}
+
+		output.reference_counts.extend(reference_counts);
+
+		Ok(Some((last, n)))
+	}
+
+	pub(crate) async fn handle_fsck_request<H>(
+		handle: &H,
+		request: http::Request<Body>,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
+		// Get the accept header.
+		let accept = request
+			.parse_header::<mime::Mime, _>(http::header::ACCEPT)
+			.transpose()?;
+
+		// Get the stop signal.
+		let stop = request.extensions().get::<Stop>().cloned().unwrap();
+
+		// Get the arg.
+		let arg = request.json().await?;
+
+		// Get the stream.
+		let stream = handle.fsck(arg).await?;
+
+		// Stop the stream when the server stops.
+		let stop = async move {
+			stop.wait().await;
+		};
+		let stream = stream.take_until(stop);
+
+		let (content_type, body) = match accept
+			.as_ref()
+			.map(|accept| (accept.type_(), accept.subtype()))
+		{
+			Some((mime::TEXT, mime::EVENT_STREAM)) => {
+				let content_type = mime::TEXT_EVENT_STREAM;
+				let stream = stream.map(|result| match result {
+					Ok(event) => event.try_into(),
+					Err(error) => error.try_into(),
+				});
+				(Some(content_type), Body::with_sse_stream(stream))
+			},
+
+			_ => {
+				return Err(tg::error!(?accept, "invalid accept header"));
+			},
+		};
+
+		// Create the response.
+		let mut response = http::Response::builder();
+		if let Some(content_type) = content_type {
+			response = response.header(http::header::CONTENT_TYPE, content_type.to_string());
+		}
+		let response = response.body(body).unwrap();
+
+		Ok(response)
+	}
+}
@@ -1,12 +1,110 @@
 use crate::{Server, database::Database};
+use bytes::Bytes;
+use futures::{StreamExt as _, stream::FuturesUnordered};
 use num::ToPrimitive as _;
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 use tangram_client as tg;
 use tangram_database::{self as db, prelude::*};
 use tangram_http::{Body, response::builder::Ext as _};
 
+#[cfg(test)]
+mod tests;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
 impl Server {
 	pub async fn health(&self) -> tg::Result<tg::Health> {
+		// Get the database and process health.
+		let (database, processes) = self.database_health().await;
+
+		// Get the index health.
+		let index = if self.config.indexer.is_some() {
+			let result = self.get_index_backlog().await;
+			Some(tg::health::Index {
+				backlog: result.as_ref().ok().copied(),
+				error: result.as_ref().err().map(ToString::to_string),
+			})
+		} else {
+			None
+		};
+
+		// Get the runner health.
+		let runner = self
+			.config
+			.runner
+			.as_ref()
+			.map(|config| tg::health::Runner {
+				available_permits: self.process_semaphore.available_permits().to_u64().unwrap(),
+				concurrency: config.concurrency.to_u64().unwrap(),
+			});
+
+		// Get the store and remotes health.
+		let (store, remotes) = futures::join!(self.store_health(), self.remotes_health());
+
+		// Get the VFS health.
+		let vfs = self.config.vfs.as_ref().map(|_| tg::health::Vfs {
+			mounted: self.vfs.lock().unwrap().is_some(),
+		});
+
+		// The server is degraded if any local component is unavailable. The remotes are reported for information only, because a server that cannot reach its remotes is still able to serve requests.
+		let index_degraded = index.as_ref().is_some_and(|index| {
+			let max_backlog = self
+				.config
+				.indexer
+				.as_ref()
+				.and_then(|config| config.max_backlog);
+			match index.backlog {
+				Some(backlog) => max_backlog.is_some_and(|max_backlog| backlog > max_backlog),
+				None => true,
+			}
+		});
+		let degraded = database.error.is_some()
+			|| index_degraded
+			|| !store.reachable
+			|| vfs.as_ref().is_some_and(|vfs| !vfs.mounted);
+		let status = if degraded {
+			tg::health::Status::Degraded
+		} else {
+			tg::health::Status::Healthy
+		};
+
+		let health = tg::Health {
+			database: Some(database),
+			diagnostics: self.diagnostics.lock().unwrap().clone(),
+			index,
+			processes,
+			remotes,
+			runner,
+			status,
+			store: Some(store),
+			version: Some(self.version.clone()),
+			vfs,
+		};
+
+		Ok(health)
+	}
+
+	async fn database_health(&self) -> (tg::health::Database, Option<tg::health::Processes>) {
+		let result = tokio::time::timeout(TIMEOUT, self.processes_health())
+			.await
+			.map_err(|_| tg::error!("the database timed out"))
+			.and_then(|result| result);
+		let available_connections = match &self.database {
+			Database::Sqlite(database) => {
+				database.read_pool().available().to_u64().unwrap()
+					+ database.write_pool().available().to_u64().unwrap()
+			},
+			#[cfg(feature = "postgres")]
+			Database::Postgres(database) => database.pool().available().to_u64().unwrap(),
+		};
+		let database = tg::health::Database {
+			available_connections,
+			error: result.as_ref().err().map(ToString::to_string),
+		};
+		(database, result.ok())
+	}
+
+	async fn processes_health(&self) -> tg::Result<tg::health::Processes> {
 		// Get a database connection.
 		let connection = self
 			.database
@@ -14,7 +112,7 @@ impl Server {
 			.await
 			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
 
-		// Get the process health.
+		// Count the processes by status.
 		let permits = if self.config.runner.is_some() {
 			Some(self.process_semaphore.available_permits().to_u64().unwrap())
 		} else {
@@ -56,26 +154,47 @@ impl Server {
 		// Drop the database connection.
 		drop(connection);
 
-		let available_connections = match &self.database {
-			Database::Sqlite(database) => {
-				database.read_pool().available().to_u64().unwrap()
-					+ database.write_pool().available().to_u64().unwrap()
-			},
-			#[cfg(feature = "postgres")]
-			Database::Postgres(database) => database.pool().available().to_u64().unwrap(),
-		};
-		let database = tg::health::Database {
-			available_connections,
-		};
+		Ok(processes)
+	}
 
-		let health = tg::Health {
-			database: Some(database),
-			diagnostics: self.diagnostics.lock().unwrap().clone(),
-			processes: Some(processes),
-			version: Some(self.version.clone()),
-		};
+	async fn store_health(&self) -> tg::health::Store {
+		// Attempt to get an object that does not need to exist.
+		let id = tg::object::Id::new(tg::object::Kind::Blob, &Bytes::new());
+		let result = tokio::time::timeout(TIMEOUT, self.store.try_get(&id))
+			.await
+			.map_err(|_| tg::error!("the store timed out"))
+			.and_then(|result| result);
+		tg::health::Store {
+			backend: self.store.backend.name().to_owned(),
+			error: result.as_ref().err().map(ToString::to_string),
+			reachable: result.is_ok(),
+		}
+	}
 
-		Ok(health)
+	async fn remotes_health(&self) -> BTreeMap<String, tg::health::Remote> {
+		let clients = match self.get_remote_clients().await {
+			Ok(clients) => clients,
+			Err(error) => {
+				tracing::warn!(?error, "failed to get the remote clients");
+				return BTreeMap::new();
+			},
+		};
+		clients
+			.into_iter()
+			.map(|(name, client)| async move {
+				let result = tokio::time::timeout(TIMEOUT, client.health())
+					.await
+					.map_err(|_| tg::error!("the remote timed out"))
+					.and_then(|result| result);
+				let remote = tg::health::Remote {
+					error: result.as_ref().err().map(ToString::to_string),
+					reachable: result.is_ok(),
+				};
+				(name, remote)
+			})
+			.collect::<FuturesUnordered<_>>()
+			.collect()
+			.await
 	}
 
 	pub(crate) async fn diagnostics_task(&self) -> tg::Result<()> {
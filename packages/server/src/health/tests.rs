@@ -0,0 +1,25 @@
+use crate::test::test;
+use tangram_client as tg;
+
+/// Test that an unreachable remote is reported without degrading the server.
+#[tokio::test]
+async fn unreachable_remote() {
+	test(async move |context| {
+		let server = context.start_server().await;
+
+		// Add a remote that cannot be reached.
+		let arg = tg::remote::put::Arg {
+			token: None,
+			url: "http://localhost:1".parse().unwrap(),
+		};
+		server.put_remote("unreachable", arg).await.unwrap();
+
+		// Get the health.
+		let health = server.health().await.unwrap();
+		assert_eq!(health.status, tg::health::Status::Healthy);
+		assert!(!health.remotes["unreachable"].reachable);
+		assert!(health.index.unwrap().backlog.is_some());
+		assert!(health.database.unwrap().error.is_none());
+	})
+	.await;
+}
@@ -1,4 +1,4 @@
-use crate::{Server, store::Store};
+use crate::{Server, store::Backend};
 use futures::{
 	FutureExt as _, Stream, StreamExt, TryFutureExt as _, TryStreamExt as _, future,
 	stream::{self, FuturesUnordered},
@@ -334,12 +334,14 @@ impl Server {
 		progress: &Arc<Progress>,
 	) -> tg::Result<()> {
 		// Choose the batch parameters.
-		let (concurrency, max_objects_per_batch, max_bytes_per_batch) = match &self.store {
+		let (concurrency, max_objects_per_batch, max_bytes_per_batch) = match &self.store.backend {
 			#[cfg(feature = "foundationdb")]
-			Store::Fdb(_) => (8, 1_000, 5_000_000),
-			Store::Lmdb(_) => (1, 1_000, 5_000_000),
-			Store::Memory(_) => (1, 1, u64::MAX),
-			Store::S3(_) => (256, 1, u64::MAX),
+			Backend::Fdb(_) => (8, 1_000, 5_000_000),
+			Backend::Fs(_) => (8, 1_000, 5_000_000),
+			Backend::Lmdb(_) => (1, 1_000, 5_000_000),
+			Backend::Memory(_) => (1, 1, u64::MAX),
+			Backend::S3(_) => (256, 1, u64::MAX),
+			Backend::Tiered(_) => (1, 1_000, 5_000_000),
 		};
 
 		// Create a stream of batches.
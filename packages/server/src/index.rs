@@ -1,5 +1,9 @@
-use crate::{Server, database::Database, util::iter::Ext as _};
-use futures::{FutureExt as _, Stream, StreamExt as _, TryStreamExt as _, future};
+use crate::{
+	Server,
+	database::{Database, migration},
+	util::iter::Ext as _,
+};
+use futures::{Stream, StreamExt as _, TryStreamExt as _, future};
 use indoc::indoc;
 use num::ToPrimitive as _;
 use rusqlite as sqlite;
@@ -14,6 +18,8 @@ use tangram_http::{Body, request::Ext as _};
 use tangram_messenger::{self as messenger, Acker, prelude::*};
 use tokio_util::task::AbortOnDropHandle;
 
+pub mod rebuild;
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "snake_case", tag = "kind")]
 pub enum Message {
@@ -133,6 +139,30 @@ impl Server {
 		Ok(stream)
 	}
 
+	pub(crate) async fn get_index_backlog(&self) -> tg::Result<u64> {
+		let stream = self
+			.messenger
+			.get_stream("index".to_owned())
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the index stream"))?;
+		let stream_info = stream
+			.info()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the index stream info"))?;
+		let consumer = stream
+			.get_consumer("index".to_owned())
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the index consumer"))?;
+		let consumer_info = consumer
+			.info()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get the index consumer info"))?;
+		let backlog = stream_info
+			.last_sequence
+			.saturating_sub(consumer_info.sequence);
+		Ok(backlog)
+	}
+
 	pub(crate) async fn indexer_task(&self, config: &crate::config::Indexer) -> tg::Result<()> {
 		// Get the messages stream.
 		let stream = self.indexer_task_create_message_stream(config).await?;
@@ -976,76 +1006,10 @@ impl Server {
 	}
 }
 
-pub async fn migrate(database: &Database) -> tg::Result<()> {
-	#[allow(irrefutable_let_patterns)]
-	let Database::Sqlite(database) = database else {
-		return Ok(());
-	};
-
-	let migrations = vec![migration_0000(database).boxed()];
-
-	let connection = database
-		.connection()
-		.await
-		.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-	let version =
-		connection
-			.with(|connection| {
-				connection
-					.pragma_query_value(None, "user_version", |row| {
-						Ok(row.get_unwrap::<_, usize>(0))
-					})
-					.map_err(|source| tg::error!(!source, "failed to get the version"))
-			})
-			.await?;
-	drop(connection);
-
-	// If this path is from a newer version of Tangram, then return an error.
-	if version > migrations.len() {
-		return Err(tg::error!(
-			r"The index has run migrations from a newer version of Tangram. Please run `tg self update` to update to the latest version of Tangram."
-		));
-	}
-
-	// Run all migrations and update the version.
-	let migrations = migrations.into_iter().enumerate().skip(version);
-	for (version, migration) in migrations {
-		// Run the migration.
-		migration.await?;
-
-		// Update the version.
-		let connection = database
-			.write_connection()
-			.await
-			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-		connection
-			.with(move |connection| {
-				connection
-					.pragma_update(None, "user_version", version + 1)
-					.map_err(|source| tg::error!(!source, "failed to get the version"))
-			})
-			.await?;
-	}
-
-	Ok(())
-}
-
-async fn migration_0000(database: &db::sqlite::Database) -> tg::Result<()> {
-	let sql = include_str!("index/schema.sql");
-	let connection = database
-		.write_connection()
-		.await
-		.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-	connection
-		.with(move |connection| {
-			connection
-				.execute_batch(sql)
-				.map_err(|source| tg::error!(!source, "failed to execute the statements"))?;
-			Ok::<_, tg::Error>(())
-		})
-		.await?;
-	Ok(())
-}
+pub const MIGRATIONS: &[migration::Migration] = &[migration::Migration {
+	postgres: None,
+	sqlite: include_str!("index/schema.sql"),
+}];
 
 impl std::fmt::Display for ProcessObjectKind {
 	fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,573 @@
+use super::{
+	Message, ProcessObjectKind, PutCacheEntryMessage, PutObjectMessage, PutProcessMessage,
+	PutTagMessage,
+};
+use crate::Server;
+use futures::{Stream, StreamExt as _};
+use indoc::{formatdoc, indoc};
+use num::ToPrimitive as _;
+use std::path::PathBuf;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_either::Either;
+use tangram_futures::{stream::Ext as _, task::Stop};
+use tangram_http::{Body, request::Ext as _};
+use tangram_messenger::prelude::*;
+use tokio_util::task::AbortOnDropHandle;
+
+#[cfg(test)]
+mod tests;
+
+const BATCH_SIZE: usize = 1_000;
+
+impl Server {
+	pub async fn rebuild_index(
+		&self,
+		arg: tg::index::rebuild::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>> + Send + 'static,
+	> {
+		let progress = crate::progress::Handle::new();
+		let task = AbortOnDropHandle::new(tokio::spawn({
+			let progress = progress.clone();
+			let server = self.clone();
+			async move {
+				let result = server.rebuild_index_inner(arg, &progress).await;
+				progress.finish_all();
+				match result {
+					Ok(output) => {
+						progress.output(output);
+					},
+					Err(error) => {
+						progress.error(error);
+					},
+				}
+			}
+		}));
+		let stream = progress.stream().attach(task);
+		Ok(stream)
+	}
+
+	async fn rebuild_index_inner(
+		&self,
+		arg: tg::index::rebuild::Arg,
+		progress: &crate::progress::Handle<tg::index::rebuild::Output>,
+	) -> tg::Result<tg::index::rebuild::Output> {
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let mut output = tg::index::rebuild::Output::default();
+
+		// Truncate the index.
+		if arg.truncate {
+			self.rebuild_index_truncate().await?;
+		}
+
+		// Publish the cache entries.
+		progress.start(
+			"cache_entries".to_owned(),
+			"cache entries".to_owned(),
+			tg::progress::IndicatorFormat::Normal,
+			Some(0),
+			None,
+		);
+		output.cache_entries = self.rebuild_index_cache_entries(now, progress).await?;
+		progress.finish("cache_entries");
+
+		// Publish the objects.
+		progress.start(
+			"objects".to_owned(),
+			"objects".to_owned(),
+			tg::progress::IndicatorFormat::Normal,
+			Some(0),
+			None,
+		);
+		output.objects = self.rebuild_index_objects(now, progress).await?;
+		progress.finish("objects");
+
+		// Publish the processes.
+		output.processes = self.rebuild_index_processes(now, progress).await?;
+		progress.finish("processes");
+
+		// Publish the tags.
+		output.tags = self.rebuild_index_tags(progress).await?;
+		progress.finish("tags");
+
+		Ok(output)
+	}
+
+	async fn rebuild_index_truncate(&self) -> tg::Result<()> {
+		let mut connection = self
+			.index
+			.write_connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get an index connection"))?;
+		let transaction = connection
+			.transaction()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+
+		// Delete the rows that refer to other rows first, so that the delete triggers have as little to update as possible.
+		let tables = [
+			"tags",
+			"process_objects",
+			"process_children",
+			"processes",
+			"object_children",
+			"objects",
+			"cache_entries",
+		];
+		for table in tables {
+			let statement = format!("delete from {table};");
+			let params = db::params![];
+			transaction
+				.execute(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, %table, "failed to truncate the table"))?;
+		}
+
+		transaction
+			.commit()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to commit the transaction"))?;
+
+		Ok(())
+	}
+
+	async fn rebuild_index_cache_entries(
+		&self,
+		now: i64,
+		progress: &crate::progress::Handle<tg::index::rebuild::Output>,
+	) -> tg::Result<u64> {
+		let mut count = 0;
+		let mut ids = Vec::new();
+		let mut read_dir = match tokio::fs::read_dir(self.cache_path()).await {
+			Ok(read_dir) => read_dir,
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+				return Ok(0);
+			},
+			Err(source) => {
+				return Err(tg::error!(!source, "failed to read the cache directory"));
+			},
+		};
+		while let Some(entry) = read_dir
+			.next_entry()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to read the cache directory"))?
+		{
+			// Skip entries that are not artifacts, such as in-progress temporary files.
+			let Some(id) = entry
+				.file_name()
+				.to_str()
+				.and_then(|name| name.parse::<tg::artifact::Id>().ok())
+			else {
+				continue;
+			};
+			ids.push(id);
+			if ids.len() >= BATCH_SIZE {
+				count += self
+					.rebuild_index_publish_cache_entries(std::mem::take(&mut ids), now)
+					.await?;
+				progress.increment("cache_entries", BATCH_SIZE.to_u64().unwrap());
+			}
+		}
+		let n = ids.len().to_u64().unwrap();
+		count += self.rebuild_index_publish_cache_entries(ids, now).await?;
+		progress.increment("cache_entries", n);
+		Ok(count)
+	}
+
+	async fn rebuild_index_publish_cache_entries(
+		&self,
+		ids: Vec<tg::artifact::Id>,
+		now: i64,
+	) -> tg::Result<u64> {
+		// The cache entries' files have their times reset, so use the times their artifacts were last touched in the store.
+		let objects = ids
+			.iter()
+			.cloned()
+			.map(Into::into)
+			.collect::<Vec<tg::object::Id>>();
+		let touched_ats = self
+			.store
+			.backend
+			.try_get_touched_at_batch(&objects)
+			.await?;
+		let messages = std::iter::zip(ids, touched_ats)
+			.map(|(id, touched_at)| {
+				Message::PutCacheEntry(PutCacheEntryMessage {
+					id,
+					touched_at: touched_at.unwrap_or(now),
+				})
+			})
+			.collect();
+		self.rebuild_index_publish(messages).await
+	}
+
+	async fn rebuild_index_objects(
+		&self,
+		now: i64,
+		progress: &crate::progress::Handle<tg::index::rebuild::Output>,
+	) -> tg::Result<u64> {
+		let mut count = 0;
+		let mut after = None;
+		loop {
+			// Get a batch of ids from the store.
+			let ids = self.store.backend.list(after.as_ref(), BATCH_SIZE).await?;
+			let Some(last) = ids.last().cloned() else {
+				break;
+			};
+
+			// Get the times the objects were last touched.
+			let touched_ats = self.store.backend.try_get_touched_at_batch(&ids).await?;
+
+			// Create the messages.
+			let mut messages = Vec::with_capacity(ids.len());
+			for (id, touched_at) in std::iter::zip(ids, touched_ats) {
+				// Objects whose bytes are only in the cache directory are read through their cache reference.
+				let cache_reference = self
+					.store
+					.try_get_cache_reference(&id)
+					.await?
+					.map(|reference| reference.artifact);
+				let Some(object) = self.try_get_object_local(&id).await? else {
+					continue;
+				};
+				let data = tg::object::Data::deserialize(id.kind(), object.bytes.clone())?;
+				messages.push(Message::PutObject(PutObjectMessage {
+					cache_reference,
+					children: data.children().collect(),
+					id,
+					size: object.bytes.len().to_u64().unwrap(),
+					touched_at: touched_at.unwrap_or(now),
+				}));
+			}
+
+			// Publish the messages.
+			let n = messages.len().to_u64().unwrap();
+			count += self.rebuild_index_publish(messages).await?;
+			progress.increment("objects", n);
+
+			after = Some(last);
+		}
+		Ok(count)
+	}
+
+	async fn rebuild_index_processes(
+		&self,
+		now: i64,
+		progress: &crate::progress::Handle<tg::index::rebuild::Output>,
+	) -> tg::Result<u64> {
+		// Count the processes.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+		let statement = indoc!(
+			"
+				select count(*) from processes;
+			"
+		);
+		let params = db::params![];
+		let total = connection
+			.query_one_value_into::<u64>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to count the processes"))?;
+		drop(connection);
+		progress.start(
+			"processes".to_owned(),
+			"processes".to_owned(),
+			tg::progress::IndicatorFormat::Normal,
+			Some(0),
+			Some(total),
+		);
+
+		let mut count = 0;
+		let mut after = String::new();
+		loop {
+			// Get a batch of ids with the times the processes were last touched.
+			let connection = self
+				.database
+				.connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			#[derive(serde::Deserialize)]
+			struct Row {
+				id: tg::process::Id,
+				touched_at: Option<i64>,
+			}
+			let p = connection.p();
+			let statement = formatdoc!(
+				"
+					select id, touched_at
+					from processes
+					where id > {p}1
+					order by id
+					limit {p}2;
+				"
+			);
+			let params = db::params![after, BATCH_SIZE.to_u64().unwrap()];
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the processes"))?;
+			drop(connection);
+			let Some(last) = rows.last().map(|row| row.id.to_string()) else {
+				break;
+			};
+
+			// Create the messages.
+			let mut messages = Vec::with_capacity(rows.len());
+			for row in rows {
+				let touched_at = row.touched_at.unwrap_or(now);
+				if let Some(message) = self
+					.rebuild_index_process_message(&row.id, touched_at)
+					.await?
+				{
+					messages.push(message);
+				}
+			}
+
+			// Publish the messages.
+			let n = messages.len().to_u64().unwrap();
+			count += self.rebuild_index_publish(messages).await?;
+			progress.increment("processes", n);
+
+			after = last;
+		}
+		Ok(count)
+	}
+
+	async fn rebuild_index_process_message(
+		&self,
+		id: &tg::process::Id,
+		touched_at: i64,
+	) -> tg::Result<Option<Message>> {
+		let Some(output) = self.try_get_process_local(id).await? else {
+			return Ok(None);
+		};
+		let data = output.data;
+
+		// Get the children if the process is finished, as they are when the process finishes.
+		let children = if data.status.is_finished() {
+			let connection = self
+				.database
+				.connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			#[derive(serde::Deserialize)]
+			struct Row {
+				child: tg::process::Id,
+				path: Option<PathBuf>,
+				tag: Option<tg::Tag>,
+			}
+			let p = connection.p();
+			let statement = formatdoc!(
+				"
+					select child, path, tag
+					from process_children
+					where process = {p}1
+					order by position;
+				"
+			);
+			let params = db::params![id];
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+			drop(connection);
+			let children = rows
+				.into_iter()
+				.map(|row| tg::Referent {
+					item: row.child,
+					path: row.path,
+					tag: row.tag,
+				})
+				.collect();
+			Some(children)
+		} else {
+			None
+		};
+
+		// Get the objects.
+		let objects = std::iter::once((data.command.clone().into(), ProcessObjectKind::Command))
+			.chain(
+				data.error
+					.as_ref()
+					.map(tg::error::Data::children)
+					.into_iter()
+					.flatten()
+					.map(|object| (object, ProcessObjectKind::Error)),
+			)
+			.chain(
+				data.output
+					.as_ref()
+					.map(tg::value::Data::children)
+					.into_iter()
+					.flatten()
+					.map(|object| (object, ProcessObjectKind::Output)),
+			)
+			.collect();
+
+		let message = Message::PutProcess(PutProcessMessage {
+			children,
+			id: id.clone(),
+			objects,
+			touched_at,
+		});
+
+		Ok(Some(message))
+	}
+
+	async fn rebuild_index_tags(
+		&self,
+		progress: &crate::progress::Handle<tg::index::rebuild::Output>,
+	) -> tg::Result<u64> {
+		progress.start(
+			"tags".to_owned(),
+			"tags".to_owned(),
+			tg::progress::IndicatorFormat::Normal,
+			Some(0),
+			None,
+		);
+		let mut count = 0;
+		let mut after = String::new();
+		loop {
+			// Get a batch of tags.
+			let connection = self
+				.database
+				.connection()
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+			#[derive(serde::Deserialize)]
+			struct Row {
+				tag: String,
+				item: Either<tg::process::Id, tg::object::Id>,
+			}
+			let p = connection.p();
+			let statement = formatdoc!(
+				"
+					select tag, item
+					from tags
+					where tag > {p}1
+					order by tag
+					limit {p}2;
+				"
+			);
+			let params = db::params![after, BATCH_SIZE.to_u64().unwrap()];
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to get the tags"))?;
+			drop(connection);
+			let Some(last) = rows.last().map(|row| row.tag.clone()) else {
+				break;
+			};
+
+			// Publish the messages.
+			let messages = rows
+				.into_iter()
+				.map(|row| {
+					Message::PutTag(PutTagMessage {
+						tag: row.tag,
+						item: row.item,
+					})
+				})
+				.collect::<Vec<_>>();
+			let n = messages.len().to_u64().unwrap();
+			count += self.rebuild_index_publish(messages).await?;
+			progress.increment("tags", n);
+
+			after = last;
+		}
+		Ok(count)
+	}
+
+	async fn rebuild_index_publish(&self, messages: Vec<Message>) -> tg::Result<u64> {
+		if messages.is_empty() {
+			return Ok(0);
+		}
+		let n = messages.len();
+		let payloads = messages
+			.iter()
+			.map(|message| {
+				let payload = serde_json::to_vec(message)
+					.map_err(|source| tg::error!(!source, "failed to serialize the message"))?;
+				Ok::<_, tg::Error>(payload.into())
+			})
+			.collect::<tg::Result<Vec<_>>>()?;
+		let published = self
+			.messenger
+			.stream_batch_publish("index".to_owned(), payloads)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to publish the messages"))?
+			.await
+			.map_err(|source| tg::error!(!source, "failed to publish the messages"))?;
+		if published.len() != n {
+			return Err(tg::error!(
+				"the index stream did not accept all of the messages"
+			));
+		}
+		Ok(n.to_u64().unwrap())
+	}
+
+	pub(crate) async fn handle_rebuild_index_request<H>(
+		handle: &H,
+		request: http::Request<Body>,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
+		// Get the accept header.
+		let accept = request
+			.parse_header::<mime::Mime, _>(http::header::ACCEPT)
+			.transpose()?;
+
+		// Get the stop signal.
+		let stop = request.extensions().get::<Stop>().cloned().unwrap();
+
+		// Get the arg. Clients that predate the arg send an empty body.
+		let bytes = request.bytes().await?;
+		let arg = if bytes.is_empty() {
+			tg::index::rebuild::Arg::default()
+		} else {
+			serde_json::from_slice(&bytes)
+				.map_err(|source| tg::error!(!source, "failed to deserialize the arg"))?
+		};
+
+		// Get the stream.
+		let stream = handle.rebuild_index(arg).await?;
+
+		// Stop the stream when the server stops.
+		let stop = async move {
+			stop.wait().await;
+		};
+		let stream = stream.take_until(stop);
+
+		let (content_type, body) = match accept
+			.as_ref()
+			.map(|accept| (accept.type_(), accept.subtype()))
+		{
+			Some((mime::TEXT, mime::EVENT_STREAM)) => {
+				let content_type = mime::TEXT_EVENT_STREAM;
+				let stream = stream.map(|result| match result {
+					Ok(event) => event.try_into(),
+					Err(error) => error.try_into(),
+				});
+				(Some(content_type), Body::with_sse_stream(stream))
+			},
+
+			_ => {
+				return Err(tg::error!(?accept, "invalid accept header"));
+			},
+		};
+
+		// Create the response.
+		let mut response = http::Response::builder();
+		if let Some(content_type) = content_type {
+			response = response.header(http::header::CONTENT_TYPE, content_type.to_string());
+		}
+		let response = response.body(body).unwrap();
+
+		Ok(response)
+	}
+}
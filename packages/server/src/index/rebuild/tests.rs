@@ -0,0 +1,123 @@
+use crate::{
+	Server,
+	test::{index, test, update_object},
+};
+use bytes::Bytes;
+use indoc::formatdoc;
+use std::pin::pin;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_futures::stream::TryExt as _;
+
+/// Test that rebuilding with truncation replaces corrupt rows and removes rows for objects that are not in the store.
+#[tokio::test]
+async fn truncate() {
+	test(async move |context| {
+		let server = context.start_server().await;
+		let file = tg::file!("hello").store(&server).await.unwrap();
+		let id: tg::object::Id = file.into();
+		index(&server).await;
+		let expected = get(&server, &id).await.unwrap();
+		assert_eq!(expected.0, 1);
+
+		// Corrupt the file's row and insert a row for an object that is not in the store.
+		let missing = tg::file!("missing").id();
+		let missing: tg::object::Id = missing.into();
+		update_object(&server, &id, "complete = 0, count = 1000").await;
+		let connection = server.index.write_connection().await.unwrap();
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				insert into objects (id, complete, size)
+				values ({p}1, 1, 0);
+			"
+		);
+		let params = db::params![&missing];
+		connection.execute(statement.into(), params).await.unwrap();
+		drop(connection);
+
+		// Rebuild the index with truncation.
+		let arg = tg::index::rebuild::Arg { truncate: true };
+		let stream = server.rebuild_index(arg).await.unwrap();
+		let event = pin!(stream).try_last().await.unwrap().unwrap();
+		let tg::progress::Event::Output(output) = event else {
+			panic!("expected the output");
+		};
+		assert!(output.objects > 0);
+		index(&server).await;
+
+		// Confirm that the file's row was replaced and the row for the missing object was removed.
+		assert_eq!(get(&server, &id).await, Some(expected));
+		assert_eq!(get(&server, &missing).await, None);
+	})
+	.await;
+}
+
+/// Test that rebuilding publishes the times objects were last touched in the store.
+#[tokio::test]
+async fn touched_at() {
+	test(async move |context| {
+		let server = context.start_server().await;
+
+		// Put an object that was last touched at the epoch.
+		let data = tg::blob::Data::Leaf(tg::blob::data::Leaf {
+			bytes: Bytes::from("hello"),
+		});
+		let bytes = data.serialize().unwrap();
+		let id: tg::object::Id = tg::blob::Id::new(&bytes).into();
+		let arg = crate::store::PutArg {
+			bytes: Some(bytes),
+			cache_reference: None,
+			id: id.clone(),
+			touched_at: 0,
+		};
+		server.store.put(arg).await.unwrap();
+
+		// Rebuild the index with truncation.
+		let arg = tg::index::rebuild::Arg { truncate: true };
+		let stream = server.rebuild_index(arg).await.unwrap();
+		pin!(stream).try_last().await.unwrap();
+		index(&server).await;
+
+		// Confirm that the object's row has the time it was last touched.
+		let connection = server.index.connection().await.unwrap();
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select touched_at
+				from objects
+				where id = {p}1;
+			"
+		);
+		let params = db::params![&id];
+		let touched_at = connection
+			.query_one_value_into::<i64>(statement.into(), params)
+			.await
+			.unwrap();
+		assert_eq!(touched_at, 0);
+	})
+	.await;
+}
+
+async fn get(server: &Server, id: &tg::object::Id) -> Option<(u64, Option<u64>)> {
+	#[derive(serde::Deserialize)]
+	struct Row {
+		complete: u64,
+		count: Option<u64>,
+	}
+	let connection = server.index.connection().await.unwrap();
+	let p = connection.p();
+	let statement = formatdoc!(
+		"
+			select complete, count
+			from objects
+			where id = {p}1;
+		"
+	);
+	let params = db::params![id];
+	connection
+		.query_optional_into::<Row>(statement.into(), params)
+		.await
+		.unwrap()
+		.map(|row| (row.complete, row.count))
+}
@@ -1,5 +1,9 @@
 use self::{
-	database::Database, messenger::Messenger, runtime::Runtime, store::Store, util::fs::remove,
+	database::Database,
+	messenger::Messenger,
+	runtime::Runtime,
+	store::{Backend, Store},
+	util::fs::remove,
 };
 #[cfg(feature = "nats")]
 use async_nats as nats;
@@ -19,7 +23,7 @@ use std::{
 	path::{Path, PathBuf},
 	pin::{Pin, pin},
 	sync::{Arc, Mutex, RwLock},
-	time::Duration,
+	time::{Duration, Instant},
 };
 use tangram_client as tg;
 use tangram_database::{self as db, prelude::*};
@@ -35,6 +39,7 @@ use tower::ServiceExt as _;
 use tower_http::ServiceBuilderExt as _;
 use url::Url;
 
+mod authorization;
 mod blob;
 mod cache;
 mod check;
@@ -47,11 +52,13 @@ mod database;
 mod document;
 mod export;
 mod format;
+mod fsck;
 mod health;
 mod import;
 mod index;
 mod lockfile;
 mod messenger;
+mod metrics;
 mod module;
 mod object;
 mod pipe;
@@ -67,12 +74,16 @@ mod runtime;
 mod store;
 mod tag;
 mod temp;
+mod token;
 mod user;
 mod util;
 mod vfs;
 mod watchdog;
 
-pub use self::config::Config;
+pub use self::{
+	config::Config,
+	store::{MigrateStoreArg, migrate_store},
+};
 
 pub mod config;
 pub mod test;
@@ -91,6 +102,7 @@ pub struct Inner {
 	local_pool_handle: Option<tokio_util::task::LocalPoolHandle>,
 	lock_file: Mutex<Option<tokio::fs::File>>,
 	messenger: Messenger,
+	metrics: Arc<self::metrics::Metrics>,
 	path: PathBuf,
 	pipes: DashMap<tg::pipe::Id, pipe::Pipe>,
 	process_permits: ProcessPermits,
@@ -102,6 +114,7 @@ pub struct Inner {
 	store: Store,
 	task: Mutex<Option<Task<()>>>,
 	temp_paths: DashSet<PathBuf, fnv::FnvBuildHasher>,
+	termination_task_map: ProcessTaskMap,
 	version: String,
 	vfs: Mutex<Option<self::vfs::Server>>,
 }
@@ -122,6 +135,13 @@ struct ProcessPermit(
 
 type ProcessTaskMap = TaskMap<tg::process::Id, (), fnv::FnvBuildHasher>;
 
+/// The number of migrations that [`Server::migrate`] applied.
+#[derive(Clone, Debug, Default)]
+pub struct MigrateOutput {
+	pub database: usize,
+	pub index: usize,
+}
+
 impl Server {
 	pub async fn start(config: Config) -> tg::Result<Server> {
 		// Ensure the directory exists.
@@ -134,31 +154,7 @@ impl Server {
 		)?;
 
 		// Lock the lock file.
-		let lock_path = directory.join("lock");
-		let mut lock_file = tokio::fs::OpenOptions::new()
-			.read(true)
-			.write(true)
-			.create(true)
-			.truncate(false)
-			.open(lock_path)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to open the lock file"))?;
-		let ret = unsafe { libc::flock(lock_file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
-		if ret != 0 {
-			return Err(tg::error!(
-				source = std::io::Error::last_os_error(),
-				"failed to lock the lock file"
-			));
-		}
-		let pid = std::process::id();
-		lock_file
-			.set_len(0)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to truncate the lock file"))?;
-		lock_file
-			.write_all(pid.to_string().as_bytes())
-			.await
-			.map_err(|source| tg::error!(!source, "failed to write the pid to the lock file"))?;
+		let lock_file = Self::lock(&directory).await?;
 		let lock_file = Mutex::new(Some(lock_file));
 
 		// Verify the version file.
@@ -232,94 +228,20 @@ impl Server {
 		// Create the process task map.
 		let process_task_map = TaskMap::default();
 
+		// Create the process termination task map.
+		let termination_task_map = TaskMap::default();
+
 		// Create the compilers.
 		let compilers = RwLock::new(Vec::new());
 
 		// Create the database.
-		let database = match &config.database {
-			self::config::Database::Sqlite(options) => {
-				let initialize = Arc::new(self::database::initialize);
-				let options = db::sqlite::DatabaseOptions {
-					connections: options.connections,
-					initialize,
-					path: directory.join("database"),
-				};
-				let database = db::sqlite::Database::new(options)
-					.await
-					.map_err(|source| tg::error!(!source, "failed to create the database"))?;
-				Database::Sqlite(database)
-			},
-			self::config::Database::Postgres(options) => {
-				#[cfg(not(feature = "postgres"))]
-				{
-					let _ = options;
-					return Err(tg::error!(
-						"this version of tangram was not compiled with postgres support"
-					));
-				}
-				#[cfg(feature = "postgres")]
-				{
-					let options = db::postgres::DatabaseOptions {
-						url: options.url.clone(),
-						connections: options.connections,
-					};
-					let database = db::postgres::Database::new(options)
-						.await
-						.map_err(|source| tg::error!(!source, "failed to create the database"))?;
-					Database::Postgres(database)
-				}
-			},
-		};
+		let database = Self::create_database(&config, &directory).await?;
 
 		// Create the diagnostics.
 		let diagnostics = Mutex::new(Vec::new());
 
 		// Create the index.
-		let index = match &config.index {
-			self::config::Index::Sqlite(options) => {
-				let initialize = Arc::new(|connection: &sqlite::Connection| {
-					connection.pragma_update(None, "auto_vaccum", "incremental")?;
-					connection.pragma_update(None, "busy_timeout", "5000")?;
-					connection.pragma_update(None, "cache_size", "-20000")?;
-					connection.pragma_update(None, "foreign_keys", "on")?;
-					connection.pragma_update(None, "journal_mode", "wal")?;
-					connection.pragma_update(None, "mmap_size", "2147483648")?;
-					connection.pragma_update(None, "recursive_triggers", "on")?;
-					connection.pragma_update(None, "synchronous", "normal")?;
-					connection.pragma_update(None, "temp_store", "memory")?;
-					Ok(())
-				});
-				let options = db::sqlite::DatabaseOptions {
-					connections: options.connections,
-					initialize,
-					path: directory.join("index"),
-				};
-				let database = db::sqlite::Database::new(options)
-					.await
-					.map_err(|source| tg::error!(!source, "failed to create the index"))?;
-				Database::Sqlite(database)
-			},
-			self::config::Index::Postgres(options) => {
-				#[cfg(not(feature = "postgres"))]
-				{
-					let _ = options;
-					return Err(tg::error!(
-						"this version of tangram was not compiled with postgres support"
-					));
-				}
-				#[cfg(feature = "postgres")]
-				{
-					let options = db::postgres::DatabaseOptions {
-						url: options.url.clone(),
-						connections: options.connections,
-					};
-					let database = db::postgres::Database::new(options)
-						.await
-						.map_err(|source| tg::error!(!source, "failed to create the index"))?;
-					Database::Postgres(database)
-				}
-			},
-		};
+		let index = Self::create_index(&config, &directory).await?;
 
 		// Create the local pool handle.
 		let local_pool_handle = config
@@ -329,6 +251,13 @@ impl Server {
 
 		// Create the messenger.
 		let messenger = match &config.messenger {
+			self::config::Messenger::Local(local) => {
+				let messenger =
+					tangram_messenger::local::Messenger::new(&local.path).map_err(|source| {
+						tg::error!(!source, "failed to create the local messenger")
+					})?;
+				Messenger::Local(messenger)
+			},
 			self::config::Messenger::Memory => {
 				Messenger::Memory(tangram_messenger::memory::Messenger::new())
 			},
@@ -377,14 +306,16 @@ impl Server {
 		// Create the runtimes.
 		let runtimes = RwLock::new(HashMap::default());
 
+		// Create the metrics.
+		let metrics = Arc::new(self::metrics::Metrics::new());
+
 		// Create the store.
-		let store = match &config.store {
-			config::Store::Memory => Store::new_memory(),
-			#[cfg(feature = "foundationdb")]
-			config::Store::Fdb(fdb) => Store::new_fdb(fdb)?,
-			config::Store::Lmdb(lmdb) => Store::new_lmdb(lmdb)?,
-			config::Store::S3(s3) => Store::new_s3(s3),
-		};
+		let backend = Backend::new(&config.store)?;
+		let store = Store::new(
+			backend,
+			config.advanced.store_compression.clone(),
+			metrics.clone(),
+		);
 
 		// Create the task.
 		let task = Mutex::new(None);
@@ -416,6 +347,7 @@ impl Server {
 			local_pool_handle,
 			lock_file,
 			messenger,
+			metrics,
 			path: directory,
 			pipes,
 			process_permits,
@@ -427,19 +359,20 @@ impl Server {
 			store,
 			task,
 			temp_paths,
+			termination_task_map,
 			version,
 			vfs,
 		}));
 
-		// Migrate the database.
-		self::database::migrate(&server.database)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to migrate the database"))?;
-
-		// Migrate the index.
-		self::index::migrate(&server.index)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to migrate the index"))?;
+		// Migrate the database and the index, or verify that they have no pending migrations.
+		let migrations = [
+			("database", &server.database, self::database::MIGRATIONS),
+			("index", &server.index, self::index::MIGRATIONS),
+		];
+		for (name, database, migrations) in migrations {
+			Self::migrate_or_verify(server.config.advanced.migrate, name, database, migrations)
+				.await?;
+		}
 
 		// Set the remotes if specified in the config.
 		if let Some(remotes) = &server.config.remotes {
@@ -462,11 +395,11 @@ impl Server {
 				let p = connection.p();
 				let statement = formatdoc!(
 					"
-						insert into remotes (name, url)
-						values ({p}1, {p}2);
+						insert into remotes (name, token, url)
+						values ({p}1, {p}2, {p}3);
 					",
 				);
-				let params = db::params![&remote.name, &remote.url];
+				let params = db::params![&remote.name, &remote.token, &remote.url];
 				connection
 					.execute(statement.into(), params)
 					.await
@@ -676,7 +609,7 @@ impl Server {
 			Some(Task::spawn(|stop| {
 				let server = server.clone();
 				async move {
-					Self::serve(server.clone(), listener, stop).await;
+					Self::serve(server.clone(), Some(server.clone()), listener, stop).await;
 				}
 			}))
 		} else {
@@ -773,6 +706,18 @@ impl Server {
 					tracing::trace!("shutdown watchdog task");
 				}
 
+				// Abort the process termination tasks.
+				server.termination_task_map.abort_all();
+				let results = server.termination_task_map.wait().await;
+				for result in results {
+					if let Err(error) = result {
+						if !error.is_cancelled() {
+							tracing::error!(?error, "a process termination task panicked");
+						}
+					}
+				}
+				tracing::trace!("shutdown process termination tasks");
+
 				// Remove the runtimes.
 				server.runtimes.write().unwrap().clear();
 
@@ -881,6 +826,180 @@ impl Server {
 	}
 }
 
+impl Server {
+	/// Apply the pending database and index migrations. The server must not be running.
+	pub async fn migrate(config: &Config) -> tg::Result<MigrateOutput> {
+		// Lock the lock file.
+		let directory = tokio::fs::canonicalize(&config.directory).await.map_err(
+			|source| tg::error!(!source, %path = config.directory.display(), "failed to canonicalize directory path"),
+		)?;
+		let lock_file = Self::lock(&directory).await?;
+
+		// Migrate the database.
+		let database = Self::create_database(config, &directory).await?;
+		let database =
+			self::database::migration::migrate(&database, "database", self::database::MIGRATIONS)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to migrate the database"))?;
+
+		// Migrate the index.
+		let index = Self::create_index(config, &directory).await?;
+		let index = self::database::migration::migrate(&index, "index", self::index::MIGRATIONS)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to migrate the index"))?;
+
+		drop(lock_file);
+
+		Ok(MigrateOutput { database, index })
+	}
+
+	/// Apply the pending migrations if `migrate` is set, or otherwise verify that there are none.
+	async fn migrate_or_verify(
+		migrate: bool,
+		name: &str,
+		database: &Database,
+		migrations: &[self::database::migration::Migration],
+	) -> tg::Result<()> {
+		if migrate {
+			self::database::migration::migrate(database, name, migrations)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to migrate the {name}"))?;
+		} else {
+			let pending = self::database::migration::pending(database, name, migrations).await?;
+			if pending > 0 {
+				return Err(tg::error!(
+					"the {name} has {pending} pending migrations, run `tangram server migrate` to apply them"
+				));
+			}
+		}
+		Ok(())
+	}
+
+	async fn lock(directory: &Path) -> tg::Result<tokio::fs::File> {
+		Self::try_lock(directory).await?.ok_or_else(|| {
+			tg::error!("failed to lock the lock file, another server may be running")
+		})
+	}
+
+	/// Lock the lock file, or return `None` if another process holds it.
+	async fn try_lock(directory: &Path) -> tg::Result<Option<tokio::fs::File>> {
+		let lock_path = directory.join("lock");
+		let mut lock_file = tokio::fs::OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(false)
+			.open(lock_path)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to open the lock file"))?;
+		let ret = unsafe { libc::flock(lock_file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+		if ret != 0 {
+			let error = std::io::Error::last_os_error();
+			if error.kind() == std::io::ErrorKind::WouldBlock {
+				return Ok(None);
+			}
+			return Err(tg::error!(source = error, "failed to lock the lock file"));
+		}
+		let pid = std::process::id();
+		lock_file
+			.set_len(0)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to truncate the lock file"))?;
+		lock_file
+			.write_all(pid.to_string().as_bytes())
+			.await
+			.map_err(|source| tg::error!(!source, "failed to write the pid to the lock file"))?;
+		Ok(Some(lock_file))
+	}
+
+	async fn create_database(config: &Config, directory: &Path) -> tg::Result<Database> {
+		let database = match &config.database {
+			self::config::Database::Sqlite(options) => {
+				let initialize = Arc::new(self::database::initialize);
+				let options = db::sqlite::DatabaseOptions {
+					connections: options.connections,
+					initialize,
+					path: directory.join("database"),
+				};
+				let database = db::sqlite::Database::new(options)
+					.await
+					.map_err(|source| tg::error!(!source, "failed to create the database"))?;
+				Database::Sqlite(database)
+			},
+			self::config::Database::Postgres(options) => {
+				#[cfg(not(feature = "postgres"))]
+				{
+					let _ = options;
+					return Err(tg::error!(
+						"this version of tangram was not compiled with postgres support"
+					));
+				}
+				#[cfg(feature = "postgres")]
+				{
+					let options = db::postgres::DatabaseOptions {
+						url: options.url.clone(),
+						connections: options.connections,
+					};
+					let database = db::postgres::Database::new(options)
+						.await
+						.map_err(|source| tg::error!(!source, "failed to create the database"))?;
+					Database::Postgres(database)
+				}
+			},
+		};
+		Ok(database)
+	}
+
+	async fn create_index(config: &Config, directory: &Path) -> tg::Result<Database> {
+		let index = match &config.index {
+			self::config::Index::Sqlite(options) => {
+				let initialize = Arc::new(|connection: &sqlite::Connection| {
+					connection.pragma_update(None, "auto_vaccum", "incremental")?;
+					connection.pragma_update(None, "busy_timeout", "5000")?;
+					connection.pragma_update(None, "cache_size", "-20000")?;
+					connection.pragma_update(None, "foreign_keys", "on")?;
+					connection.pragma_update(None, "journal_mode", "wal")?;
+					connection.pragma_update(None, "mmap_size", "2147483648")?;
+					connection.pragma_update(None, "recursive_triggers", "on")?;
+					connection.pragma_update(None, "synchronous", "normal")?;
+					connection.pragma_update(None, "temp_store", "memory")?;
+					Ok(())
+				});
+				let options = db::sqlite::DatabaseOptions {
+					connections: options.connections,
+					initialize,
+					path: directory.join("index"),
+				};
+				let database = db::sqlite::Database::new(options)
+					.await
+					.map_err(|source| tg::error!(!source, "failed to create the index"))?;
+				Database::Sqlite(database)
+			},
+			self::config::Index::Postgres(options) => {
+				#[cfg(not(feature = "postgres"))]
+				{
+					let _ = options;
+					return Err(tg::error!(
+						"this version of tangram was not compiled with postgres support"
+					));
+				}
+				#[cfg(feature = "postgres")]
+				{
+					let options = db::postgres::DatabaseOptions {
+						url: options.url.clone(),
+						connections: options.connections,
+					};
+					let database = db::postgres::Database::new(options)
+						.await
+						.map_err(|source| tg::error!(!source, "failed to create the index"))?;
+					Database::Postgres(database)
+				}
+			},
+		};
+		Ok(index)
+	}
+}
+
 impl Server {
 	async fn listen(
 		url: &Url,
@@ -916,6 +1035,7 @@ impl Server {
 
 	async fn serve<H>(
 		handle: H,
+		server: Option<Server>,
 		listener: tokio_util::either::Either<tokio::net::UnixListener, tokio::net::TcpListener>,
 		stop: Stop,
 	) where
@@ -992,10 +1112,25 @@ impl Server {
 				)
 				.service_fn({
 					let handle = handle.clone();
+					let server = server.clone();
 					move |request| {
 						let handle = handle.clone();
+						let server = server.clone();
 						async move {
-							let response = Self::handle_request(&handle, request).await;
+							let start = Instant::now();
+							let method = request.method().clone();
+							let response =
+								Self::handle_request(&handle, server.as_ref(), request).await;
+							if let Some(server) = &server {
+								server
+									.metrics
+									.http_request_duration
+									.get(&[
+										("method", method.as_str()),
+										("status", response.status().as_str()),
+									])
+									.observe(start.elapsed());
+							}
 							Ok::<_, Infallible>(response)
 						}
 					}
@@ -1037,17 +1172,74 @@ impl Server {
 		task_tracker.wait().await;
 	}
 
-	async fn handle_request<H>(handle: &H, mut request: http::Request<Body>) -> http::Response<Body>
+	async fn handle_request<H>(
+		handle: &H,
+		server: Option<&Server>,
+		mut request: http::Request<Body>,
+	) -> http::Response<Body>
 	where
 		H: tg::Handle,
 	{
 		let id = tg::Id::new_uuidv7(tg::id::Kind::Request);
 		request.extensions_mut().insert(id.clone());
 
+		// Authorize the request.
+		let authorization = match server {
+			Some(server) => server.authorize(&request).await,
+			None => Ok(()),
+		};
+
+		// Route the request.
+		let response = match authorization {
+			Ok(()) => Self::route_request(handle, request).await,
+			Err(error) => Err(error),
+		};
+
+		// Handle an error.
+		let mut response = response.unwrap_or_else(|error| {
+			let status = match error.code {
+				Some(tg::error::Code::Unauthorized) => http::StatusCode::UNAUTHORIZED,
+				Some(tg::error::Code::Forbidden) => http::StatusCode::FORBIDDEN,
+				_ => http::StatusCode::INTERNAL_SERVER_ERROR,
+			};
+			if status.is_server_error() {
+				tracing::error!(?error);
+			} else {
+				tracing::debug!(?error);
+			}
+			let bytes = serde_json::to_string(&error.to_data())
+				.ok()
+				.unwrap_or_default();
+			http::Response::builder()
+				.status(status)
+				.bytes(bytes)
+				.unwrap()
+		});
+
+		// Add the request ID to the response.
+		let key = http::HeaderName::from_static("x-tg-request-id");
+		let value = http::HeaderValue::from_str(&id.to_string()).unwrap();
+		response.headers_mut().insert(key, value);
+
+		response.map(|body| {
+			Body::new(body.map_err(|error| {
+				tracing::error!(?error, "response body error");
+				error
+			}))
+		})
+	}
+
+	async fn route_request<H>(
+		handle: &H,
+		request: http::Request<Body>,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
 		let method = request.method().clone();
 		let path = request.uri().path().to_owned();
 		let path_components = path.split('/').skip(1).collect_vec();
-		let response = match (method, path_components.as_slice()) {
+		match (method, path_components.as_slice()) {
 			(http::Method::POST, ["cache"]) => Self::handle_cache_request(handle, request).boxed(),
 			(http::Method::POST, ["check"]) => Self::handle_check_request(handle, request).boxed(),
 			(http::Method::POST, ["checkin"]) => {
@@ -1068,13 +1260,20 @@ impl Server {
 			(http::Method::POST, ["format"]) => {
 				Self::handle_format_request(handle, request).boxed()
 			},
+			(http::Method::POST, ["fsck"]) => Self::handle_fsck_request(handle, request).boxed(),
 			(http::Method::GET, ["health"]) => {
 				Self::handle_server_health_request(handle, request).boxed()
 			},
+			(http::Method::GET, ["metrics"]) => {
+				Self::handle_metrics_request(handle, request).boxed()
+			},
 			(http::Method::POST, ["import"]) => {
 				Self::handle_import_request(handle, request).boxed()
 			},
 			(http::Method::POST, ["index"]) => Self::handle_index_request(handle, request).boxed(),
+			(http::Method::POST, ["index", "rebuild"]) => {
+				Self::handle_rebuild_index_request(handle, request).boxed()
+			},
 			(http::Method::POST, ["lsp"]) => Self::handle_lsp_request(handle, request).boxed(),
 			(http::Method::POST, ["pull"]) => Self::handle_pull_request(handle, request).boxed(),
 			(http::Method::POST, ["push"]) => Self::handle_push_request(handle, request).boxed(),
@@ -1212,6 +1411,17 @@ impl Server {
 				Self::handle_delete_tag_request(handle, request, tag).boxed()
 			},
 
+			// Tokens.
+			(http::Method::GET, ["tokens"]) => {
+				Self::handle_list_tokens_request(handle, request).boxed()
+			},
+			(http::Method::POST, ["tokens"]) => {
+				Self::handle_create_token_request(handle, request).boxed()
+			},
+			(http::Method::DELETE, ["tokens", id]) => {
+				Self::handle_delete_token_request(handle, request, id).boxed()
+			},
+
 			// Users.
 			(http::Method::GET, ["user"]) => Self::handle_get_user_request(handle, request).boxed(),
 
@@ -1223,31 +1433,7 @@ impl Server {
 			)
 			.boxed(),
 		}
-		.await;
-
-		// Handle an error.
-		let mut response = response.unwrap_or_else(|error| {
-			tracing::error!(?error);
-			let bytes = serde_json::to_string(&error.to_data())
-				.ok()
-				.unwrap_or_default();
-			http::Response::builder()
-				.status(http::StatusCode::INTERNAL_SERVER_ERROR)
-				.bytes(bytes)
-				.unwrap()
-		});
-
-		// Add the request ID to the response.
-		let key = http::HeaderName::from_static("x-tg-request-id");
-		let value = http::HeaderValue::from_str(&id.to_string()).unwrap();
-		response.headers_mut().insert(key, value);
-
-		response.map(|body| {
-			Body::new(body.map_err(|error| {
-				tracing::error!(?error, "response body error");
-				error
-			}))
-		})
+		.await
 	}
 }
 
@@ -1291,12 +1477,13 @@ impl tg::Handle for Server {
 
 	fn clean(
 		&self,
+		arg: tg::clean::Arg,
 	) -> impl Future<
 		Output = tg::Result<
-			impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static,
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
 		>,
 	> + Send {
-		self.clean()
+		self.clean(arg)
 	}
 
 	fn document(
@@ -1320,6 +1507,17 @@ impl tg::Handle for Server {
 		self.format(arg)
 	}
 
+	fn fsck(
+		&self,
+		arg: tg::fsck::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+		>,
+	> + Send {
+		self.fsck(arg)
+	}
+
 	fn health(&self) -> impl Future<Output = tg::Result<tg::Health>> {
 		self.health()
 	}
@@ -1344,6 +1542,19 @@ impl tg::Handle for Server {
 		self.index()
 	}
 
+	fn rebuild_index(
+		&self,
+		arg: tg::index::rebuild::Arg,
+	) -> impl Future<
+		Output = tg::Result<
+			impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>>
+			+ Send
+			+ 'static,
+		>,
+	> + Send {
+		self.rebuild_index(arg)
+	}
+
 	fn lsp(
 		&self,
 		input: impl AsyncBufRead + Send + Unpin + 'static,
@@ -1352,6 +1563,10 @@ impl tg::Handle for Server {
 		self.lsp(input, output)
 	}
 
+	fn metrics(&self) -> impl Future<Output = tg::Result<String>> {
+		self.metrics()
+	}
+
 	fn pull(
 		&self,
 		arg: tg::pull::Arg,
@@ -1731,6 +1946,26 @@ impl tg::handle::Tag for Server {
 	}
 }
 
+impl tg::handle::Token for Server {
+	fn create_token(
+		&self,
+		arg: tg::token::create::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::create::Output>> {
+		self.create_token(arg)
+	}
+
+	fn list_tokens(
+		&self,
+		arg: tg::token::list::Arg,
+	) -> impl Future<Output = tg::Result<tg::token::list::Output>> {
+		self.list_tokens(arg)
+	}
+
+	fn delete_token(&self, id: &tg::token::Id) -> impl Future<Output = tg::Result<()>> {
+		self.delete_token(id)
+	}
+}
+
 impl tg::handle::User for Server {
 	fn get_user(&self, token: &str) -> impl Future<Output = tg::Result<Option<tg::User>>> {
 		self.get_user(token)
@@ -86,11 +86,17 @@ impl Server {
 		let index = nodes.len();
 
 		#[allow(clippy::match_wildcard_for_single_variants)]
-		let artifact = match &self.store {
-			crate::store::Store::Lmdb(lmdb) => lmdb.try_get_object_data_sync(&id.clone().into())?,
-			crate::store::Store::Memory(memory) => {
+		let artifact = match &self.store.backend {
+			crate::store::Backend::Fs(fs) => fs.try_get_object_data_sync(&id.clone().into())?,
+			crate::store::Backend::Lmdb(lmdb) => {
+				lmdb.try_get_object_data_sync(&id.clone().into())?
+			},
+			crate::store::Backend::Memory(memory) => {
 				memory.try_get_object_data(&id.clone().into())?
 			},
+			crate::store::Backend::Tiered(tiered) => {
+				tiered.try_get_object_data_sync(&id.clone().into())?
+			},
 			_ => {
 				return Err(tg::error!("invalid store"));
 			},
@@ -277,11 +283,17 @@ impl Server {
 			return Ok(existing.clone());
 		}
 		#[allow(clippy::match_wildcard_for_single_variants)]
-		let graph: tg::graph::Data = match &self.store {
-			crate::store::Store::Lmdb(lmdb) => lmdb.try_get_object_data_sync(&id.clone().into())?,
-			crate::store::Store::Memory(memory) => {
+		let graph: tg::graph::Data = match &self.store.backend {
+			crate::store::Backend::Fs(fs) => fs.try_get_object_data_sync(&id.clone().into())?,
+			crate::store::Backend::Lmdb(lmdb) => {
+				lmdb.try_get_object_data_sync(&id.clone().into())?
+			},
+			crate::store::Backend::Memory(memory) => {
 				memory.try_get_object_data(&id.clone().into())?
 			},
+			crate::store::Backend::Tiered(tiered) => {
+				tiered.try_get_object_data_sync(&id.clone().into())?
+			},
 			_ => {
 				return Err(tg::error!("invalid store"));
 			},
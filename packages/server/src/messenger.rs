@@ -2,18 +2,21 @@ use bytes::Bytes;
 use tangram_messenger as messenger;
 
 pub enum Messenger {
+	Local(messenger::local::Messenger),
 	Memory(messenger::memory::Messenger),
 	#[cfg(feature = "nats")]
 	Nats(messenger::nats::Messenger),
 }
 
 pub enum Stream {
+	Local(messenger::local::Stream),
 	Memory(messenger::memory::Stream),
 	#[cfg(feature = "nats")]
 	Nats(messenger::nats::Stream),
 }
 
 pub enum Consumer {
+	Local(messenger::local::Consumer),
 	Memory(messenger::memory::Consumer),
 	#[cfg(feature = "nats")]
 	Nats(messenger::nats::Consumer),
@@ -24,6 +27,7 @@ impl messenger::Messenger for Messenger {
 
 	async fn publish(&self, subject: String, payload: Bytes) -> Result<(), messenger::Error> {
 		match self {
+			Self::Local(s) => s.publish(subject, payload).await,
 			Self::Memory(s) => s.publish(subject, payload).await,
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.publish(subject, payload).await,
@@ -36,6 +40,10 @@ impl messenger::Messenger for Messenger {
 		group: Option<String>,
 	) -> Result<impl futures::Stream<Item = messenger::Message> + 'static, messenger::Error> {
 		match self {
+			Self::Local(s) => s
+				.subscribe(subject, group)
+				.await
+				.map(futures::StreamExt::boxed),
 			Self::Memory(s) => s
 				.subscribe(subject, group)
 				.await
@@ -50,6 +58,7 @@ impl messenger::Messenger for Messenger {
 
 	async fn get_stream(&self, subject: String) -> Result<Self::Stream, messenger::Error> {
 		match self {
+			Self::Local(s) => s.get_stream(subject).await.map(Stream::Local),
 			Self::Memory(s) => s.get_stream(subject).await.map(Stream::Memory),
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.get_stream(subject).await.map(Stream::Nats),
@@ -62,6 +71,7 @@ impl messenger::Messenger for Messenger {
 		config: messenger::StreamConfig,
 	) -> Result<Self::Stream, messenger::Error> {
 		match self {
+			Self::Local(s) => s.create_stream(subject, config).await.map(Stream::Local),
 			Self::Memory(s) => s.create_stream(subject, config).await.map(Stream::Memory),
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.create_stream(subject, config).await.map(Stream::Nats),
@@ -74,6 +84,10 @@ impl messenger::Messenger for Messenger {
 		config: messenger::StreamConfig,
 	) -> Result<Self::Stream, messenger::Error> {
 		match self {
+			Self::Local(s) => s
+				.get_or_create_stream(subject, config)
+				.await
+				.map(Stream::Local),
 			Self::Memory(s) => s
 				.get_or_create_stream(subject, config)
 				.await
@@ -88,6 +102,7 @@ impl messenger::Messenger for Messenger {
 
 	async fn delete_stream(&self, subject: String) -> Result<(), messenger::Error> {
 		match self {
+			Self::Local(s) => s.delete_stream(subject).await,
 			Self::Memory(s) => s.delete_stream(subject).await,
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.delete_stream(subject).await,
@@ -100,6 +115,10 @@ impl messenger::Messenger for Messenger {
 		payload: Bytes,
 	) -> Result<impl Future<Output = Result<u64, messenger::Error>>, messenger::Error> {
 		match self {
+			Self::Local(s) => s
+				.stream_publish(name, payload)
+				.await
+				.map(futures::FutureExt::boxed),
 			Self::Memory(s) => s
 				.stream_publish(name, payload)
 				.await
@@ -119,6 +138,10 @@ impl messenger::Messenger for Messenger {
 	) -> Result<impl Future<Output = Result<Vec<u64>, messenger::Error>> + Send, messenger::Error>
 	{
 		match self {
+			Self::Local(s) => s
+				.stream_batch_publish(name, payloads)
+				.await
+				.map(futures::FutureExt::boxed),
 			Self::Memory(s) => s
 				.stream_batch_publish(name, payloads)
 				.await
@@ -137,6 +160,7 @@ impl messenger::Stream for Stream {
 
 	async fn info(&self) -> Result<messenger::StreamInfo, messenger::Error> {
 		match self {
+			Self::Local(s) => s.info().await,
 			Self::Memory(s) => s.info().await,
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.info().await,
@@ -145,6 +169,7 @@ impl messenger::Stream for Stream {
 
 	async fn get_consumer(&self, name: String) -> Result<Self::Consumer, messenger::Error> {
 		match self {
+			Self::Local(s) => s.get_consumer(name).await.map(Consumer::Local),
 			Self::Memory(s) => s.get_consumer(name).await.map(Consumer::Memory),
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.get_consumer(name).await.map(Consumer::Nats),
@@ -157,6 +182,7 @@ impl messenger::Stream for Stream {
 		config: messenger::ConsumerConfig,
 	) -> Result<Self::Consumer, messenger::Error> {
 		match self {
+			Self::Local(s) => s.create_consumer(name, config).await.map(Consumer::Local),
 			Self::Memory(s) => s.create_consumer(name, config).await.map(Consumer::Memory),
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.create_consumer(name, config).await.map(Consumer::Nats),
@@ -169,6 +195,10 @@ impl messenger::Stream for Stream {
 		config: messenger::ConsumerConfig,
 	) -> Result<Self::Consumer, messenger::Error> {
 		match self {
+			Self::Local(s) => s
+				.get_or_create_consumer(name, config)
+				.await
+				.map(Consumer::Local),
 			Self::Memory(s) => s
 				.get_or_create_consumer(name, config)
 				.await
@@ -183,6 +213,7 @@ impl messenger::Stream for Stream {
 
 	async fn delete_consumer(&self, name: String) -> Result<(), messenger::Error> {
 		match self {
+			Self::Local(s) => s.delete_consumer(name).await,
 			Self::Memory(s) => s.delete_consumer(name).await,
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.delete_consumer(name).await,
@@ -193,6 +224,7 @@ impl messenger::Stream for Stream {
 impl messenger::Consumer for Consumer {
 	async fn info(&self) -> Result<messenger::ConsumerInfo, messenger::Error> {
 		match self {
+			Self::Local(s) => s.info().await,
 			Self::Memory(s) => s.info().await,
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.info().await,
@@ -206,6 +238,7 @@ impl messenger::Consumer for Consumer {
 		messenger::Error,
 	> {
 		match self {
+			Self::Local(s) => s.subscribe().await.map(futures::StreamExt::boxed),
 			Self::Memory(s) => s.subscribe().await.map(futures::StreamExt::boxed),
 			#[cfg(feature = "nats")]
 			Self::Nats(s) => s.subscribe().await.map(futures::StreamExt::boxed),
@@ -220,6 +253,10 @@ impl messenger::Consumer for Consumer {
 		messenger::Error,
 	> {
 		match self {
+			Self::Local(s) => s
+				.batch_subscribe(config)
+				.await
+				.map(futures::StreamExt::boxed),
 			Self::Memory(s) => s
 				.batch_subscribe(config)
 				.await
@@ -0,0 +1,329 @@
+use crate::Server;
+use dashmap::DashMap;
+use indoc::indoc;
+use std::{
+	fmt::Write as _,
+	sync::atomic::{AtomicU64, Ordering},
+	time::Duration,
+};
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_http::{Body, response::builder::Ext as _};
+
+#[cfg(test)]
+mod tests;
+
+const DURATION_BUCKETS: &[f64] = &[
+	0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const LONG_DURATION_BUCKETS: &[f64] = &[
+	0.1, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 600.0, 1800.0, 3600.0, 14400.0,
+];
+
+pub struct Metrics {
+	pub http_request_duration: Family<Histogram>,
+	pub process_cache_hits: Counter,
+	pub process_cache_misses: Counter,
+	pub process_dequeue_latency: Histogram,
+	pub process_runtime_duration: Family<Histogram>,
+	pub store_get_bytes: Family<Counter>,
+	pub store_get_duration: Family<Histogram>,
+	pub store_put_bytes: Family<Counter>,
+	pub store_put_duration: Family<Histogram>,
+}
+
+#[derive(Default)]
+pub struct Counter(AtomicU64);
+
+pub struct Histogram {
+	buckets: &'static [f64],
+	counts: Vec<AtomicU64>,
+	count: AtomicU64,
+	sum: AtomicU64,
+}
+
+pub struct Family<T> {
+	map: DashMap<String, T, fnv::FnvBuildHasher>,
+	new: fn() -> T,
+}
+
+impl Metrics {
+	pub fn new() -> Self {
+		Self {
+			http_request_duration: Family::new(|| Histogram::new(DURATION_BUCKETS)),
+			process_cache_hits: Counter::default(),
+			process_cache_misses: Counter::default(),
+			process_dequeue_latency: Histogram::new(LONG_DURATION_BUCKETS),
+			process_runtime_duration: Family::new(|| Histogram::new(LONG_DURATION_BUCKETS)),
+			store_get_bytes: Family::new(Counter::default),
+			store_get_duration: Family::new(|| Histogram::new(DURATION_BUCKETS)),
+			store_put_bytes: Family::new(Counter::default),
+			store_put_duration: Family::new(|| Histogram::new(DURATION_BUCKETS)),
+		}
+	}
+
+	fn render(&self, output: &mut String) {
+		self.http_request_duration.render(
+			output,
+			"tangram_http_request_duration_seconds",
+			"The duration of HTTP requests until the response headers are sent.",
+		);
+		self.process_cache_hits.render(
+			output,
+			"tangram_process_cache_hits_total",
+			"The number of spawns that were satisfied by a cached process.",
+		);
+		self.process_cache_misses.render(
+			output,
+			"tangram_process_cache_misses_total",
+			"The number of cacheable spawns that were not satisfied by a cached process.",
+		);
+		self.process_dequeue_latency.render(
+			output,
+			"tangram_process_dequeue_latency_seconds",
+			"The time processes spent enqueued before they were dequeued.",
+		);
+		self.process_runtime_duration.render(
+			output,
+			"tangram_process_runtime_duration_seconds",
+			"The duration of processes run by this server's runtimes.",
+		);
+		self.store_get_bytes.render(
+			output,
+			"tangram_store_get_bytes_total",
+			"The number of bytes read from the store.",
+		);
+		self.store_get_duration.render(
+			output,
+			"tangram_store_get_duration_seconds",
+			"The duration of store reads.",
+		);
+		self.store_put_bytes.render(
+			output,
+			"tangram_store_put_bytes_total",
+			"The number of bytes written to the store.",
+		);
+		self.store_put_duration.render(
+			output,
+			"tangram_store_put_duration_seconds",
+			"The duration of store writes.",
+		);
+	}
+}
+
+impl Default for Metrics {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Counter {
+	pub fn increment(&self, amount: u64) {
+		self.0.fetch_add(amount, Ordering::Relaxed);
+	}
+
+	fn render(&self, output: &mut String, name: &str, help: &str) {
+		write_header(output, name, help, "counter");
+		self.render_samples(output, name, "");
+	}
+
+	fn render_samples(&self, output: &mut String, name: &str, labels: &str) {
+		let value = self.0.load(Ordering::Relaxed);
+		if labels.is_empty() {
+			writeln!(output, "{name} {value}").unwrap();
+		} else {
+			writeln!(output, "{name}{{{labels}}} {value}").unwrap();
+		}
+	}
+}
+
+impl Histogram {
+	pub fn new(buckets: &'static [f64]) -> Self {
+		let counts = buckets.iter().map(|_| AtomicU64::new(0)).collect();
+		Self {
+			buckets,
+			counts,
+			count: AtomicU64::new(0),
+			sum: AtomicU64::new(0f64.to_bits()),
+		}
+	}
+
+	pub fn observe(&self, duration: Duration) {
+		let value = duration.as_secs_f64();
+		if let Some(index) = self.buckets.iter().position(|bucket| value <= *bucket) {
+			self.counts[index].fetch_add(1, Ordering::Relaxed);
+		}
+		self.count.fetch_add(1, Ordering::Relaxed);
+		self.sum
+			.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sum| {
+				Some((f64::from_bits(sum) + value).to_bits())
+			})
+			.ok();
+	}
+
+	fn render(&self, output: &mut String, name: &str, help: &str) {
+		write_header(output, name, help, "histogram");
+		self.render_samples(output, name, "");
+	}
+
+	fn render_samples(&self, output: &mut String, name: &str, labels: &str) {
+		let separator = if labels.is_empty() { "" } else { "," };
+		let mut cumulative = 0;
+		for (bucket, count) in self.buckets.iter().zip(&self.counts) {
+			cumulative += count.load(Ordering::Relaxed);
+			writeln!(
+				output,
+				"{name}_bucket{{{labels}{separator}le=\"{bucket}\"}} {cumulative}"
+			)
+			.unwrap();
+		}
+		let count = self.count.load(Ordering::Relaxed);
+		let sum = f64::from_bits(self.sum.load(Ordering::Relaxed));
+		writeln!(
+			output,
+			"{name}_bucket{{{labels}{separator}le=\"+Inf\"}} {count}"
+		)
+		.unwrap();
+		if labels.is_empty() {
+			writeln!(output, "{name}_sum {sum}").unwrap();
+			writeln!(output, "{name}_count {count}").unwrap();
+		} else {
+			writeln!(output, "{name}_sum{{{labels}}} {sum}").unwrap();
+			writeln!(output, "{name}_count{{{labels}}} {count}").unwrap();
+		}
+	}
+}
+
+impl<T> Family<T> {
+	pub fn new(new: fn() -> T) -> Self {
+		Self {
+			map: DashMap::default(),
+			new,
+		}
+	}
+
+	pub fn get(&self, labels: &[(&str, &str)]) -> dashmap::mapref::one::Ref<'_, String, T> {
+		let key = labels
+			.iter()
+			.map(|(name, value)| format!("{name}=\"{}\"", escape(value)))
+			.collect::<Vec<_>>()
+			.join(",");
+		if let Some(value) = self.map.get(&key) {
+			return value;
+		}
+		self.map.entry(key).or_insert_with(self.new).downgrade()
+	}
+}
+
+impl Family<Counter> {
+	fn render(&self, output: &mut String, name: &str, help: &str) {
+		write_header(output, name, help, "counter");
+		for entry in self.map.iter() {
+			entry.value().render_samples(output, name, entry.key());
+		}
+	}
+}
+
+impl Family<Histogram> {
+	fn render(&self, output: &mut String, name: &str, help: &str) {
+		write_header(output, name, help, "histogram");
+		for entry in self.map.iter() {
+			entry.value().render_samples(output, name, entry.key());
+		}
+	}
+}
+
+fn write_header(output: &mut String, name: &str, help: &str, kind: &str) {
+	writeln!(output, "# HELP {name} {help}").unwrap();
+	writeln!(output, "# TYPE {name} {kind}").unwrap();
+}
+
+fn escape(value: &str) -> String {
+	value
+		.replace('\\', "\\\\")
+		.replace('"', "\\\"")
+		.replace('\n', "\\n")
+}
+
+impl Server {
+	pub async fn metrics(&self) -> tg::Result<String> {
+		let mut output = String::new();
+
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Count the processes by status.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			status: tg::process::Status,
+			count: u64,
+		}
+		let statement = indoc!(
+			"
+				select status, count(*) as count
+				from processes
+				group by status;
+			"
+		);
+		let params = db::params![];
+		let rows = connection
+			.query_all_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+		drop(connection);
+		write_header(
+			&mut output,
+			"tangram_processes",
+			"The number of processes by status.",
+			"gauge",
+		);
+		for row in rows {
+			writeln!(
+				&mut output,
+				"tangram_processes{{status=\"{}\"}} {}",
+				row.status, row.count
+			)
+			.unwrap();
+		}
+
+		// Get the indexer lag.
+		if self.config.indexer.is_some() {
+			let lag = self.get_index_backlog().await?;
+			write_header(
+				&mut output,
+				"tangram_indexer_lag",
+				"The number of index messages that have not been handled.",
+				"gauge",
+			);
+			writeln!(&mut output, "tangram_indexer_lag {lag}").unwrap();
+		}
+
+		// Render the counters and histograms.
+		self.metrics.render(&mut output);
+
+		Ok(output)
+	}
+
+	pub(crate) async fn handle_metrics_request<H>(
+		handle: &H,
+		_request: http::Request<Body>,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
+		let output = handle.metrics().await?;
+		let response = http::Response::builder()
+			.header(
+				http::header::CONTENT_TYPE,
+				"text/plain; version=0.0.4; charset=utf-8",
+			)
+			.bytes(output)
+			.unwrap();
+		Ok(response)
+	}
+}
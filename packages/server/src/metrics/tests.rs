@@ -0,0 +1,96 @@
+use super::{Counter, Family, Histogram, Metrics};
+use indoc::indoc;
+use std::time::Duration;
+
+/// Test rendering a counter.
+#[test]
+fn counter() {
+	let counter = Counter::default();
+	counter.increment(2);
+	counter.increment(1);
+	let mut output = String::new();
+	counter.render(&mut output, "tangram_test_total", "A test counter.");
+	let expected = indoc!(
+		"
+			# HELP tangram_test_total A test counter.
+			# TYPE tangram_test_total counter
+			tangram_test_total 3
+		"
+	);
+	assert_eq!(output, expected);
+}
+
+/// Test rendering a histogram, including an observation that exceeds every bucket.
+#[test]
+fn histogram() {
+	let histogram = Histogram::new(&[0.1, 1.0]);
+	histogram.observe(Duration::from_millis(250));
+	histogram.observe(Duration::from_millis(500));
+	histogram.observe(Duration::from_secs(2));
+	let mut output = String::new();
+	histogram.render(&mut output, "tangram_test_seconds", "A test histogram.");
+	let expected = indoc!(
+		r#"
+			# HELP tangram_test_seconds A test histogram.
+			# TYPE tangram_test_seconds histogram
+			tangram_test_seconds_bucket{le="0.1"} 0
+			tangram_test_seconds_bucket{le="1"} 2
+			tangram_test_seconds_bucket{le="+Inf"} 3
+			tangram_test_seconds_sum 2.75
+			tangram_test_seconds_count 3
+		"#
+	);
+	assert_eq!(output, expected);
+}
+
+/// Test rendering families with labels that must be escaped.
+#[test]
+fn family() {
+	let counters = Family::new(Counter::default);
+	counters
+		.get(&[("method", "GET"), ("path", "/a\"b\\c\nd")])
+		.increment(1);
+	let mut output = String::new();
+	counters.render(&mut output, "tangram_test_total", "A test family.");
+	let expected = indoc!(
+		r#"
+			# HELP tangram_test_total A test family.
+			# TYPE tangram_test_total counter
+			tangram_test_total{method="GET",path="/a\"b\\c\nd"} 1
+		"#
+	);
+	assert_eq!(output, expected);
+
+	let histograms = Family::new(|| Histogram::new(&[1.0]));
+	histograms
+		.get(&[("method", "GET")])
+		.observe(Duration::from_millis(500));
+	let mut output = String::new();
+	histograms.render(&mut output, "tangram_test_seconds", "A test family.");
+	let expected = indoc!(
+		r#"
+			# HELP tangram_test_seconds A test family.
+			# TYPE tangram_test_seconds histogram
+			tangram_test_seconds_bucket{method="GET",le="1"} 1
+			tangram_test_seconds_bucket{method="GET",le="+Inf"} 1
+			tangram_test_seconds_sum{method="GET"} 0.5
+			tangram_test_seconds_count{method="GET"} 1
+		"#
+	);
+	assert_eq!(output, expected);
+}
+
+/// Test that every metric is rendered with a header and that unlabeled metrics have samples.
+#[test]
+fn metrics() {
+	let metrics = Metrics::new();
+	let mut output = String::new();
+	metrics.render(&mut output);
+	let types = output
+		.lines()
+		.filter(|line| line.starts_with("# TYPE "))
+		.count();
+	assert_eq!(types, 9);
+	assert!(output.contains("tangram_process_cache_hits_total 0\n"));
+	assert!(output.contains("tangram_process_dequeue_latency_seconds_count 0\n"));
+}
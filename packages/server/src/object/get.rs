@@ -55,11 +55,15 @@ impl Server {
 		file: &mut Option<(tg::artifact::Id, Option<PathBuf>, std::fs::File)>,
 	) -> tg::Result<Option<tg::object::get::Output>> {
 		#[allow(clippy::match_wildcard_for_single_variants)]
-		let mut bytes = match &self.store {
-			crate::store::Store::Lmdb(lmdb) => lmdb.try_get_sync(id)?,
-			crate::store::Store::Memory(memory) => memory.try_get(id),
+		let mut bytes = match &self.store.backend {
+			crate::store::Backend::Fs(fs) => fs.try_get_sync(id)?,
+			crate::store::Backend::Lmdb(lmdb) => lmdb.try_get_sync(id)?,
+			crate::store::Backend::Memory(memory) => memory.try_get(id),
+			crate::store::Backend::Tiered(tiered) => tiered.try_get_sync(id)?,
 			_ => return Err(tg::error!("invalid store")),
-		};
+		}
+		.map(|bytes| Ok::<_, tg::Error>(crate::store::decompress(bytes)?.into_owned()))
+		.transpose()?;
 
 		// If the bytes were not in the store, then attempt to read the bytes from the cache.
 		if bytes.is_none() {
@@ -198,13 +202,17 @@ impl Server {
 	) -> tg::Result<Option<Bytes>> {
 		// Get the cache reference.
 		#[allow(clippy::match_wildcard_for_single_variants)]
-		let cache_reference = match &self.store {
-			crate::store::Store::Lmdb(lmdb) => {
+		let cache_reference = match &self.store.backend {
+			crate::store::Backend::Fs(fs) => fs.try_get_cache_reference_sync(&id.clone().into())?,
+			crate::store::Backend::Lmdb(lmdb) => {
 				lmdb.try_get_cache_reference_sync(&id.clone().into())?
 			},
-			crate::store::Store::Memory(memory) => {
+			crate::store::Backend::Memory(memory) => {
 				memory.try_get_cache_reference(&id.clone().into())
 			},
+			crate::store::Backend::Tiered(tiered) => {
+				tiered.try_get_cache_reference_sync(&id.clone().into())?
+			},
 			_ => {
 				return Err(tg::error!("invalid store"));
 			},
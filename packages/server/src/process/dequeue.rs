@@ -1,6 +1,7 @@
 use crate::Server;
 use futures::{StreamExt as _, future, stream};
 use indoc::formatdoc;
+use itertools::Itertools as _;
 use num::ToPrimitive as _;
 use std::time::Duration;
 use tangram_client as tg;
@@ -10,10 +11,15 @@ use tangram_http::{Body, request::Ext as _};
 use tangram_messenger::prelude::*;
 use tokio_stream::wrappers::IntervalStream;
 
+#[cfg(test)]
+mod tests;
+
+const CANDIDATE_BATCH_SIZE: usize = 256;
+
 impl Server {
 	pub async fn try_dequeue_process(
 		&self,
-		_arg: tg::process::dequeue::Arg,
+		arg: tg::process::dequeue::Arg,
 	) -> tg::Result<Option<tg::process::dequeue::Output>> {
 		// Create the event stream.
 		let created = self
@@ -29,47 +35,161 @@ impl Server {
 
 		// Attempt to dequeue a process after each event.
 		while let Some(()) = events.next().await {
-			let connection = self
-				.database
-				.write_connection()
-				.await
-				.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
-			let p = connection.p();
+			if let Some(output) = self.try_dequeue_process_inner(&arg).await? {
+				return Ok(Some(output));
+			}
+		}
+
+		Ok(None)
+	}
+
+	async fn try_dequeue_process_inner(
+		&self,
+		arg: &tg::process::dequeue::Arg,
+	) -> tg::Result<Option<tg::process::dequeue::Output>> {
+		// If the runner supports no hosts, then no process can be dequeued.
+		if arg.hosts.as_ref().is_some_and(Vec::is_empty) {
+			return Ok(None);
+		}
+
+		// Get a database connection.
+		let connection = self
+			.database
+			.write_connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+		let p = connection.p();
+
+		// Get the candidates whose host matches, in the order of their effective enqueue times. Labels are matched after the candidates are fetched, so page through the candidates until one matches or there are none left.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			id: tg::process::Id,
+			effective_enqueued_at: Option<i64>,
+			enqueued_at: Option<i64>,
+			labels: Option<db::value::Json<Vec<String>>>,
+		}
+		let hosts = arg.hosts.iter().flatten().collect::<Vec<_>>();
+		let host = if hosts.is_empty() {
+			String::new()
+		} else {
+			let placeholders = (0..hosts.len())
+				.map(|index| format!("{p}{}", index + 3))
+				.join(", ");
+			format!("and host in ({placeholders})")
+		};
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let timeout = self.config.advanced.process_dequeue_timeout;
+		let time = now - timeout.as_secs().to_i64().unwrap();
+		let mut cursor: Option<(i64, tg::process::Id)> = None;
+		loop {
+			let after = if cursor.is_some() {
+				let n = hosts.len() + 3;
+				let m = n + 1;
+				format!(
+					"and (effective_enqueued_at > {p}{n} or (effective_enqueued_at = {p}{n} and id > {p}{m}))"
+				)
+			} else {
+				String::new()
+			};
 			let statement = formatdoc!(
 				"
-					update processes
-					set
-						status = 'dequeued',
-						dequeued_at = {p}1
-					where id in (
-						select id
+					select id, effective_enqueued_at, enqueued_at, labels
+					from (
+						select id, effective_enqueued_at, enqueued_at, labels
 						from processes
-						where
-							status = 'enqueued' or
-							(status = 'dequeued' and dequeued_at <= {p}2)
-						order by enqueued_at
-						limit 1
-					)
-					returning id;
+						where status = 'enqueued' {host} {after}
+						order by effective_enqueued_at, id
+						limit {p}2
+					) as enqueued
+					union all
+					select id, effective_enqueued_at, enqueued_at, labels
+					from (
+						select id, effective_enqueued_at, enqueued_at, labels
+						from processes
+						where status = 'dequeued' and dequeued_at <= {p}1 {host} {after}
+						order by effective_enqueued_at, id
+						limit {p}2
+					) as dequeued
+					order by effective_enqueued_at, id
+					limit {p}2;
 				"
 			);
-			let now = time::OffsetDateTime::now_utc().unix_timestamp();
-			let timeout = self.config.advanced.process_dequeue_timeout;
-			let time = now - timeout.as_secs().to_i64().unwrap();
-			let params = db::params![now, time];
-			let Some(id) = connection
-				.query_optional_value_into(statement.into(), params)
+			let mut params = db::params![time, CANDIDATE_BATCH_SIZE];
+			params.extend(hosts.iter().flat_map(|host| db::params![host]));
+			if let Some((effective_enqueued_at, id)) = &cursor {
+				params.extend(db::params![effective_enqueued_at, id]);
+			}
+			let rows = connection
+				.query_all_into::<Row>(statement.into(), params)
 				.await
-				.map_err(|source| tg::error!(!source, "failed to execute the statement"))?
-			else {
-				continue;
-			};
-			return Ok(Some(tg::process::dequeue::Output { process: id }));
+				.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+			let full = rows.len() == CANDIDATE_BATCH_SIZE;
+			cursor = rows.last().and_then(|row| {
+				row.effective_enqueued_at
+					.map(|effective_enqueued_at| (effective_enqueued_at, row.id.clone()))
+			});
+
+			// Attempt to dequeue the first candidate whose labels are all present.
+			for row in rows {
+				let labels = row.labels.map(|labels| labels.0).unwrap_or_default();
+				if !labels.iter().all(|label| arg.labels.contains(label)) {
+					continue;
+				}
+				let statement = formatdoc!(
+					"
+						update processes
+						set
+							status = 'dequeued',
+							dequeued_at = {p}1
+						where
+							id = {p}3 and
+							(status = 'enqueued' or (status = 'dequeued' and dequeued_at <= {p}2))
+						returning id;
+					"
+				);
+				let params = db::params![now, time, row.id];
+				let Some(id) = connection
+					.query_optional_value_into(statement.into(), params)
+					.await
+					.map_err(|source| tg::error!(!source, "failed to execute the statement"))?
+				else {
+					continue;
+				};
+				if let Some(enqueued_at) = row.enqueued_at {
+					let latency = (now - enqueued_at).to_u64().unwrap_or_default();
+					self.metrics
+						.process_dequeue_latency
+						.observe(Duration::from_secs(latency));
+				}
+				return Ok(Some(tg::process::dequeue::Output { process: id }));
+			}
+
+			// Stop if there are no more candidates.
+			if !full || cursor.is_none() {
+				break;
+			}
 		}
 
 		Ok(None)
 	}
 
+	/// Get the time that orders a process in the queue. Each level of priority moves a process ahead in the queue by the aging duration, so low priority processes are not starved.
+	pub(crate) fn get_process_effective_enqueued_at(
+		&self,
+		enqueued_at: Option<i64>,
+		priority: Option<i64>,
+	) -> Option<i64> {
+		let aging = self
+			.config
+			.advanced
+			.process_priority_aging
+			.as_secs()
+			.to_i64()
+			.unwrap();
+		let priority = priority.unwrap_or_default();
+		enqueued_at.map(|enqueued_at| enqueued_at.saturating_sub(priority.saturating_mul(aging)))
+	}
+
 	pub(crate) async fn handle_dequeue_process_request<H>(
 		handle: &H,
 		request: http::Request<Body>,
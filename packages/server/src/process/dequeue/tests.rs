@@ -0,0 +1,125 @@
+use super::CANDIDATE_BATCH_SIZE;
+use crate::{
+	Server,
+	test::{Context, test},
+};
+use tangram_client as tg;
+use tangram_temp::Temp;
+
+/// Test that only the processes whose host the runner supports are dequeued.
+#[tokio::test]
+async fn hosts() {
+	test(async move |context| {
+		let server = start(context).await;
+		let a = spawn(&server, "a", &[], None).await;
+		let b = spawn(&server, "b", &[], None).await;
+		assert_eq!(dequeue(&server, Some(&[]), &[]).await, None);
+		assert_eq!(dequeue(&server, Some(&["b"]), &[]).await, Some(b));
+		assert_eq!(dequeue(&server, Some(&["b"]), &[]).await, None);
+		assert_eq!(dequeue(&server, None, &[]).await, Some(a));
+	})
+	.await;
+}
+
+/// Test that only the processes whose labels are all present on the runner are dequeued.
+#[tokio::test]
+async fn labels() {
+	test(async move |context| {
+		let server = start(context).await;
+		let a = spawn(&server, "host", &["gpu", "large"], None).await;
+		let b = spawn(&server, "host", &["gpu"], None).await;
+		assert_eq!(dequeue(&server, None, &[]).await, None);
+		assert_eq!(dequeue(&server, None, &["gpu"]).await, Some(b));
+		assert_eq!(dequeue(&server, None, &["gpu"]).await, None);
+		assert_eq!(
+			dequeue(&server, None, &["large", "gpu", "other"]).await,
+			Some(a)
+		);
+	})
+	.await;
+}
+
+/// Test that a matching process is dequeued after more than a batch of candidates that do not match.
+#[tokio::test]
+async fn paging() {
+	test(async move |context| {
+		let server = start(context).await;
+		for _ in 0..CANDIDATE_BATCH_SIZE {
+			spawn(&server, "host", &["gpu"], None).await;
+		}
+		let process = spawn(&server, "host", &[], None).await;
+		assert_eq!(dequeue(&server, None, &[]).await, Some(process));
+		assert_eq!(dequeue(&server, None, &[]).await, None);
+	})
+	.await;
+}
+
+/// Test that processes are dequeued in the order of their enqueue times moved ahead by their priorities.
+#[tokio::test]
+async fn priority() {
+	test(async move |context| {
+		let server = start(context).await;
+		let a = spawn(&server, "host", &[], Some(-1)).await;
+		let b = spawn(&server, "host", &[], None).await;
+		let c = spawn(&server, "host", &[], Some(1)).await;
+		let mut order = Vec::new();
+		while let Some(process) = dequeue(&server, None, &[]).await {
+			order.push(process);
+		}
+		assert_eq!(order, vec![c, b, a]);
+	})
+	.await;
+}
+
+async fn start(context: &mut Context) -> Server {
+	// Do not start a runner, so that only the test dequeues processes.
+	let temp = Temp::new();
+	let mut config = crate::test::config(temp.path());
+	config.runner = None;
+	context
+		.start_server_with_temp_and_config(temp, config)
+		.await
+}
+
+async fn spawn(
+	server: &Server,
+	host: &str,
+	labels: &[&str],
+	priority: Option<i64>,
+) -> tg::process::Id {
+	let executable = tg::command::Executable::Artifact(tg::command::ArtifactExecutable {
+		artifact: tg::file!("").into(),
+		path: None,
+	});
+	let command = tg::Command::builder(host.to_owned(), executable).build();
+	command.store(server).await.unwrap();
+	let arg = tg::process::spawn::Arg {
+		cached: Some(false),
+		command: Some(command.id()),
+		labels: labels.iter().map(ToString::to_string).collect(),
+		priority,
+		..Default::default()
+	};
+	server
+		.try_spawn_process(arg)
+		.await
+		.unwrap()
+		.unwrap()
+		.process
+}
+
+async fn dequeue(
+	server: &Server,
+	hosts: Option<&[&str]>,
+	labels: &[&str],
+) -> Option<tg::process::Id> {
+	let arg = tg::process::dequeue::Arg {
+		hosts: hosts.map(|hosts| hosts.iter().map(ToString::to_string).collect()),
+		labels: labels.iter().map(ToString::to_string).collect(),
+	};
+	server
+		.try_dequeue_process_inner(&arg)
+		.await
+		.unwrap()
+		.map(|output| output.process)
+}
@@ -38,6 +38,7 @@ impl Server {
 			mut error,
 			output,
 			mut exit,
+			usage,
 			..
 		} = arg;
 
@@ -134,9 +135,10 @@ impl Server {
 					output = {p}4,
 					exit = {p}5,
 					status = {p}6,
-					touched_at = {p}7
+					touched_at = {p}7,
+					usage = {p}8
 				where
-					id = {p}8 and
+					id = {p}9 and
 					status != 'finished';
 			"
 		);
@@ -149,6 +151,7 @@ impl Server {
 			exit,
 			tg::process::Status::Finished,
 			now,
+			usage.map(db::value::Json),
 			id,
 		];
 		let n = connection
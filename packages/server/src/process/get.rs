@@ -52,8 +52,11 @@ impl Server {
 			expected_checksum: Option<tg::Checksum>,
 			finished_at: Option<i64>,
 			host: String,
+			labels: Option<db::value::Json<Vec<String>>>,
+			limits: Option<db::value::Json<tg::process::Limits>>,
 			log: Option<tg::blob::Id>,
 			output: Option<db::value::Json<tg::value::Data>>,
+			priority: Option<i64>,
 			retry: bool,
 			mounts: Option<db::value::Json<Vec<tg::process::data::Mount>>>,
 			network: bool,
@@ -62,6 +65,8 @@ impl Server {
 			stderr: Option<tg::process::Stdio>,
 			stdin: Option<tg::process::Stdio>,
 			stdout: Option<tg::process::Stdio>,
+			timeout: Option<u64>,
+			usage: Option<db::value::Json<tg::process::Usage>>,
 		}
 		let p = connection.p();
 		let statement = formatdoc!(
@@ -87,7 +92,12 @@ impl Server {
 					status,
 					stderr,
 					stdin,
-					stdout
+					stdout,
+					limits,
+					usage,
+					timeout,
+					labels,
+					priority
 				from processes
 				where id = {p}1;
 			"
@@ -111,8 +121,11 @@ impl Server {
 				expected_checksum: row.expected_checksum,
 				finished_at: row.finished_at,
 				host: row.host,
+				labels: row.labels.map(|labels| labels.0).unwrap_or_default(),
+				limits: row.limits.map(|limits| limits.0),
 				log: row.log,
 				output: row.output.map(|output| output.0),
+				priority: row.priority,
 				retry: row.retry,
 				mounts: row.mounts.map(|output| output.0).unwrap_or_default(),
 				network: row.network,
@@ -121,6 +134,8 @@ impl Server {
 				stderr: row.stderr,
 				stdin: row.stdin,
 				stdout: row.stdout,
+				timeout: row.timeout,
+				usage: row.usage.map(|usage| usage.0),
 			};
 			tg::process::get::Output { data }
 		});
@@ -159,7 +174,12 @@ impl Server {
 					status,
 					stderr,
 					stdin,
-					stdout
+					stdout,
+					limits,
+					usage,
+					timeout,
+					labels,
+					priority
 				from processes
 				where id = ?1;
 			"
@@ -267,6 +287,31 @@ impl Server {
 			.map_err(|source| tg::error!(!source, "expected a string"))?
 			.map(|s| s.parse())
 			.transpose()?;
+		let limits = row
+			.get::<_, Option<String>>(21)
+			.map_err(|source| tg::error!(!source, "expected a string"))?
+			.map(|s| serde_json::from_str(&s))
+			.transpose()
+			.map_err(|source| tg::error!(!source, "failed to deserialize"))?;
+		let usage = row
+			.get::<_, Option<String>>(22)
+			.map_err(|source| tg::error!(!source, "expected a string"))?
+			.map(|s| serde_json::from_str(&s))
+			.transpose()
+			.map_err(|source| tg::error!(!source, "failed to deserialize"))?;
+		let timeout = row
+			.get::<_, Option<u64>>(23)
+			.map_err(|source| tg::error!(!source, "expected an integer"))?;
+		let labels = row
+			.get::<_, Option<String>>(24)
+			.map_err(|source| tg::error!(!source, "expected a string"))?
+			.map(|s| serde_json::from_str(&s))
+			.transpose()
+			.map_err(|source| tg::error!(!source, "failed to deserialize"))?
+			.unwrap_or_default();
+		let priority = row
+			.get::<_, Option<i64>>(25)
+			.map_err(|source| tg::error!(!source, "expected an integer"))?;
 
 		// Get the children.
 		let statement = indoc!(
@@ -318,8 +363,11 @@ impl Server {
 			expected_checksum,
 			finished_at,
 			host,
+			labels,
+			limits,
 			log,
 			output,
+			priority,
 			retry,
 			mounts,
 			network,
@@ -328,6 +376,8 @@ impl Server {
 			stderr,
 			stdin,
 			stdout,
+			timeout,
+			usage,
 		};
 
 		Ok(Some(data))
@@ -416,7 +466,12 @@ impl Server {
 					status,
 					stderr,
 					stdin,
-					stdout
+					stdout,
+					limits,
+					usage,
+					timeout,
+					labels,
+					priority
 				from unnest($1) as ids (id)
 				left join processes on processes.id = ids.id;
 			"
@@ -487,6 +542,26 @@ impl Server {
 					.get::<_, Option<String>>(21)
 					.map(|s| s.parse())
 					.transpose()?;
+				let limits = row
+					.get::<_, Option<String>>(22)
+					.map(|s| serde_json::from_str(&s))
+					.transpose()
+					.map_err(|source| tg::error!(!source, "failed to deserialize"))?;
+				let usage = row
+					.get::<_, Option<String>>(23)
+					.map(|s| serde_json::from_str(&s))
+					.transpose()
+					.map_err(|source| tg::error!(!source, "failed to deserialize"))?;
+				let timeout = row
+					.get::<_, Option<i64>>(24)
+					.map(|timeout| timeout.to_u64().unwrap());
+				let labels = row
+					.get::<_, Option<String>>(25)
+					.map(|s| serde_json::from_str(&s))
+					.transpose()
+					.map_err(|source| tg::error!(!source, "failed to deserialize"))?
+					.unwrap_or_default();
+				let priority = row.get::<_, Option<i64>>(26);
 				let data = tg::process::Data {
 					actual_checksum,
 					cacheable,
@@ -500,8 +575,11 @@ impl Server {
 					expected_checksum,
 					finished_at,
 					host,
+					labels,
+					limits,
 					log,
 					output,
+					priority,
 					retry,
 					mounts,
 					network,
@@ -510,6 +588,8 @@ impl Server {
 					stderr,
 					stdin,
 					stdout,
+					timeout,
+					usage,
 				};
 				let output = tg::process::get::Output { data };
 				Ok::<_, tg::Error>(Some(output))
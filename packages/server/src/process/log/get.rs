@@ -1,10 +1,12 @@
 use super::reader::Reader;
 use crate::Server;
 use futures::{FutureExt as _, Stream, StreamExt as _, TryStreamExt as _, future, stream};
+use indoc::formatdoc;
 use itertools::Itertools as _;
 use num::ToPrimitive as _;
 use std::time::Duration;
 use tangram_client::{self as tg, prelude::*};
+use tangram_database::{self as db, prelude::*};
 use tangram_futures::{stream::Ext as _, task::Stop};
 use tangram_http::{Body, request::Ext as _, response::builder::Ext as _};
 use tangram_messenger::prelude::*;
@@ -12,6 +14,9 @@ use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
 use tokio_stream::wrappers::IntervalStream;
 use tokio_util::task::AbortOnDropHandle;
 
+#[cfg(test)]
+mod tests;
+
 impl Server {
 	pub async fn try_get_process_log_stream(
 		&self,
@@ -117,15 +122,43 @@ impl Server {
 			// Get the process's status.
 			let status = self.get_current_process_status_local(id).await?;
 
+			// Get the range of bytes written between the arg's since and until.
+			let (start, end) = self.get_process_log_range(id, &arg).await?;
+			let mut limit = false;
+
 			// Send as many data events as possible.
 			loop {
 				// Get the position.
-				let position = reader
+				let mut position = reader
 					.stream_position()
 					.await
 					.map_err(|source| tg::error!(!source, "failed to get the stream position"))?;
 
+				// Move the position into the range, or stop if it has left the range.
+				let backward = arg.length.is_some_and(|length| length < 0);
+				let clamped = if backward {
+					end.map_or(position, |end| position.min(end))
+				} else {
+					position.max(start)
+				};
+				if clamped != position {
+					position = reader
+						.seek(std::io::SeekFrom::Start(clamped))
+						.await
+						.map_err(|source| tg::error!(!source, "failed to seek the reader"))?;
+				}
+				let remaining = if backward {
+					position.saturating_sub(start)
+				} else {
+					end.map_or(u64::MAX, |end| end.saturating_sub(position))
+				};
+				if remaining == 0 {
+					limit = backward || end.is_some();
+					break;
+				}
+
 				// Determine the size.
+				let size = size.min(remaining);
 				let size = match arg.length {
 					None => size,
 					Some(length) => {
@@ -166,8 +199,10 @@ impl Server {
 				}
 				data.truncate(n);
 				let chunk = tg::process::log::get::Chunk {
-					position,
 					bytes: data.into(),
+					position,
+					stream: None,
+					timestamp: None,
 				};
 
 				// Update the state.
@@ -187,10 +222,18 @@ impl Server {
 					break;
 				}
 
+				// Split the chunk by entry. If reading backwards, then reverse the chunks.
+				let mut chunks = self.split_process_log_chunk(id, chunk, &arg).await?;
+				if arg.length.is_some_and(|length| length < 0) {
+					chunks.reverse();
+				}
+
 				// Send the data.
-				let result = sender.try_send(Ok(tg::process::log::get::Event::Chunk(chunk)));
-				if result.is_err() {
-					return Ok(());
+				for chunk in chunks {
+					let result = sender.try_send(Ok(tg::process::log::get::Event::Chunk(chunk)));
+					if result.is_err() {
+						return Ok(());
+					}
 				}
 			}
 
@@ -204,7 +247,7 @@ impl Server {
 			} else {
 				false
 			};
-			if end || status.is_finished() {
+			if end || limit || status.is_finished() {
 				let result = sender.try_send(Ok(tg::process::log::get::Event::End));
 				if result.is_err() {
 					return Ok(());
@@ -219,6 +262,162 @@ impl Server {
 		Ok(())
 	}
 
+	async fn split_process_log_chunk(
+		&self,
+		id: &tg::process::Id,
+		chunk: tg::process::log::get::Chunk,
+		arg: &tg::process::log::get::Arg,
+	) -> tg::Result<Vec<tg::process::log::get::Chunk>> {
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Get the log entries that overlap the chunk.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			position: u64,
+			length: u64,
+			stream: tg::process::log::Stream,
+			timestamp: i64,
+		}
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select position, length, stream, timestamp
+				from process_log_entries
+				where process = {p}1 and position < {p}3 and position + length > {p}2
+				order by position;
+			"
+		);
+		let start = chunk.position;
+		let end = chunk.position + chunk.bytes.len().to_u64().unwrap();
+		let params = db::params![id, start, end];
+		let rows = connection
+			.query_all_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
+		// Drop the database connection.
+		drop(connection);
+
+		// Split the chunk. Bytes that are not covered by an entry have an unknown stream, so they are omitted if the arg has a stream filter. Entries are written concurrently, so an entry within the range may have been written outside the arg's time range, and such entries are omitted.
+		let filter = arg.stream.is_some();
+		let contains = |row: &Row| {
+			arg.since.is_none_or(|since| row.timestamp >= since)
+				&& arg.until.is_none_or(|until| row.timestamp < until)
+		};
+		let slice = |from: u64, to: u64, row: Option<&Row>| {
+			let bytes = chunk
+				.bytes
+				.slice((from - start).to_usize().unwrap()..(to - start).to_usize().unwrap());
+			tg::process::log::get::Chunk {
+				bytes,
+				position: from,
+				stream: row.map(|row| row.stream),
+				timestamp: row.map(|row| row.timestamp),
+			}
+		};
+		let mut chunks = Vec::new();
+		let mut position = start;
+		for row in &rows {
+			let from = row.position.max(start);
+			let to = (row.position + row.length).min(end);
+			if from > position && !filter {
+				chunks.push(slice(position, from, None));
+			}
+			if arg.stream.is_none_or(|stream| stream == row.stream) && contains(row) {
+				chunks.push(slice(from, to, Some(row)));
+			}
+			position = to;
+		}
+		if position < end && !filter {
+			chunks.push(slice(position, end, None));
+		}
+
+		Ok(chunks)
+	}
+
+	/// Get the range of positions that contains every entry written at or after the arg's since and before its until. Concurrent writes to stdout and stderr can be timestamped out of order, so entries are selected by timestamp and the range spans their positions. The range may contain entries outside the time range, which are omitted when the chunks are split. The end is `None` if no entry has been written at or after until.
+	async fn get_process_log_range(
+		&self,
+		id: &tg::process::Id,
+		arg: &tg::process::log::get::Arg,
+	) -> tg::Result<(u64, Option<u64>)> {
+		if arg.since.is_none() && arg.until.is_none() {
+			return Ok((0, None));
+		}
+
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+		let p = connection.p();
+
+		// Get the start. If no entry has been written at or after since, then start at the end of the entries.
+		let start = if let Some(since) = arg.since {
+			let statement = formatdoc!(
+				"
+					select coalesce(
+						(
+							select min(position)
+							from process_log_entries
+							where process = {p}1 and timestamp >= {p}2
+						),
+						(
+							select coalesce(max(position + length), 0)
+							from process_log_entries
+							where process = {p}1
+						)
+					);
+				"
+			);
+			let params = db::params![id, since];
+			connection
+				.query_one_value_into::<u64>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to execute the statement"))?
+		} else {
+			0
+		};
+
+		// Get the end.
+		let end = if let Some(until) = arg.until {
+			let statement = formatdoc!(
+				"
+					select case
+						when exists (
+							select 1
+							from process_log_entries
+							where process = {p}1 and timestamp >= {p}2
+						)
+						then (
+							select coalesce(max(position + length), 0)
+							from process_log_entries
+							where process = {p}1 and timestamp < {p}2
+						)
+					end;
+				"
+			);
+			let params = db::params![id, until];
+			connection
+				.query_one_value_into::<Option<u64>>(statement.into(), params)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to execute the statement"))?
+		} else {
+			None
+		};
+
+		// Drop the database connection.
+		drop(connection);
+
+		Ok((start, end.map(|end| end.max(start))))
+	}
+
 	async fn try_get_process_log_remote(
 		&self,
 		id: &tg::process::Id,
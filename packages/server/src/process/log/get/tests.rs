@@ -0,0 +1,126 @@
+use crate::{
+	Server,
+	test::{Context, test},
+};
+use futures::TryStreamExt as _;
+use indoc::formatdoc;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_temp::Temp;
+
+/// Test that a time range selects entries by timestamp when stdout and stderr were timestamped out of order.
+#[tokio::test]
+async fn interleaved() {
+	test(async move |context| {
+		let server = start(context).await;
+		let process = spawn(&server).await;
+		write(
+			&server,
+			&process,
+			&[
+				("out1\n", tg::process::log::Stream::Stdout, 100),
+				("err1\n", tg::process::log::Stream::Stderr, 300),
+				("out2\n", tg::process::log::Stream::Stdout, 200),
+				("out3\n", tg::process::log::Stream::Stdout, 400),
+			],
+		)
+		.await;
+		assert_eq!(
+			log(&server, &process, None, Some(250)).await,
+			"out1\nout2\n"
+		);
+		assert_eq!(log(&server, &process, Some(250), Some(350)).await, "err1\n");
+		assert_eq!(log(&server, &process, Some(150), Some(250)).await, "out2\n");
+	})
+	.await;
+}
+
+async fn start(context: &mut Context) -> Server {
+	// Do not start a runner, so that the process is not run.
+	let temp = Temp::new();
+	let mut config = crate::test::config(temp.path());
+	config.runner = None;
+	context
+		.start_server_with_temp_and_config(temp, config)
+		.await
+}
+
+async fn spawn(server: &Server) -> tg::process::Id {
+	let executable = tg::command::Executable::Artifact(tg::command::ArtifactExecutable {
+		artifact: tg::file!("").into(),
+		path: None,
+	});
+	let command = tg::Command::builder(tg::host().to_owned(), executable).build();
+	command.store(server).await.unwrap();
+	let arg = tg::process::spawn::Arg {
+		cached: Some(false),
+		command: Some(command.id()),
+		..Default::default()
+	};
+	server
+		.try_spawn_process(arg)
+		.await
+		.unwrap()
+		.unwrap()
+		.process
+}
+
+async fn write(
+	server: &Server,
+	process: &tg::process::Id,
+	entries: &[(&str, tg::process::log::Stream, i64)],
+) {
+	let bytes = entries
+		.iter()
+		.map(|(bytes, _, _)| *bytes)
+		.collect::<String>();
+	let path = server.logs_path().join(process.to_string());
+	tokio::fs::write(&path, bytes).await.unwrap();
+	let connection = server.database.write_connection().await.unwrap();
+	let p = connection.p();
+	let statement = formatdoc!(
+		"
+			insert into process_log_entries (process, position, length, stream, timestamp)
+			values ({p}1, {p}2, {p}3, {p}4, {p}5);
+		"
+	);
+	let mut position = 0;
+	for (bytes, stream, timestamp) in entries {
+		let params = db::params![process, position, bytes.len(), stream, timestamp];
+		connection
+			.execute(statement.clone().into(), params)
+			.await
+			.unwrap();
+		position += bytes.len();
+	}
+}
+
+async fn log(
+	server: &Server,
+	process: &tg::process::Id,
+	since: Option<i64>,
+	until: Option<i64>,
+) -> String {
+	let arg = tg::process::log::get::Arg {
+		since,
+		until,
+		..Default::default()
+	};
+	let events = server
+		.try_get_process_log_stream(process, arg)
+		.await
+		.unwrap()
+		.unwrap()
+		.try_collect::<Vec<_>>()
+		.await
+		.unwrap();
+	events
+		.into_iter()
+		.filter_map(|event| match event {
+			tg::process::log::get::Event::Chunk(chunk) => {
+				Some(String::from_utf8(chunk.bytes.to_vec()).unwrap())
+			},
+			tg::process::log::get::Event::End => None,
+		})
+		.collect()
+}
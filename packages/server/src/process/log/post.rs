@@ -1,9 +1,15 @@
 use crate::Server;
 use bytes::Bytes;
+use indoc::formatdoc;
+use num::ToPrimitive as _;
 use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
 use tangram_http::{Body, request::Ext as _, response::builder::Ext as _};
 use tangram_messenger::prelude::*;
-use tokio::io::AsyncWriteExt as _;
+use tokio::io::{AsyncSeekExt as _, AsyncWriteExt as _};
+
+/// Writes to the same stream within this many nanoseconds of an entry's timestamp are coalesced into that entry.
+const COALESCE_WINDOW: i64 = 100_000_000;
 
 impl Server {
 	pub async fn post_process_log(
@@ -40,7 +46,11 @@ impl Server {
 		}
 
 		// Write to the log file.
-		self.post_process_log_to_file(id, arg.bytes.clone()).await?;
+		let position = self.post_process_log_to_file(id, arg.bytes.clone()).await?;
+
+		// Insert the log entry.
+		self.insert_process_log_entry(id, position, arg.bytes.len(), arg.stream)
+			.await?;
 
 		// Publish the message.
 		tokio::spawn({
@@ -59,7 +69,11 @@ impl Server {
 		Ok(())
 	}
 
-	async fn post_process_log_to_file(&self, id: &tg::process::Id, bytes: Bytes) -> tg::Result<()> {
+	async fn post_process_log_to_file(
+		&self,
+		id: &tg::process::Id,
+		bytes: Bytes,
+	) -> tg::Result<u64> {
 		let path = self.logs_path().join(format!("{id}"));
 		let mut file = tokio::fs::File::options()
 			.create(true)
@@ -72,6 +86,71 @@ impl Server {
 		file.write_all(&bytes).await.map_err(
 			|source| tg::error!(!source, %path = path.display(), "failed to write to the log file"),
 		)?;
+		file.flush().await.map_err(
+			|source| tg::error!(!source, %path = path.display(), "failed to flush the log file"),
+		)?;
+
+		// Get the position of the write. The file was opened in append mode, so its offset is the end of this write.
+		let end = file.stream_position().await.map_err(
+			|source| tg::error!(!source, %path = path.display(), "failed to get the log file position"),
+		)?;
+		let position = end - bytes.len().to_u64().unwrap();
+
+		Ok(position)
+	}
+
+	async fn insert_process_log_entry(
+		&self,
+		id: &tg::process::Id,
+		position: u64,
+		length: usize,
+		stream: tg::process::log::Stream,
+	) -> tg::Result<()> {
+		let connection = self
+			.database
+			.write_connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+		let p = connection.p();
+		let timestamp = time::OffsetDateTime::now_utc()
+			.unix_timestamp_nanos()
+			.to_i64()
+			.unwrap();
+
+		// Extend the last entry if it is a recent write to the same stream that ends where this write begins.
+		let statement = formatdoc!(
+			"
+				update process_log_entries
+				set length = length + {p}3
+				where
+					process = {p}1 and
+					position = (select max(position) from process_log_entries where process = {p}1) and
+					position + length = {p}2 and
+					stream = {p}4 and
+					timestamp >= {p}5;
+			"
+		);
+		let params = db::params![id, position, length, stream, timestamp - COALESCE_WINDOW];
+		let n = connection
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+		if n > 0 {
+			return Ok(());
+		}
+
+		// Otherwise, insert a new entry.
+		let statement = formatdoc!(
+			"
+				insert into process_log_entries (process, position, length, stream, timestamp)
+				values ({p}1, {p}2, {p}3, {p}4, {p}5);
+			"
+		);
+		let params = db::params![id, position, length, stream, timestamp];
+		connection
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
 		Ok(())
 	}
 
@@ -17,14 +17,18 @@ impl Server {
 	) -> tg::Result<()> {
 		let now = time::OffsetDateTime::now_utc().unix_timestamp();
 
+		// Get the time that orders the process in the queue.
+		let effective_enqueued_at =
+			self.get_process_effective_enqueued_at(arg.data.enqueued_at, arg.data.priority);
+
 		// Insert the process into the database.
 		match &self.database {
 			Database::Sqlite(database) => {
-				Self::put_process_sqlite(id, &arg, database, now).await?;
+				Self::put_process_sqlite(id, &arg, database, now, effective_enqueued_at).await?;
 			},
 			#[cfg(feature = "postgres")]
 			Database::Postgres(database) => {
-				Self::put_process_postgres(id, &arg, database, now).await?;
+				Self::put_process_postgres(id, &arg, database, now, effective_enqueued_at).await?;
 			},
 		}
 
@@ -64,6 +68,7 @@ impl Server {
 		arg: &tg::process::put::Arg,
 		database: &db::sqlite::Database,
 		touched_at: i64,
+		effective_enqueued_at: Option<i64>,
 	) -> tg::Result<()> {
 		// Get a database connection.
 		let mut connection = database
@@ -88,24 +93,30 @@ impl Server {
 					command,
 					created_at,
 					dequeued_at,
+					effective_enqueued_at,
 					enqueued_at,
 					error,
 					exit,
 					expected_checksum,
 					finished_at,
 					host,
+					labels,
+					limits,
 					log,
 					mounts,
 					network,
 					output,
+					priority,
 					retry,
 					started_at,
 					status,
 					stderr,
 					stdin,
 					stdout,
+					timeout,
 					token_count,
-					touched_at
+					touched_at,
+					usage
 				)
 				values (
 					?1,
@@ -131,7 +142,13 @@ impl Server {
 					?21,
 					?22,
 					?23,
-					?24
+					?24,
+					?25,
+					?26,
+					?27,
+					?28,
+					?29,
+					?30
 				)
 				on conflict (id) do update set
 					actual_checksum = ?2,
@@ -139,24 +156,30 @@ impl Server {
 					command = ?4,
 					created_at = ?5,
 					dequeued_at = ?6,
-					enqueued_at = ?7,
-					error = ?8,
-					exit = ?9,
-					expected_checksum = ?10,
-					finished_at = ?11,
-					host = ?12,
-					log = ?13,
-					mounts = ?14,
-					network = ?15,
-					output = ?16,
-					retry = ?17,
-					started_at = ?18,
-					status = ?19,
-					stderr = ?20,
-					stdin = ?21,
-					stdout = ?22,
-					token_count = ?23,
-					touched_at = ?24
+					effective_enqueued_at = ?7,
+					enqueued_at = ?8,
+					error = ?9,
+					exit = ?10,
+					expected_checksum = ?11,
+					finished_at = ?12,
+					host = ?13,
+					labels = ?14,
+					limits = ?15,
+					log = ?16,
+					mounts = ?17,
+					network = ?18,
+					output = ?19,
+					priority = ?20,
+					retry = ?21,
+					started_at = ?22,
+					status = ?23,
+					stderr = ?24,
+					stdin = ?25,
+					stdout = ?26,
+					timeout = ?27,
+					token_count = ?28,
+					touched_at = ?29,
+					usage = ?30
 			"
 		);
 		let params = db::params![
@@ -166,24 +189,30 @@ impl Server {
 			arg.data.command,
 			arg.data.created_at,
 			arg.data.dequeued_at,
+			effective_enqueued_at,
 			arg.data.enqueued_at,
 			arg.data.error.as_ref().map(db::value::Json),
 			arg.data.exit,
 			arg.data.expected_checksum,
 			arg.data.finished_at,
 			arg.data.host,
+			(!arg.data.labels.is_empty()).then(|| db::value::Json(arg.data.labels.clone())),
+			arg.data.limits.as_ref().map(db::value::Json),
 			arg.data.log,
 			(!arg.data.mounts.is_empty()).then_some(db::value::Json(arg.data.mounts.clone())),
 			arg.data.network,
 			arg.data.output.as_ref().map(db::value::Json),
+			arg.data.priority,
 			arg.data.retry,
 			arg.data.started_at,
 			arg.data.status,
 			arg.data.stderr,
 			arg.data.stdin,
 			arg.data.stdout,
+			arg.data.timeout.map(|timeout| timeout.to_i64().unwrap()),
 			0,
-			touched_at
+			touched_at,
+			arg.data.usage.as_ref().map(db::value::Json),
 		];
 		transaction
 			.execute(statement.into(), params)
@@ -244,6 +273,7 @@ impl Server {
 		arg: &tg::process::put::Arg,
 		database: &db::postgres::Database,
 		touched_at: i64,
+		effective_enqueued_at: Option<i64>,
 	) -> tg::Result<()> {
 		// Get a database connection.
 		let mut connection = database
@@ -268,24 +298,30 @@ impl Server {
 					command,
 					created_at,
 					dequeued_at,
+					effective_enqueued_at,
 					enqueued_at,
 					error,
 					exit,
 					expected_checksum,
 					finished_at,
 					host,
+					labels,
+					limits,
 					log,
 					mounts,
 					network,
 					output,
+					priority,
 					retry,
 					started_at,
 					status,
 					stderr,
 					stdin,
 					stdout,
+					timeout,
 					token_count,
-					touched_at
+					touched_at,
+					usage
 				)
 				values (
 					$1,
@@ -311,7 +347,13 @@ impl Server {
 					$21,
 					$22,
 					$23,
-					$24
+					$24,
+					$25,
+					$26,
+					$27,
+					$28,
+					$29,
+					$30
 				)
 				on conflict (id) do update set
 					actual_checksum = $2,
@@ -319,24 +361,30 @@ impl Server {
 					command = $4,
 					created_at = $5,
 					dequeued_at = $6,
-					enqueued_at = $7,
-					error = $8,
-					exit = $9,
-					expected_checksum = $10,
-					finished_at = $11,
-					host = $12,
-					log = $13,
-					mounts = $14,
-					network = $15,
-					output = $16,
-					retry = $17,
-					started_at = $18,
-					status = $19,
-					stderr = $20,
-					stdin = $21,
-					stdout = $22,
-					token_count = $23,
-					touched_at = $24;
+					effective_enqueued_at = $7,
+					enqueued_at = $8,
+					error = $9,
+					exit = $10,
+					expected_checksum = $11,
+					finished_at = $12,
+					host = $13,
+					labels = $14,
+					limits = $15,
+					log = $16,
+					mounts = $17,
+					network = $18,
+					output = $19,
+					priority = $20,
+					retry = $21,
+					started_at = $22,
+					status = $23,
+					stderr = $24,
+					stdin = $25,
+					stdout = $26,
+					timeout = $27,
+					token_count = $28,
+					touched_at = $29,
+					usage = $30;
 			"
 		);
 		transaction
@@ -349,6 +397,7 @@ impl Server {
 					&arg.data.command.to_string(),
 					&arg.data.created_at,
 					&arg.data.dequeued_at,
+					&effective_enqueued_at,
 					&arg.data.enqueued_at,
 					&arg.data
 						.error
@@ -358,6 +407,12 @@ impl Server {
 					&arg.data.expected_checksum.as_ref().map(ToString::to_string),
 					&arg.data.finished_at,
 					&arg.data.host,
+					&(!arg.data.labels.is_empty())
+						.then(|| serde_json::to_string(&arg.data.labels).unwrap()),
+					&arg.data
+						.limits
+						.as_ref()
+						.map(|limits| serde_json::to_string(limits).unwrap()),
 					&arg.data
 						.log
 						.as_ref()
@@ -369,14 +424,20 @@ impl Server {
 						.output
 						.as_ref()
 						.map(|error| serde_json::to_string(error).unwrap()),
+					&arg.data.priority,
 					&i64::from(arg.data.retry),
 					&arg.data.started_at,
 					&arg.data.status.to_string(),
 					&arg.data.stderr.as_ref().map(ToString::to_string),
 					&arg.data.stdin.as_ref().map(ToString::to_string),
 					&arg.data.stdout.as_ref().map(ToString::to_string),
+					&arg.data.timeout.map(|timeout| timeout.to_i64().unwrap()),
 					&0i64,
 					&touched_at,
+					&arg.data
+						.usage
+						.as_ref()
+						.map(|usage| serde_json::to_string(usage).unwrap()),
 				],
 			)
 			.await
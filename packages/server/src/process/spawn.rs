@@ -3,6 +3,7 @@ use bytes::Bytes;
 use futures::{FutureExt as _, future};
 use indoc::formatdoc;
 use itertools::Itertools as _;
+use num::ToPrimitive as _;
 use std::{path::PathBuf, pin::pin};
 use tangram_client::{self as tg, prelude::*};
 use tangram_database::{self as db, prelude::*};
@@ -64,6 +65,7 @@ impl Server {
 
 		// Return a matching local process if one exists.
 		if let Some((id, token)) = self.try_get_cached_process_local(&arg).await? {
+			self.metrics.process_cache_hits.increment(1);
 			if let Some(parent) = arg.parent.as_ref() {
 				self.try_add_process_child(
 					parent,
@@ -87,6 +89,7 @@ impl Server {
 
 		// Reuse a local process if possible.
 		if let Some(id) = self.try_reuse_process_local(&arg).await? {
+			self.metrics.process_cache_hits.increment(1);
 			if let Some(parent) = arg.parent.as_ref() {
 				self.try_add_process_child(parent, &id, arg.path.as_ref(), arg.tag.as_ref(), None)
 					.await
@@ -104,6 +107,7 @@ impl Server {
 
 		// Attempt to get a remote process.
 		if let Some(output) = self.try_get_cached_process_remote(&arg).await? {
+			self.metrics.process_cache_hits.increment(1);
 			if let Some(parent) = arg.parent.as_ref() {
 				self.try_add_process_child(
 					parent,
@@ -120,6 +124,8 @@ impl Server {
 			return Ok(Some(output));
 		}
 
+		self.metrics.process_cache_misses.increment(1);
+
 		// If cached is true, then return none.
 		if matches!(arg.cached, Some(true)) {
 			return Ok(None);
@@ -425,6 +431,19 @@ impl Server {
 			.await
 			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
 
+		// Copy the log entries.
+		let statement = formatdoc!(
+			"
+				insert into process_log_entries (process, position, length, stream, timestamp)
+				select {p}1, position, length, stream, timestamp from process_log_entries where process = {p}2;
+			"
+		);
+		let params = db::params![id, existing_id];
+		transaction
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
 		// Insert the process.
 		let statement = formatdoc!(
 			"
@@ -575,16 +594,21 @@ impl Server {
 					cacheable,
 					command,
 					created_at,
+					effective_enqueued_at,
 					enqueued_at,
 					expected_checksum,
 					host,
+					labels,
+					limits,
 					mounts,
 					network,
+					priority,
 					retry,
 					status,
 					stderr,
 					stdin,
 					stdout,
+					timeout,
 					token_count,
 					touched_at
 				)
@@ -604,24 +628,34 @@ impl Server {
 					{p}13,
 					{p}14,
 					{p}15,
-					{p}16
+					{p}16,
+					{p}17,
+					{p}18,
+					{p}19,
+					{p}20,
+					{p}21
 				)
 				on conflict (id) do update set
 					cacheable = {p}2,
 					command = {p}3,
 					created_at = {p}4,
-					enqueued_at = {p}5,
-					expected_checksum = {p}6,
-					host = {p}7,
-					mounts = {p}8,
-					network = {p}9,
-					retry = {p}10,
-					status = {p}11,
-					stderr = {p}12,
-					stdin = {p}13,
-					stdout = {p}14,
-					token_count = {p}15,
-					touched_at = {p}16;
+					effective_enqueued_at = {p}5,
+					enqueued_at = {p}6,
+					expected_checksum = {p}7,
+					host = {p}8,
+					labels = {p}9,
+					limits = {p}10,
+					mounts = {p}11,
+					network = {p}12,
+					priority = {p}13,
+					retry = {p}14,
+					status = {p}15,
+					stderr = {p}16,
+					stdin = {p}17,
+					stdout = {p}18,
+					timeout = {p}19,
+					token_count = {p}20,
+					touched_at = {p}21;
 			"
 		);
 		let now = time::OffsetDateTime::now_utc().unix_timestamp();
@@ -630,16 +664,21 @@ impl Server {
 			cacheable,
 			arg.command,
 			now,
+			self.get_process_effective_enqueued_at(Some(now), arg.priority),
 			now,
 			arg.checksum,
 			host,
+			(!arg.labels.is_empty()).then(|| db::value::Json(arg.labels.clone())),
+			arg.limits.as_ref().map(db::value::Json),
 			(!arg.mounts.is_empty()).then(|| db::value::Json(arg.mounts.clone())),
 			arg.network,
+			arg.priority,
 			arg.retry,
 			tg::process::Status::Enqueued,
 			arg.stderr,
 			arg.stdin,
 			arg.stdout,
+			arg.timeout.map(|timeout| timeout.to_i64().unwrap()),
 			0,
 			now,
 		];
@@ -48,6 +48,7 @@ impl Server {
 					lockfile: true,
 					path: path.clone(),
 					updates: Vec::new(),
+					watch: false,
 				};
 				let stream = self.checkin(arg).await?.map_ok(move |event| match event {
 					tg::progress::Event::Log(log) => tg::progress::Event::Log(log),
@@ -1,7 +1,10 @@
 use crate::Server;
 use futures::{TryStreamExt as _, stream::FuturesUnordered};
+use indoc::formatdoc;
 use std::collections::BTreeMap;
 use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use url::Url;
 
 pub mod delete;
 pub mod get;
@@ -34,10 +37,37 @@ impl Server {
 		if let Some(client) = self.remotes.get(&remote) {
 			return Ok(Some(client.clone()));
 		}
-		let Some(output) = self.try_get_remote(&remote).await? else {
+
+		// Get the remote's url and token. The token is only read here, so that it is never returned by the API.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+		#[derive(Debug, serde::Deserialize)]
+		struct Row {
+			token: Option<String>,
+			url: Url,
+		}
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				select token, url
+				from remotes
+				where name = {p}1;
+			",
+		);
+		let params = db::params![&remote];
+		let row = connection
+			.query_optional_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+		drop(connection);
+		let Some(row) = row else {
 			return Ok(None);
 		};
-		let client = tg::Client::new(output.url, Some(self.version.clone()));
+
+		let client = tg::Client::new(row.url, Some(self.version.clone()), row.token);
 		self.remotes.insert(remote, client.clone());
 		Ok(Some(client))
 	}
@@ -14,13 +14,13 @@ impl Server {
 		let p = connection.p();
 		let statement = formatdoc!(
 			"
-				insert into remotes (name, url)
-				values ({p}1, {p}2)
+				insert into remotes (name, token, url)
+				values ({p}1, {p}2, {p}3)
 				on conflict (name)
-				do update set url = {p}2;
+				do update set token = {p}2, url = {p}3;
 			",
 		);
-		let params = db::params![&name, &arg.url];
+		let params = db::params![&name, &arg.token, &arg.url];
 		connection
 			.execute(statement.into(), params)
 			.await
@@ -1,6 +1,10 @@
 use crate::{ProcessPermit, Server, runtime};
 use futures::{FutureExt as _, TryFutureExt as _, future};
-use std::{sync::Arc, time::Duration};
+use num::ToPrimitive as _;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
 use tangram_client::{self as tg, prelude::*};
 use tangram_either::Either;
 use tangram_futures::task::Task;
@@ -18,9 +22,19 @@ impl Server {
 			let permit = ProcessPermit(Either::Left(permit));
 
 			// Try to dequeue a process locally or from one of the remotes.
-			let arg = tg::process::dequeue::Arg::default();
+			let hosts = self.runtimes.read().unwrap().keys().cloned().collect();
+			let labels = self
+				.config
+				.runner
+				.as_ref()
+				.map(|config| config.labels.clone())
+				.unwrap_or_default();
+			let arg = tg::process::dequeue::Arg {
+				hosts: Some(hosts),
+				labels,
+			};
 			let futures = std::iter::once(
-				self.dequeue_process(arg)
+				self.dequeue_process(arg.clone())
 					.map_ok(|output| tg::Process::new(output.process, None, None, None, None))
 					.boxed(),
 			)
@@ -28,9 +42,9 @@ impl Server {
 				config.remotes.iter().map(|name| {
 					let server = self.clone();
 					let remote = name.to_owned();
+					let arg = arg.clone();
 					async move {
 						let client = server.get_remote_client(remote).await?;
-						let arg = tg::process::dequeue::Arg::default();
 						let output = client.dequeue_process(arg).await?;
 						let process =
 							tg::Process::new(output.process, None, Some(name.clone()), None, None);
@@ -99,7 +113,12 @@ impl Server {
 		}
 
 		// Run.
-		let wait = self.process_task_inner(process).await?;
+		let mut wait = self.process_task_inner(process).await?;
+
+		// If the process failed after exceeding its timeout, then set the error.
+		if wait.exit != 0 && wait.error.is_none() {
+			wait.error = self.try_get_process_timeout_error(process).await?;
+		}
 
 		// Store the output.
 		let output = if let Some(output) = &wait.output {
@@ -132,6 +151,7 @@ impl Server {
 			force: false,
 			output,
 			remote: process.remote().cloned(),
+			usage: wait.usage,
 		};
 		self.finish_process(process.id(), arg).await?;
 
@@ -155,11 +175,39 @@ impl Server {
 			.clone();
 
 		// Run the process.
+		let start = Instant::now();
 		let output = runtime.run(process).await;
+		self.metrics
+			.process_runtime_duration
+			.get(&[("host", &*host)])
+			.observe(start.elapsed());
 
 		Ok(output)
 	}
 
+	async fn try_get_process_timeout_error(
+		&self,
+		process: &tg::Process,
+	) -> tg::Result<Option<tg::Error>> {
+		let Some(output) = self.try_get_process_local(process.id()).await? else {
+			return Ok(None);
+		};
+		let (Some(started_at), Some(timeout)) = (output.data.started_at, output.data.timeout)
+		else {
+			return Ok(None);
+		};
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		if now < started_at + timeout.to_i64().unwrap() {
+			return Ok(None);
+		}
+		let error = tg::error!(
+			code = tg::error::Code::Timeout,
+			%timeout,
+			"the process exceeded its timeout"
+		);
+		Ok(Some(error))
+	}
+
 	async fn heartbeat_task(&self, process: &tg::Process) -> tg::Result<()> {
 		let config = self.config.runner.clone().unwrap_or_default();
 		loop {
@@ -30,6 +30,7 @@ pub struct Output {
 	pub exit: u8,
 	#[allow(clippy::struct_field_names)]
 	pub output: Option<tg::Value>,
+	pub usage: Option<tg::process::Usage>,
 }
 
 impl Runtime {
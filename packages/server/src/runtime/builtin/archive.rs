@@ -59,6 +59,7 @@ impl Runtime {
 			error: None,
 			exit: 0,
 			output: Some(output),
+			usage: None,
 		};
 
 		Ok(output)
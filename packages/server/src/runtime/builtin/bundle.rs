@@ -34,6 +34,7 @@ impl Runtime {
 				error: None,
 				exit: 0,
 				output: Some(output),
+				usage: None,
 			};
 			return Ok(output);
 		}
@@ -88,6 +89,7 @@ impl Runtime {
 			error: None,
 			exit: 0,
 			output: Some(output),
+			usage: None,
 		};
 
 		Ok(output)
@@ -44,6 +44,7 @@ impl Runtime {
 			error: None,
 			exit: 0,
 			output: Some(output),
+			usage: None,
 		};
 
 		Ok(output)
@@ -205,6 +205,7 @@ impl Runtime {
 			lockfile: false,
 			path: temp.path().to_owned(),
 			updates: Vec::new(),
+			watch: false,
 		};
 		let artifact = tg::checkin(server, arg)
 			.await
@@ -234,6 +235,7 @@ impl Runtime {
 			error: None,
 			exit: 0,
 			output: Some(output),
+			usage: None,
 		};
 
 		Ok(output)
@@ -105,6 +105,7 @@ impl Runtime {
 				destructive: true,
 				path: temp.path().to_owned(),
 				updates: Vec::new(),
+				watch: false,
 			})
 			.await?;
 		let output = pin!(stream)
@@ -135,6 +136,7 @@ impl Runtime {
 			error: None,
 			exit: 0,
 			output: Some(output),
+			usage: None,
 		};
 
 		Ok(output)
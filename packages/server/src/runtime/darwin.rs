@@ -46,6 +46,11 @@ impl Runtime {
 
 		let state = process.load(&self.server).await?;
 		let command = process.command(&self.server).await?;
+
+		// Limits are enforced with cgroups, which are only available on Linux.
+		if state.limits.is_some() {
+			return Err(tg::error!("limits are not supported on darwin"));
+		}
 		let command = command.data(&self.server).await?;
 		let remote = process.remote();
 
@@ -107,7 +112,7 @@ impl Runtime {
 				let port = listener.port();
 				url = format!("http://localhost:{port}").parse::<Url>().unwrap();
 			}
-			let task = Task::spawn(|stop| Server::serve(proxy, listener, stop));
+			let task = Task::spawn(|stop| Server::serve(proxy, None, listener, stop));
 			Some((task, url))
 		};
 
@@ -298,6 +303,7 @@ impl Runtime {
 				locked: true,
 				lockfile: false,
 				updates: Vec::new(),
+				watch: false,
 			};
 			let artifact = tg::checkin(&self.server, arg)
 				.await
@@ -313,6 +319,7 @@ impl Runtime {
 			error: None,
 			exit,
 			output,
+			usage: None,
 		};
 
 		Ok(output)
@@ -108,6 +108,12 @@ impl Runtime {
 		main_runtime_handle: tokio::runtime::Handle,
 		isolate_handle_sender: tokio::sync::watch::Sender<Option<v8::IsolateHandle>>,
 	) -> tg::Result<super::Output> {
+		// Limits are enforced by the sandbox, which does not run js processes.
+		let state = process.load(&self.server).await?;
+		if state.limits.is_some() {
+			return Err(tg::error!("limits are not supported for js processes"));
+		}
+
 		// Get the root module.
 		let command = process.command(&self.server).await?;
 		let executable = command
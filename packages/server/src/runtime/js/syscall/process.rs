@@ -73,10 +73,31 @@ pub async fn spawn(
 
 			// Spawn.
 			let retry = *parent.retry(&server).await?;
+			let labels = if arg.labels.is_empty() {
+				parent.labels(&server).await?.clone()
+			} else {
+				arg.labels
+			};
+			let limits = match arg.limits {
+				Some(limits) => Some(limits),
+				None => parent.limits(&server).await?.clone(),
+			};
+			let priority = match arg.priority {
+				Some(priority) => Some(priority),
+				None => *parent.priority(&server).await?,
+			};
+			let timeout = match arg.timeout {
+				Some(timeout) => Some(timeout),
+				None => *parent.timeout(&server).await?,
+			};
 			let arg = tg::process::spawn::Arg {
+				labels,
+				limits,
 				parent: Some(parent.id().clone()),
+				priority,
 				remote: parent.remote().cloned(),
 				retry,
+				timeout,
 				..arg
 			};
 			let output = server.spawn_process(arg).await?;
@@ -15,6 +15,9 @@ use tangram_either::Either;
 use tangram_futures::task::Task;
 use url::Url;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Clone)]
 pub struct Runtime {
 	pub(super) server: Server,
@@ -246,7 +249,7 @@ impl Runtime {
 				Some(path_map),
 			);
 			let listener = Server::listen(&host_url).await?;
-			let task = Task::spawn(|stop| Server::serve(proxy, listener, stop));
+			let task = Task::spawn(|stop| Server::serve(proxy, None, listener, stop));
 			Some((task, guest_url))
 		};
 
@@ -366,7 +369,43 @@ impl Runtime {
 		// Set the chroot.
 		cmd.arg("--chroot").arg(root_path);
 
+		// Set the report path.
+		let report_path = temp.path().join("report");
+		cmd.arg("--report").arg(&report_path);
+
+		// Set the limits.
+		if let Some(limits) = &state.limits {
+			// The cpu, memory, and pids limits are enforced with a cgroup, which must be created in a delegated cgroup.
+			if limits.cpu.is_some() || limits.memory.is_some() || limits.pids.is_some() {
+				let cgroup = self
+					.server
+					.config()
+					.runner
+					.as_ref()
+					.and_then(|runner| runner.cgroup.as_ref())
+					.ok_or_else(|| {
+						tg::error!(
+							"the runner must be configured with a delegated cgroup to enforce cpu, memory, and pids limits"
+						)
+					})?;
+				cmd.arg("--cgroup").arg(cgroup);
+			}
+			if let Some(cpu) = limits.cpu {
+				cmd.arg("--cpu").arg(cpu.to_string());
+			}
+			if let Some(memory) = limits.memory {
+				cmd.arg("--memory").arg(memory.to_string());
+			}
+			if let Some(open_files) = limits.open_files {
+				cmd.arg("--open-files").arg(open_files.to_string());
+			}
+			if let Some(pids) = limits.pids {
+				cmd.arg("--pids").arg(pids.to_string());
+			}
+		}
+
 		// Spawn the process.
+		let start = std::time::Instant::now();
 		let mut child = cmd
 			.arg(executable)
 			.arg("--")
@@ -406,6 +445,7 @@ impl Runtime {
 			|source| tg::error!(!source, %process = process.id(), "failed to wait for the child process"),
 		)?;
 		let exit = exit.code().or(exit.signal()).unwrap().to_u8().unwrap();
+		let wall_time = start.elapsed().as_millis().to_u64().unwrap();
 
 		// Stop and await the proxy task.
 		if let Some(task) = proxy.map(|(proxy, _)| proxy) {
@@ -419,6 +459,34 @@ impl Runtime {
 		// Stop and await the stdio task.
 		stdio_task.await.unwrap()?;
 
+		// Read the report.
+		let report = match tokio::fs::read_to_string(&report_path).await {
+			Ok(report) => report,
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+			Err(source) => {
+				return Err(tg::error!(!source, "failed to read the sandbox report"));
+			},
+		};
+		let report = report
+			.lines()
+			.filter_map(|line| line.split_once(' '))
+			.collect::<HashMap<_, _>>();
+
+		// Get the usage.
+		let value = |name: &str| report.get(name).and_then(|value| value.parse().ok());
+		let usage = tg::process::Usage {
+			cpu_system: value("cpu_system"),
+			cpu_user: value("cpu_user"),
+			max_rss: value("max_rss"),
+			read_bytes: value("read_bytes"),
+			wall_time: Some(wall_time),
+			written_bytes: value("written_bytes"),
+		};
+
+		// Determine if a limit was exceeded.
+		let exceeded = report.get("exceeded").copied();
+		let error = limit_error(exceeded, exit, state.limits.as_ref());
+
 		// Create the output.
 		let output = output_path.join("output");
 		let exists = tokio::fs::try_exists(&output).await.map_err(|source| {
@@ -433,6 +501,7 @@ impl Runtime {
 				locked: true,
 				lockfile: false,
 				updates: Vec::new(),
+				watch: false,
 			};
 			let artifact = tg::checkin(&self.server, arg)
 				.await
@@ -445,15 +514,36 @@ impl Runtime {
 		// Create the output.
 		let output = super::Output {
 			checksum: None,
-			error: None,
+			error,
 			exit,
 			output,
+			usage: Some(usage),
 		};
 
 		Ok(output)
 	}
 }
 
+/// Get the error for a limit that the process exceeded. The memory limit kills the process. The pids limit only causes a fork to fail, which the process may handle, so it is only attributed if the process failed.
+fn limit_error(
+	exceeded: Option<&str>,
+	exit: u8,
+	limits: Option<&tg::process::Limits>,
+) -> Option<tg::Error> {
+	match exceeded? {
+		"memory" => {
+			let memory = limits.and_then(|limits| limits.memory);
+			Some(tg::error!(?memory, "the process exceeded its memory limit"))
+		},
+		_ if exit == 0 => None,
+		"pids" => {
+			let pids = limits.and_then(|limits| limits.pids);
+			Some(tg::error!(?pids, "the process exceeded its pids limit"))
+		},
+		exceeded => Some(tg::error!(%exceeded, "the process exceeded a limit")),
+	}
+}
+
 fn bind(source: impl AsRef<Path>, target: impl AsRef<Path>, readonly: bool) -> String {
 	let mut string = format!(
 		"type=bind,source={},target={}",
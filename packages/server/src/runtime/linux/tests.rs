@@ -0,0 +1,28 @@
+use super::limit_error;
+use tangram_client as tg;
+
+/// Test that the memory limit is always attributed, but the pids limit is only attributed if the process failed.
+#[test]
+fn limits() {
+	let limits = tg::process::Limits {
+		memory: Some(1 << 20),
+		pids: Some(8),
+		..Default::default()
+	};
+	let message =
+		|exceeded, exit| limit_error(exceeded, exit, Some(&limits)).and_then(|error| error.message);
+	assert_eq!(message(None, 1), None);
+	assert_eq!(
+		message(Some("memory"), 0).as_deref(),
+		Some("the process exceeded its memory limit")
+	);
+	assert_eq!(
+		message(Some("memory"), 9).as_deref(),
+		Some("the process exceeded its memory limit")
+	);
+	assert_eq!(message(Some("pids"), 0), None);
+	assert_eq!(
+		message(Some("pids"), 1).as_deref(),
+		Some("the process exceeded its pids limit")
+	);
+}
@@ -100,9 +100,30 @@ impl tg::Handle for Proxy {
 		Err::<stream::Empty<_>, _>(tg::error!("forbidden"))
 	}
 
+	async fn rebuild_index(
+		&self,
+		_arg: tg::index::rebuild::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::index::rebuild::Output>>> + Send + 'static,
+	> {
+		Err::<stream::Empty<_>, _>(tg::error!("forbidden"))
+	}
+
 	async fn clean(
 		&self,
-	) -> tg::Result<impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static> {
+		_arg: tg::clean::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::clean::Output>>> + Send + 'static,
+	> {
+		Err::<stream::Empty<_>, _>(tg::error!("forbidden"))
+	}
+
+	async fn fsck(
+		&self,
+		_arg: tg::fsck::Arg,
+	) -> tg::Result<
+		impl Stream<Item = tg::Result<tg::progress::Event<tg::fsck::Output>>> + Send + 'static,
+	> {
 		Err::<stream::Empty<_>, _>(tg::error!("forbidden"))
 	}
 
@@ -201,6 +222,10 @@ impl tg::Handle for Proxy {
 		Err(tg::error!("forbidden"))
 	}
 
+	async fn metrics(&self) -> tg::Result<String> {
+		Err(tg::error!("forbidden"))
+	}
+
 	fn create_blob(
 		&self,
 		reader: impl AsyncRead + Send + 'static,
@@ -534,6 +559,23 @@ impl tg::handle::Tag for Proxy {
 	}
 }
 
+impl tg::handle::Token for Proxy {
+	async fn create_token(
+		&self,
+		_arg: tg::token::create::Arg,
+	) -> tg::Result<tg::token::create::Output> {
+		Err(tg::error!("forbidden"))
+	}
+
+	async fn list_tokens(&self, _arg: tg::token::list::Arg) -> tg::Result<tg::token::list::Output> {
+		Err(tg::error!("forbidden"))
+	}
+
+	async fn delete_token(&self, _id: &tg::token::Id) -> tg::Result<()> {
+		Err(tg::error!("forbidden"))
+	}
+}
+
 impl tg::handle::User for Proxy {
 	async fn get_user(&self, _token: &str) -> tg::Result<Option<tg::User>> {
 		Err(tg::error!("forbidden"))
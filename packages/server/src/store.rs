@@ -1,26 +1,50 @@
 use bytes::Bytes;
-use std::path::PathBuf;
+use num::ToPrimitive as _;
+use std::{path::PathBuf, sync::Arc, time::Instant};
 use tangram_client as tg;
 
 #[cfg(feature = "foundationdb")]
 mod fdb;
+mod fs;
 mod lmdb;
 mod memory;
+mod migrate;
 mod s3;
+#[cfg(test)]
+mod tests;
+mod tiered;
 
 #[cfg(feature = "foundationdb")]
 pub use self::fdb::Fdb;
-pub use self::{lmdb::Lmdb, memory::Memory, s3::S3};
+pub use self::{
+	fs::Fs,
+	lmdb::Lmdb,
+	memory::Memory,
+	migrate::{MigrateStoreArg, migrate_store},
+	s3::S3,
+	tiered::Tiered,
+};
+
+/// The flag byte that prefixes compressed objects. Uncompressed objects never begin with this byte, because blobs begin with `0` or `1` and all other objects are serialized as JSON.
+const COMPRESSED: u8 = 0xff;
+
+pub struct Store {
+	pub backend: Backend,
+	compression: Option<crate::config::StoreCompression>,
+	metrics: Arc<crate::metrics::Metrics>,
+}
 
 #[derive(derive_more::IsVariant, derive_more::TryUnwrap, derive_more::Unwrap)]
 #[try_unwrap(ref)]
 #[unwrap(ref)]
-pub enum Store {
+pub enum Backend {
 	#[cfg(feature = "foundationdb")]
 	Fdb(Fdb),
+	Fs(Fs),
 	Lmdb(Lmdb),
 	Memory(Memory),
 	S3(S3),
+	Tiered(Tiered),
 }
 
 pub struct PutArg {
@@ -57,12 +81,155 @@ pub struct CacheReference {
 }
 
 impl Store {
+	pub fn new(
+		backend: Backend,
+		compression: Option<crate::config::StoreCompression>,
+		metrics: Arc<crate::metrics::Metrics>,
+	) -> Self {
+		Self {
+			backend,
+			compression,
+			metrics,
+		}
+	}
+
+	pub async fn try_get(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
+		let start = Instant::now();
+		let bytes = self.backend.try_get(id).await?;
+		let length = bytes.as_ref().map_or(0, Bytes::len);
+		self.record_get(start, length);
+		bytes
+			.map(|bytes| Ok(decompress(bytes)?.into_owned()))
+			.transpose()
+	}
+
+	pub async fn try_get_batch(&self, ids: &[tg::object::Id]) -> tg::Result<Vec<Option<Bytes>>> {
+		let start = Instant::now();
+		let bytes = self.backend.try_get_batch(ids).await?;
+		let length = bytes.iter().flatten().map(Bytes::len).sum();
+		self.record_get(start, length);
+		bytes
+			.into_iter()
+			.map(|bytes| {
+				bytes
+					.map(|bytes| Ok(decompress(bytes)?.into_owned()))
+					.transpose()
+			})
+			.collect()
+	}
+
+	pub async fn try_get_cache_reference(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<CacheReference>> {
+		self.backend.try_get_cache_reference(id).await
+	}
+
+	pub async fn put(&self, mut arg: PutArg) -> tg::Result<()> {
+		if let Some(compression) = &self.compression {
+			arg.bytes = arg
+				.bytes
+				.map(|bytes| compress(bytes, compression))
+				.transpose()?;
+		}
+		let start = Instant::now();
+		let length = arg.bytes.as_ref().map_or(0, Bytes::len);
+		self.backend.put(arg).await?;
+		self.record_put(start, length);
+		Ok(())
+	}
+
+	pub async fn put_batch(&self, mut arg: PutBatchArg) -> tg::Result<()> {
+		if let Some(compression) = &self.compression {
+			for (_, bytes, _) in &mut arg.objects {
+				*bytes = bytes
+					.take()
+					.map(|bytes| compress(bytes, compression))
+					.transpose()?;
+			}
+		}
+		let start = Instant::now();
+		let length = arg
+			.objects
+			.iter()
+			.filter_map(|(_, bytes, _)| bytes.as_ref())
+			.map(Bytes::len)
+			.sum();
+		self.backend.put_batch(arg).await?;
+		self.record_put(start, length);
+		Ok(())
+	}
+
+	pub async fn delete_batch(&self, arg: DeleteBatchArg) -> tg::Result<()> {
+		self.backend.delete_batch(arg).await
+	}
+
+	pub async fn touch(&self, id: &tg::object::Id, touched_at: i64) -> tg::Result<()> {
+		self.backend.touch(id, touched_at).await
+	}
+
+	fn record_get(&self, start: Instant, length: usize) {
+		let labels = [("backend", self.backend.name())];
+		self.metrics
+			.store_get_duration
+			.get(&labels)
+			.observe(start.elapsed());
+		self.metrics
+			.store_get_bytes
+			.get(&labels)
+			.increment(length.to_u64().unwrap());
+	}
+
+	fn record_put(&self, start: Instant, length: usize) {
+		let labels = [("backend", self.backend.name())];
+		self.metrics
+			.store_put_duration
+			.get(&labels)
+			.observe(start.elapsed());
+		self.metrics
+			.store_put_bytes
+			.get(&labels)
+			.increment(length.to_u64().unwrap());
+	}
+}
+
+impl Backend {
+	pub fn new(config: &crate::config::Store) -> tg::Result<Self> {
+		let backend = match config {
+			crate::config::Store::Memory => Self::new_memory(),
+			#[cfg(feature = "foundationdb")]
+			crate::config::Store::Fdb(fdb) => Self::new_fdb(fdb)?,
+			crate::config::Store::Fs(fs) => Self::new_fs(fs)?,
+			crate::config::Store::Lmdb(lmdb) => Self::new_lmdb(lmdb)?,
+			crate::config::Store::S3(s3) => Self::new_s3(s3),
+			crate::config::Store::Tiered(tiered) => Self::new_tiered(tiered)?,
+		};
+		Ok(backend)
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			#[cfg(feature = "foundationdb")]
+			Self::Fdb(_) => "fdb",
+			Self::Fs(_) => "fs",
+			Self::Lmdb(_) => "lmdb",
+			Self::Memory(_) => "memory",
+			Self::S3(_) => "s3",
+			Self::Tiered(_) => "tiered",
+		}
+	}
+
 	#[cfg(feature = "foundationdb")]
 	pub fn new_fdb(config: &crate::config::FdbStore) -> tg::Result<Self> {
 		let fdb = Fdb::new(config)?;
 		Ok(Self::Fdb(fdb))
 	}
 
+	pub fn new_fs(config: &crate::config::FsStore) -> tg::Result<Self> {
+		let fs = Fs::new(config)?;
+		Ok(Self::Fs(fs))
+	}
+
 	pub fn new_lmdb(config: &crate::config::LmdbStore) -> tg::Result<Self> {
 		let lmdb = Lmdb::new(config)?;
 		Ok(Self::Lmdb(lmdb))
@@ -76,13 +243,20 @@ impl Store {
 		Self::S3(S3::new(config))
 	}
 
+	pub fn new_tiered(config: &crate::config::TieredStore) -> tg::Result<Self> {
+		let tiered = Tiered::new(config)?;
+		Ok(Self::Tiered(tiered))
+	}
+
 	pub async fn try_get(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
 		match self {
 			#[cfg(feature = "foundationdb")]
 			Self::Fdb(fdb) => fdb.try_get(id).await,
+			Self::Fs(fs) => fs.try_get(id).await,
 			Self::Lmdb(lmdb) => lmdb.try_get(id).await,
 			Self::Memory(memory) => Ok(memory.try_get(id)),
 			Self::S3(s3) => s3.try_get(id).await,
+			Self::Tiered(tiered) => tiered.try_get(id).await,
 		}
 	}
 
@@ -90,9 +264,11 @@ impl Store {
 		match self {
 			#[cfg(feature = "foundationdb")]
 			Self::Fdb(fdb) => fdb.try_get_batch(ids).await,
+			Self::Fs(fs) => fs.try_get_batch(ids).await,
 			Self::Lmdb(lmdb) => lmdb.try_get_batch(ids).await,
 			Self::Memory(memory) => Ok(memory.try_get_batch(ids)),
 			Self::S3(s3) => s3.try_get_batch(ids).await,
+			Self::Tiered(tiered) => tiered.try_get_batch(ids).await,
 		}
 	}
 
@@ -103,9 +279,44 @@ impl Store {
 		match self {
 			#[cfg(feature = "foundationdb")]
 			Self::Fdb(fdb) => fdb.try_get_cache_reference(id).await,
+			Self::Fs(fs) => fs.try_get_cache_reference(id).await,
 			Self::Lmdb(lmdb) => lmdb.try_get_cache_reference(id).await,
 			Self::Memory(memory) => Ok(memory.try_get_cache_reference(id)),
 			Self::S3(s3) => s3.try_get_cache_reference(id).await,
+			Self::Tiered(tiered) => tiered.try_get_cache_reference(id).await,
+		}
+	}
+
+	/// Get the times the objects were last touched, or `None` for objects that are missing or whose backend does not record touch times.
+	pub async fn try_get_touched_at_batch(
+		&self,
+		ids: &[tg::object::Id],
+	) -> tg::Result<Vec<Option<i64>>> {
+		match self {
+			#[cfg(feature = "foundationdb")]
+			Self::Fdb(fdb) => fdb.try_get_touched_at_batch(ids).await,
+			Self::Fs(fs) => fs.try_get_touched_at_batch(ids).await,
+			Self::Lmdb(lmdb) => lmdb.try_get_touched_at_batch(ids).await,
+			Self::Memory(memory) => Ok(memory.try_get_touched_at_batch(ids)),
+			Self::S3(s3) => s3.try_get_touched_at_batch(ids).await,
+			Self::Tiered(tiered) => tiered.try_get_touched_at_batch(ids).await,
+		}
+	}
+
+	/// List up to `limit` objects after `after`, in an order determined by the backend.
+	pub async fn list(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		match self {
+			#[cfg(feature = "foundationdb")]
+			Self::Fdb(fdb) => fdb.list(after, limit).await,
+			Self::Fs(fs) => fs.list(after, limit).await,
+			Self::Lmdb(lmdb) => lmdb.list(after, limit).await,
+			Self::Memory(memory) => Ok(memory.list(after, limit)),
+			Self::S3(s3) => s3.list(after, limit).await,
+			Self::Tiered(tiered) => tiered.list(after, limit).await,
 		}
 	}
 
@@ -115,6 +326,9 @@ impl Store {
 			Self::Fdb(fdb) => {
 				fdb.put(arg).await?;
 			},
+			Self::Fs(fs) => {
+				fs.put(arg).await?;
+			},
 			Self::Lmdb(lmdb) => {
 				lmdb.put(arg).await?;
 			},
@@ -124,6 +338,9 @@ impl Store {
 			Self::S3(s3) => {
 				s3.put(arg).await?;
 			},
+			Self::Tiered(tiered) => {
+				tiered.put(arg).await?;
+			},
 		}
 		Ok(())
 	}
@@ -134,6 +351,9 @@ impl Store {
 			Self::Fdb(fdb) => {
 				fdb.put_batch(arg).await?;
 			},
+			Self::Fs(fs) => {
+				fs.put_batch(arg).await?;
+			},
 			Self::Lmdb(lmdb) => {
 				lmdb.put_batch(arg).await?;
 			},
@@ -143,6 +363,9 @@ impl Store {
 			Self::S3(s3) => {
 				s3.put_batch(arg).await?;
 			},
+			Self::Tiered(tiered) => {
+				tiered.put_batch(arg).await?;
+			},
 		}
 		Ok(())
 	}
@@ -153,6 +376,9 @@ impl Store {
 			Self::Fdb(fdb) => {
 				fdb.delete_batch(arg).await?;
 			},
+			Self::Fs(fs) => {
+				fs.delete_batch(arg).await?;
+			},
 			Self::Lmdb(lmdb) => {
 				lmdb.delete_batch(arg).await?;
 			},
@@ -162,6 +388,9 @@ impl Store {
 			Self::S3(s3) => {
 				s3.delete_batch(arg).await?;
 			},
+			Self::Tiered(tiered) => {
+				tiered.delete_batch(arg).await?;
+			},
 		}
 		Ok(())
 	}
@@ -172,6 +401,9 @@ impl Store {
 			Self::Fdb(fdb) => {
 				fdb.touch(id, touched_at).await?;
 			},
+			Self::Fs(fs) => {
+				fs.touch(id, touched_at).await?;
+			},
 			Self::Lmdb(lmdb) => {
 				lmdb.touch(id, touched_at).await?;
 			},
@@ -181,7 +413,36 @@ impl Store {
 			Self::S3(s3) => {
 				s3.touch(id, touched_at).await?;
 			},
+			Self::Tiered(tiered) => {
+				tiered.touch(id, touched_at).await?;
+			},
 		}
 		Ok(())
 	}
 }
+
+fn compress(bytes: Bytes, config: &crate::config::StoreCompression) -> tg::Result<Bytes> {
+	if bytes.len() < config.threshold {
+		return Ok(bytes);
+	}
+	let mut output = vec![COMPRESSED];
+	zstd::stream::copy_encode(bytes.as_ref(), &mut output, config.level)
+		.map_err(|source| tg::error!(!source, "failed to compress the object"))?;
+
+	// Store the object uncompressed if compression does not reduce its size.
+	if output.len() >= bytes.len() {
+		return Ok(bytes);
+	}
+
+	Ok(output.into())
+}
+
+pub fn decompress<'a>(bytes: impl Into<tg::bytes::Cow<'a>>) -> tg::Result<tg::bytes::Cow<'a>> {
+	let bytes = bytes.into();
+	if bytes.first() != Some(&COMPRESSED) {
+		return Ok(bytes);
+	}
+	let output = zstd::stream::decode_all(&bytes[1..])
+		.map_err(|source| tg::error!(!source, "failed to decompress the object"))?;
+	Ok(Bytes::from(output).into())
+}
@@ -115,6 +115,83 @@ impl Fdb {
 		Ok(reference)
 	}
 
+	pub async fn try_get_touched_at_batch(
+		&self,
+		ids: &[tg::object::Id],
+	) -> tg::Result<Vec<Option<i64>>> {
+		if ids.is_empty() {
+			return Ok(vec![]);
+		}
+		let touched_ats = self
+			.database
+			.run(|transaction, _| async move {
+				future::try_join_all(ids.iter().map(|id| async {
+					let Some(touched_at) = transaction
+						.get(&(0, id.to_bytes(), 1).pack_to_vec(), false)
+						.await?
+					else {
+						return Ok::<_, fdb::FdbBindingError>(None);
+					};
+					let touched_at = touched_at.as_ref().try_into().map_err(|_| {
+						fdb::FdbBindingError::new_custom_error("invalid touch time".into())
+					})?;
+					Ok(Some(i64::from_le_bytes(touched_at)))
+				}))
+				.await
+			})
+			.await
+			.map_err(|source| tg::error!(!source, "the transaction failed"))?;
+		Ok(touched_ats)
+	}
+
+	pub async fn list(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		if limit == 0 {
+			return Ok(Vec::new());
+		}
+		let ids = self
+			.database
+			.run(|transaction, _| async move {
+				// Every object has a touched at key, so list the objects by their touched at keys.
+				let start = match after {
+					Some(after) => (0, after.to_bytes(), 3).pack_to_vec(),
+					None => (0,).pack_to_vec(),
+				};
+				let end = (1,).pack_to_vec();
+				let mut range = fdb::RangeOption::from((start, end));
+				range.mode = fdb::options::StreamingMode::Iterator;
+				let stream = transaction.get_ranges(range, false);
+				let mut stream = pin!(stream);
+				let mut output = Vec::new();
+				'outer: while let Some(entries) = stream.try_next().await? {
+					for entry in entries {
+						let Ok((_, bytes, kind)) =
+							foundationdb_tuple::unpack::<(i64, Vec<u8>, i64)>(entry.key())
+						else {
+							continue;
+						};
+						if kind != 1 {
+							continue;
+						}
+						let id = tg::object::Id::from_slice(&bytes).map_err(|_| {
+							FdbBindingError::new_custom_error("invalid object id".into())
+						})?;
+						output.push(id);
+						if output.len() == limit {
+							break 'outer;
+						}
+					}
+				}
+				Ok(output)
+			})
+			.await
+			.map_err(|source| tg::error!(!source, "the transaction failed"))?;
+		Ok(ids)
+	}
+
 	pub async fn put(&self, arg: super::PutArg) -> tg::Result<()> {
 		let arg = &arg;
 		self.database
@@ -0,0 +1,414 @@
+use super::CacheReference;
+use bytes::Bytes;
+use num::ToPrimitive as _;
+use std::{
+	io::Write as _,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tangram_client as tg;
+
+pub struct Fs {
+	path: PathBuf,
+}
+
+impl Fs {
+	pub fn new(config: &crate::config::FsStore) -> tg::Result<Self> {
+		let path = config.path.clone();
+		std::fs::create_dir_all(path.join("tmp"))
+			.map_err(|source| tg::error!(!source, "failed to create the store directory"))?;
+
+		// Remove the temp files of writes that were interrupted.
+		for temp_path in read_dir_sorted(&path.join("tmp"))? {
+			remove(&temp_path)?;
+		}
+
+		Ok(Self { path })
+	}
+
+	pub async fn try_get(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
+		let path = self.object_path(id);
+		let bytes = tokio::task::spawn_blocking(move || read(&path))
+			.await
+			.map_err(|source| tg::error!(!source, "the task panicked"))?
+			.map_err(|source| tg::error!(!source, %id, "failed to get the object"))?
+			.map(Bytes::from);
+		Ok(bytes)
+	}
+
+	pub async fn try_get_batch(&self, ids: &[tg::object::Id]) -> tg::Result<Vec<Option<Bytes>>> {
+		if ids.is_empty() {
+			return Ok(vec![]);
+		}
+		let paths = ids
+			.iter()
+			.map(|id| self.object_path(id))
+			.collect::<Vec<_>>();
+		let bytes = tokio::task::spawn_blocking(move || {
+			paths
+				.iter()
+				.map(|path| Ok(read(path)?.map(Bytes::from)))
+				.collect::<tg::Result<Vec<_>>>()
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to get the objects"))?;
+		Ok(bytes)
+	}
+
+	pub fn try_get_sync(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
+		let bytes = read(&self.object_path(id))
+			.map_err(|source| tg::error!(!source, %id, "failed to get the object"))?
+			.map(Bytes::from);
+		Ok(bytes)
+	}
+
+	pub fn try_get_object_data_sync(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<tg::object::Data>> {
+		let Some(bytes) = self.try_get_sync(id)? else {
+			return Ok(None);
+		};
+		let data = tg::object::Data::deserialize(id.kind(), super::decompress(bytes)?)?;
+		Ok(Some(data))
+	}
+
+	pub async fn try_get_cache_reference(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<CacheReference>> {
+		let path = self.cache_reference_path(id);
+		let reference = tokio::task::spawn_blocking(move || read_cache_reference(&path))
+			.await
+			.map_err(|source| tg::error!(!source, "the task panicked"))?
+			.map_err(|source| tg::error!(!source, %id, "failed to get the cache reference"))?;
+		Ok(reference)
+	}
+
+	pub async fn try_get_touched_at_batch(
+		&self,
+		ids: &[tg::object::Id],
+	) -> tg::Result<Vec<Option<i64>>> {
+		// An object's touch time is the latest modified time of its files.
+		let items = ids
+			.iter()
+			.map(|id| (self.object_path(id), self.cache_reference_path(id)))
+			.collect::<Vec<_>>();
+		let touched_ats = tokio::task::spawn_blocking(move || {
+			items
+				.iter()
+				.map(|(object_path, cache_reference_path)| {
+					let touched_at = [object_path, cache_reference_path]
+						.into_iter()
+						.map(|path| modified(path))
+						.collect::<tg::Result<Vec<_>>>()?
+						.into_iter()
+						.flatten()
+						.max();
+					Ok(touched_at.map(timestamp))
+				})
+				.collect::<tg::Result<Vec<_>>>()
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to get the touch times"))?;
+		Ok(touched_ats)
+	}
+
+	pub fn try_get_cache_reference_sync(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<CacheReference>> {
+		read_cache_reference(&self.cache_reference_path(id))
+			.map_err(|source| tg::error!(!source, %id, "failed to get the cache reference"))
+	}
+
+	pub async fn list(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		// Objects are listed in order of their shard and then their id.
+		let path = self.path.clone();
+		let after = after.map(|id| (self.shard_path(id), id.clone()));
+		tokio::task::spawn_blocking(move || {
+			let mut output = Vec::new();
+			for first in read_dir_sorted(&path)? {
+				if first.file_name().is_some_and(|name| name == "tmp") {
+					continue;
+				}
+				for shard in read_dir_sorted(&first)? {
+					if let Some((after_shard, _)) = &after {
+						if &shard < after_shard {
+							continue;
+						}
+					}
+					let mut ids = read_dir_sorted(&shard)?
+						.into_iter()
+						.filter_map(|path| {
+							let name = path.file_name()?.to_str()?;
+							let name = name.strip_suffix(".cache_reference").unwrap_or(name);
+							name.parse::<tg::object::Id>().ok()
+						})
+						.filter(|id| {
+							after.as_ref().is_none_or(|(after_shard, after)| {
+								&shard != after_shard || id > after
+							})
+						})
+						.collect::<Vec<_>>();
+					ids.sort_unstable();
+					ids.dedup();
+					for id in ids {
+						if output.len() == limit {
+							return Ok(output);
+						}
+						output.push(id);
+					}
+				}
+			}
+			Ok::<_, tg::Error>(output)
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to list the objects"))
+	}
+
+	pub async fn put(&self, arg: super::PutArg) -> tg::Result<()> {
+		let arg = super::PutBatchArg {
+			objects: vec![(arg.id, arg.bytes, arg.cache_reference)],
+			touched_at: arg.touched_at,
+		};
+		self.put_batch(arg).await
+	}
+
+	pub async fn put_batch(&self, arg: super::PutBatchArg) -> tg::Result<()> {
+		if arg.objects.is_empty() {
+			return Ok(());
+		}
+		let items = arg
+			.objects
+			.into_iter()
+			.map(|(id, bytes, reference)| {
+				let object_path = self.object_path(&id);
+				let cache_reference_path = self.cache_reference_path(&id);
+				(object_path, bytes, cache_reference_path, reference)
+			})
+			.collect::<Vec<_>>();
+		let temp_path = self.path.join("tmp");
+		let touched_at = system_time(arg.touched_at);
+		tokio::task::spawn_blocking(move || {
+			for (object_path, bytes, cache_reference_path, reference) in items {
+				if let Some(bytes) = bytes {
+					write(&temp_path, &object_path, &bytes, touched_at)?;
+				}
+				if let Some(reference) = reference {
+					let bytes = serde_json::to_vec(&reference).map_err(|source| {
+						tg::error!(!source, "failed to serialize the cache reference")
+					})?;
+					write(&temp_path, &cache_reference_path, &bytes, touched_at)?;
+				}
+			}
+			Ok::<_, tg::Error>(())
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to put the objects"))?;
+		Ok(())
+	}
+
+	pub async fn delete_batch(&self, arg: super::DeleteBatchArg) -> tg::Result<()> {
+		if arg.ids.is_empty() {
+			return Ok(());
+		}
+		let items = arg
+			.ids
+			.iter()
+			.map(|id| (self.object_path(id), self.cache_reference_path(id)))
+			.collect::<Vec<_>>();
+		let max_touched_at = system_time(arg.now - arg.ttl.to_i64().unwrap());
+		tokio::task::spawn_blocking(move || {
+			for (object_path, cache_reference_path) in items {
+				// Only delete the object if it has not been touched within the ttl.
+				let touched_at = [&object_path, &cache_reference_path]
+					.into_iter()
+					.filter_map(|path| modified(path).transpose())
+					.collect::<tg::Result<Vec<_>>>()?
+					.into_iter()
+					.max();
+				let Some(touched_at) = touched_at else {
+					continue;
+				};
+				if touched_at > max_touched_at {
+					continue;
+				}
+				remove(&object_path)?;
+				remove(&cache_reference_path)?;
+			}
+			Ok::<_, tg::Error>(())
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to delete the objects"))?;
+		Ok(())
+	}
+
+	pub async fn touch(&self, id: &tg::object::Id, touched_at: i64) -> tg::Result<()> {
+		let object_path = self.object_path(id);
+		let cache_reference_path = self.cache_reference_path(id);
+		let touched_at = system_time(touched_at);
+		tokio::task::spawn_blocking(move || {
+			for path in [object_path, cache_reference_path] {
+				let file = match std::fs::File::options().write(true).open(&path) {
+					Ok(file) => file,
+					Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+					Err(source) => {
+						return Err(
+							tg::error!(!source, %path = path.display(), "failed to open the file"),
+						);
+					},
+				};
+				file.set_modified(touched_at).map_err(
+					|source| tg::error!(!source, %path = path.display(), "failed to set the modified time"),
+				)?;
+			}
+			Ok::<_, tg::Error>(())
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, %id, "failed to touch the object"))?;
+		Ok(())
+	}
+
+	fn object_path(&self, id: &tg::object::Id) -> PathBuf {
+		self.shard_path(id).join(id.to_string())
+	}
+
+	fn cache_reference_path(&self, id: &tg::object::Id) -> PathBuf {
+		self.shard_path(id).join(format!("{id}.cache_reference"))
+	}
+
+	fn shard_path(&self, id: &tg::object::Id) -> PathBuf {
+		// Shard the objects by the first four characters of their ids' bodies, which follow the kind, version, and algorithm and are uniformly distributed. The last character of a body encodes only the remaining bit of the hash, so it is not used.
+		let id = id.to_string();
+		let start = id.find('_').unwrap() + 3;
+		let prefix = &id[start..start + 4];
+		self.path.join(&prefix[0..2]).join(&prefix[2..4])
+	}
+}
+
+fn read(path: &Path) -> tg::Result<Option<Vec<u8>>> {
+	match std::fs::read(path) {
+		Ok(bytes) => Ok(Some(bytes)),
+		Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+		Err(source) => Err(tg::error!(!source, %path = path.display(), "failed to read the file")),
+	}
+}
+
+fn read_dir_sorted(path: &Path) -> tg::Result<Vec<PathBuf>> {
+	let mut paths = std::fs::read_dir(path)
+		.map_err(
+			|source| tg::error!(!source, %path = path.display(), "failed to read the directory"),
+		)?
+		.map(|entry| {
+			let entry = entry.map_err(
+				|source| tg::error!(!source, %path = path.display(), "failed to read the directory entry"),
+			)?;
+			Ok(entry.path())
+		})
+		.collect::<tg::Result<Vec<_>>>()?;
+	paths.sort_unstable();
+	Ok(paths)
+}
+
+fn read_cache_reference(path: &Path) -> tg::Result<Option<CacheReference>> {
+	let Some(bytes) = read(path)? else {
+		return Ok(None);
+	};
+	let reference = serde_json::from_slice(&bytes)
+		.map_err(|source| tg::error!(!source, "failed to deserialize the reference"))?;
+	Ok(Some(reference))
+}
+
+fn write(temp_path: &Path, path: &Path, bytes: &[u8], touched_at: SystemTime) -> tg::Result<()> {
+	// Write the bytes to a temp file and then rename it into place, so that readers never observe a partially written file.
+	let temp_path = temp_path.join(format!("{:016x}", rand::random::<u64>()));
+	let mut file = std::fs::File::create(&temp_path).map_err(
+		|source| tg::error!(!source, %path = temp_path.display(), "failed to create the file"),
+	)?;
+	file.write_all(bytes).map_err(
+		|source| tg::error!(!source, %path = temp_path.display(), "failed to write the file"),
+	)?;
+	file.set_modified(touched_at).map_err(
+		|source| tg::error!(!source, %path = temp_path.display(), "failed to set the modified time"),
+	)?;
+	file.sync_all().map_err(
+		|source| tg::error!(!source, %path = temp_path.display(), "failed to sync the file"),
+	)?;
+	drop(file);
+	let parent = path.parent().unwrap();
+	let created = parent
+		.ancestors()
+		.take_while(|ancestor| !ancestor.exists())
+		.collect::<Vec<_>>();
+	if !created.is_empty() {
+		std::fs::create_dir_all(parent).map_err(
+			|source| tg::error!(!source, %path = parent.display(), "failed to create the directory"),
+		)?;
+		for directory in created.into_iter().rev() {
+			sync_dir(directory.parent().unwrap())?;
+		}
+	}
+	std::fs::rename(&temp_path, path).map_err(
+		|source| tg::error!(!source, %path = path.display(), "failed to rename the file"),
+	)?;
+
+	// Sync the parent directory, so that the rename survives an unclean shutdown.
+	sync_dir(parent)?;
+
+	Ok(())
+}
+
+fn sync_dir(path: &Path) -> tg::Result<()> {
+	std::fs::File::open(path)
+		.and_then(|file| file.sync_all())
+		.map_err(
+			|source| tg::error!(!source, %path = path.display(), "failed to sync the directory"),
+		)?;
+	Ok(())
+}
+
+fn remove(path: &Path) -> tg::Result<()> {
+	match std::fs::remove_file(path) {
+		Ok(()) => Ok(()),
+		Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		Err(source) => {
+			Err(tg::error!(!source, %path = path.display(), "failed to remove the file"))
+		},
+	}
+}
+
+fn modified(path: &Path) -> tg::Result<Option<SystemTime>> {
+	match std::fs::metadata(path) {
+		Ok(metadata) => {
+			let modified = metadata.modified().map_err(
+				|source| tg::error!(!source, %path = path.display(), "failed to get the modified time"),
+			)?;
+			Ok(Some(modified))
+		},
+		Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+		Err(source) => {
+			Err(tg::error!(!source, %path = path.display(), "failed to get the metadata"))
+		},
+	}
+}
+
+fn system_time(timestamp: i64) -> SystemTime {
+	UNIX_EPOCH + Duration::from_secs(timestamp.to_u64().unwrap_or_default())
+}
+
+fn timestamp(system_time: SystemTime) -> i64 {
+	system_time
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |duration| duration.as_secs().to_i64().unwrap())
+}
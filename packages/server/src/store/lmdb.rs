@@ -3,8 +3,15 @@ use bytes::Bytes;
 use foundationdb_tuple::TuplePack as _;
 use heed as lmdb;
 use num::ToPrimitive as _;
+use std::{collections::HashSet, ops::Bound};
 use tangram_client as tg;
 
+#[cfg(test)]
+mod tests;
+
+/// The number of objects to evict in each write transaction, so that puts are not blocked while the store is over its maximum size.
+const EVICTION_BATCH_SIZE: usize = 1024;
+
 pub struct Lmdb {
 	db: lmdb::Database<lmdb::types::Bytes, lmdb::types::Bytes>,
 	env: lmdb::Env,
@@ -14,6 +21,7 @@ pub struct Lmdb {
 
 enum Message {
 	Delete(Delete),
+	Evict(Evict),
 	Put(Put),
 	Touch(Touch),
 }
@@ -25,6 +33,11 @@ struct Delete {
 	ttl: u64,
 }
 
+struct Evict {
+	max_size: u64,
+	response_sender: tokio::sync::oneshot::Sender<tg::Result<bool>>,
+}
+
 struct Put {
 	items: Vec<(tg::object::Id, Option<Bytes>, Option<CacheReference>)>,
 	touched_at: i64,
@@ -59,6 +72,7 @@ impl Lmdb {
 		let db: heed::Database<heed::types::Bytes, heed::types::Bytes> = env
 			.create_database(&mut transaction, None)
 			.map_err(|source| tg::error!(!source, "failed to open the database"))?;
+		Self::create_eviction_index(&mut transaction, db)?;
 		transaction
 			.commit()
 			.map_err(|source| tg::error!(!source, "failed to commit the transaction"))?;
@@ -183,7 +197,7 @@ impl Lmdb {
 		else {
 			return Ok(None);
 		};
-		let data = tg::object::Data::deserialize(id.kind(), bytes)?;
+		let data = tg::object::Data::deserialize(id.kind(), super::decompress(bytes)?)?;
 		Ok(Some(data))
 	}
 
@@ -217,6 +231,45 @@ impl Lmdb {
 		Ok(reference)
 	}
 
+	pub async fn try_get_touched_at_batch(
+		&self,
+		ids: &[tg::object::Id],
+	) -> tg::Result<Vec<Option<i64>>> {
+		if ids.is_empty() {
+			return Ok(vec![]);
+		}
+		let touched_ats = tokio::task::spawn_blocking({
+			let db = self.db;
+			let env = self.env.clone();
+			let ids = ids.to_owned();
+			move || {
+				let transaction = env
+					.read_txn()
+					.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+				let mut output = Vec::with_capacity(ids.len());
+				for id in ids {
+					let key = (0, id.to_bytes(), 1);
+					let touched_at = db
+						.get(&transaction, &key.pack_to_vec())
+						.map_err(|source| tg::error!(!source, "failed to get the touch time"))?
+						.map(|value| {
+							let value = value
+								.try_into()
+								.map_err(|source| tg::error!(!source, "invalid touch time"))?;
+							Ok::<_, tg::Error>(i64::from_le_bytes(value))
+						})
+						.transpose()?;
+					output.push(touched_at);
+				}
+				Ok::<_, tg::Error>(output)
+			}
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to get the touch times"))?;
+		Ok(touched_ats)
+	}
+
 	pub fn try_get_cache_reference_sync(
 		&self,
 		id: &tg::object::Id,
@@ -238,6 +291,120 @@ impl Lmdb {
 		Ok::<_, tg::Error>(Some(reference))
 	}
 
+	pub async fn list(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		let ids = tokio::task::spawn_blocking({
+			let db = self.db;
+			let env = self.env.clone();
+			let after = after.cloned();
+			move || {
+				let transaction = env
+					.read_txn()
+					.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+				let start = match &after {
+					Some(after) => (0, after.to_bytes(), 3).pack_to_vec(),
+					None => (0,).pack_to_vec(),
+				};
+				let end = (1,).pack_to_vec();
+				let range = (
+					Bound::Included(start.as_slice()),
+					Bound::Excluded(end.as_slice()),
+				);
+				let iter = db
+					.range(&transaction, &range)
+					.map_err(|source| tg::error!(!source, "failed to iterate the database"))?;
+				let mut output = Vec::new();
+				let mut last = None;
+				for entry in iter {
+					let (key, _) = entry.map_err(|source| {
+						tg::error!(!source, "failed to read the database entry")
+					})?;
+					let Ok((_, bytes, _)) = foundationdb_tuple::unpack::<(i64, Vec<u8>, i64)>(key)
+					else {
+						continue;
+					};
+					if last.as_ref() == Some(&bytes) {
+						continue;
+					}
+					if output.len() == limit {
+						break;
+					}
+					output.push(tg::object::Id::from_slice(&bytes)?);
+					last = Some(bytes);
+				}
+				Ok::<_, tg::Error>(output)
+			}
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to list the objects"))?;
+		Ok(ids)
+	}
+
+	/// List up to `limit` objects after `after` that have a cache reference and no bytes.
+	pub async fn list_cache_references(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		let ids = tokio::task::spawn_blocking({
+			let db = self.db;
+			let env = self.env.clone();
+			let after = after.cloned();
+			move || {
+				let transaction = env
+					.read_txn()
+					.map_err(|source| tg::error!(!source, "failed to begin a transaction"))?;
+				let start = match &after {
+					Some(after) => (0, after.to_bytes(), 3).pack_to_vec(),
+					None => (0,).pack_to_vec(),
+				};
+				let end = (1,).pack_to_vec();
+				let range = (
+					Bound::Included(start.as_slice()),
+					Bound::Excluded(end.as_slice()),
+				);
+				let mut output = Vec::new();
+				for entry in db
+					.range(&transaction, &range)
+					.map_err(|source| tg::error!(!source, "failed to iterate the database"))?
+				{
+					if output.len() == limit {
+						break;
+					}
+					let (key, _) = entry.map_err(|source| {
+						tg::error!(!source, "failed to read the database entry")
+					})?;
+					let Ok((_, bytes, kind)) =
+						foundationdb_tuple::unpack::<(i64, Vec<u8>, i64)>(key)
+					else {
+						continue;
+					};
+					if kind != 2 {
+						continue;
+					}
+					let key = (0, bytes.clone(), 0).pack_to_vec();
+					let exists = db
+						.get(&transaction, &key)
+						.map_err(|source| tg::error!(!source, "failed to get the value"))?
+						.is_some();
+					if exists {
+						continue;
+					}
+					output.push(tg::object::Id::from_slice(&bytes)?);
+				}
+				Ok::<_, tg::Error>(output)
+			}
+		})
+		.await
+		.map_err(|source| tg::error!(!source, "the task panicked"))?
+		.map_err(|source| tg::error!(!source, "failed to list the cache references"))?;
+		Ok(ids)
+	}
+
 	pub async fn put(&self, arg: super::PutArg) -> tg::Result<()> {
 		let (sender, receiver) = tokio::sync::oneshot::channel();
 		let message = Message::Put(Put {
@@ -313,6 +480,28 @@ impl Lmdb {
 		Ok(())
 	}
 
+	pub async fn evict(&self, max_size: u64) -> tg::Result<()> {
+		// Evict in batches, so that other messages are handled between them.
+		loop {
+			let (sender, receiver) = tokio::sync::oneshot::channel();
+			let message = Message::Evict(Evict {
+				max_size,
+				response_sender: sender,
+			});
+			self.sender
+				.send(message)
+				.await
+				.map_err(|source| tg::error!(!source, "failed to send the message"))?;
+			let more = receiver
+				.await
+				.map_err(|_| tg::error!("the task panicked"))??;
+			if !more {
+				break;
+			}
+		}
+		Ok(())
+	}
+
 	async fn task(
 		env: lmdb::Env,
 		db: lmdb::Database<lmdb::types::Bytes, lmdb::types::Bytes>,
@@ -347,6 +536,10 @@ impl Lmdb {
 								db.delete(&mut transaction, &key.pack_to_vec()).map_err(
 									|source| tg::error!(!source, "failed to delete the object"),
 								)?;
+								let key = (1, touched_at, id.to_bytes());
+								db.delete(&mut transaction, &key.pack_to_vec()).map_err(
+									|source| tg::error!(!source, "failed to delete the object"),
+								)?;
 								let key = (0, id.to_bytes(), 2);
 								db.delete(&mut transaction, &key.pack_to_vec()).map_err(
 									|source| tg::error!(!source, "failed to delete the object"),
@@ -361,6 +554,20 @@ impl Lmdb {
 					.await;
 					message.response_sender.send(result).ok();
 				},
+				Message::Evict(message) => {
+					let result = async {
+						let mut transaction = env.write_txn().map_err(|source| {
+							tg::error!(!source, "failed to begin a transaction")
+						})?;
+						let more = Self::evict_inner(&mut transaction, db, message.max_size)?;
+						transaction.commit().map_err(|source| {
+							tg::error!(!source, "failed to commit the transaction")
+						})?;
+						Ok::<_, tg::Error>(more)
+					}
+					.await;
+					message.response_sender.send(result).ok();
+				},
 				Message::Put(message) => {
 					let result = async {
 						let mut transaction = env.write_txn().map_err(|source| {
@@ -383,10 +590,6 @@ impl Lmdb {
 									},
 								}
 							}
-							let key = (0, id.to_bytes(), 1);
-							let touched_at = message.touched_at.to_le_bytes();
-							db.put(&mut transaction, &key.pack_to_vec(), &touched_at)
-								.map_err(|source| tg::error!(!source, "failed to put the value"))?;
 							if let Some(reference) = reference {
 								let key = (0, id.to_bytes(), 2);
 								let value = serde_json::to_vec(&reference).unwrap();
@@ -395,6 +598,7 @@ impl Lmdb {
 										tg::error!(!source, "failed to put the value")
 									})?;
 							}
+							Self::put_touched_at(&mut transaction, db, &id, message.touched_at)?;
 						}
 						transaction.commit().map_err(|source| {
 							tg::error!(!source, "failed to commit the transaction")
@@ -410,10 +614,7 @@ impl Lmdb {
 							tg::error!(!source, "failed to begin a transaction")
 						})?;
 						for id in message.ids {
-							let key = (0, id.to_bytes(), 1);
-							let touched_at = message.touched_at.to_le_bytes();
-							db.put(&mut transaction, &key.pack_to_vec(), &touched_at)
-								.map_err(|source| tg::error!(!source, "failed to put the value"))?;
+							Self::put_touched_at(&mut transaction, db, &id, message.touched_at)?;
 						}
 						transaction.commit().map_err(|source| {
 							tg::error!(!source, "failed to commit the transaction")
@@ -426,6 +627,166 @@ impl Lmdb {
 			}
 		}
 	}
+
+	/// Set an object's touch time and update its key in the eviction index. The eviction index has a key `(1, touched_at, id)` for each object without a cache reference, so the least recently touched objects can be found without scanning the database.
+	fn put_touched_at(
+		transaction: &mut lmdb::RwTxn,
+		db: lmdb::Database<lmdb::types::Bytes, lmdb::types::Bytes>,
+		id: &tg::object::Id,
+		touched_at: i64,
+	) -> tg::Result<()> {
+		let key = (0, id.to_bytes(), 1).pack_to_vec();
+		let previous = db
+			.get(transaction, &key)
+			.map_err(|source| tg::error!(!source, "failed to get the touch time"))?
+			.map(|value| {
+				let value = value
+					.try_into()
+					.map_err(|source| tg::error!(!source, "invalid touch time"))?;
+				Ok::<_, tg::Error>(i64::from_le_bytes(value))
+			})
+			.transpose()?;
+		if let Some(previous) = previous {
+			let key = (1, previous, id.to_bytes()).pack_to_vec();
+			db.delete(transaction, &key)
+				.map_err(|source| tg::error!(!source, "failed to delete the value"))?;
+		}
+		db.put(transaction, &key, &touched_at.to_le_bytes())
+			.map_err(|source| tg::error!(!source, "failed to put the value"))?;
+
+		// Objects with cache references are not evicted, because their bytes are only stored locally.
+		let reference = (0, id.to_bytes(), 2).pack_to_vec();
+		let reference = db
+			.get(transaction, &reference)
+			.map_err(|source| tg::error!(!source, "failed to get the value"))?
+			.is_some();
+		if !reference {
+			let key = (1, touched_at, id.to_bytes()).pack_to_vec();
+			db.put(transaction, &key, &[])
+				.map_err(|source| tg::error!(!source, "failed to put the value"))?;
+		}
+
+		Ok(())
+	}
+
+	/// Create the eviction index if the database was created before it existed.
+	fn create_eviction_index(
+		transaction: &mut lmdb::RwTxn,
+		db: lmdb::Database<lmdb::types::Bytes, lmdb::types::Bytes>,
+	) -> tg::Result<()> {
+		let marker = (2,).pack_to_vec();
+		let exists = db
+			.get(transaction, &marker)
+			.map_err(|source| tg::error!(!source, "failed to get the value"))?
+			.is_some();
+		if exists {
+			return Ok(());
+		}
+		let mut keys = Vec::new();
+		let mut references = HashSet::new();
+		let end = (1,).pack_to_vec();
+		let range = (Bound::<&[u8]>::Unbounded, Bound::Excluded(end.as_slice()));
+		for entry in db
+			.range(transaction, &range)
+			.map_err(|source| tg::error!(!source, "failed to iterate the database"))?
+		{
+			let (key, value) =
+				entry.map_err(|source| tg::error!(!source, "failed to read the database entry"))?;
+			let Ok((_, id, kind)) = foundationdb_tuple::unpack::<(i64, Vec<u8>, i64)>(key) else {
+				continue;
+			};
+			match kind {
+				1 => {
+					let touched_at = value
+						.try_into()
+						.map_err(|source| tg::error!(!source, "invalid touch time"))?;
+					let touched_at = i64::from_le_bytes(touched_at);
+					keys.push((touched_at, id));
+				},
+				2 => {
+					references.insert(id);
+				},
+				_ => (),
+			}
+		}
+		for (touched_at, id) in keys {
+			if references.contains(&id) {
+				continue;
+			}
+			let key = (1, touched_at, id).pack_to_vec();
+			db.put(transaction, &key, &[])
+				.map_err(|source| tg::error!(!source, "failed to put the value"))?;
+		}
+		db.put(transaction, &marker, &[])
+			.map_err(|source| tg::error!(!source, "failed to put the value"))?;
+		Ok(())
+	}
+
+	/// Evict a batch of the least recently touched objects if the database is larger than its maximum size. Returns whether the database may still be too large.
+	fn evict_inner(
+		transaction: &mut lmdb::RwTxn,
+		db: lmdb::Database<lmdb::types::Bytes, lmdb::types::Bytes>,
+		max_size: u64,
+	) -> tg::Result<bool> {
+		// Get the size of the database.
+		let stat = db
+			.stat(transaction)
+			.map_err(|source| tg::error!(!source, "failed to get the database stats"))?;
+		let pages = stat.branch_pages + stat.leaf_pages + stat.overflow_pages;
+		let size = (pages * stat.page_size.to_usize().unwrap())
+			.to_u64()
+			.unwrap();
+		if size <= max_size {
+			return Ok(false);
+		}
+		let mut excess = size - max_size;
+
+		// Get a batch of the least recently touched objects from the eviction index.
+		let start = (1,).pack_to_vec();
+		let end = (2,).pack_to_vec();
+		let range = (
+			Bound::Included(start.as_slice()),
+			Bound::Excluded(end.as_slice()),
+		);
+		let mut objects = Vec::new();
+		for entry in db
+			.range(transaction, &range)
+			.map_err(|source| tg::error!(!source, "failed to iterate the database"))?
+			.take(EVICTION_BATCH_SIZE)
+		{
+			let (key, _) =
+				entry.map_err(|source| tg::error!(!source, "failed to read the database entry"))?;
+			let Ok((_, touched_at, id)) = foundationdb_tuple::unpack::<(i64, i64, Vec<u8>)>(key)
+			else {
+				continue;
+			};
+			objects.push((touched_at, id));
+		}
+		let full = objects.len() == EVICTION_BATCH_SIZE;
+
+		// Delete the objects until the database is within its maximum size.
+		for (touched_at, id) in objects {
+			if excess == 0 {
+				break;
+			}
+			let key = (0, id.clone(), 0).pack_to_vec();
+			let length = db
+				.get(transaction, &key)
+				.map_err(|source| tg::error!(!source, "failed to get the value"))?
+				.map_or(0, <[u8]>::len);
+			db.delete(transaction, &key)
+				.map_err(|source| tg::error!(!source, "failed to delete the object"))?;
+			let key = (0, id.clone(), 1).pack_to_vec();
+			db.delete(transaction, &key)
+				.map_err(|source| tg::error!(!source, "failed to delete the object"))?;
+			let key = (1, touched_at, id).pack_to_vec();
+			db.delete(transaction, &key)
+				.map_err(|source| tg::error!(!source, "failed to delete the object"))?;
+			excess = excess.saturating_sub(length.to_u64().unwrap());
+		}
+
+		Ok(excess > 0 && full)
+	}
 }
 
 impl Drop for Lmdb {
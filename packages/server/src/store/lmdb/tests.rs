@@ -0,0 +1,75 @@
+use super::Lmdb;
+use crate::store::{CacheReference, PutArg};
+use bytes::Bytes;
+use tangram_client as tg;
+use tangram_temp::Temp;
+
+const SIZE: usize = 1 << 20;
+
+/// Test that eviction deletes the least recently touched objects.
+#[tokio::test]
+async fn evict_least_recently_touched() {
+	let temp = Temp::new();
+	let lmdb = create(&temp).await;
+	let a = put(&lmdb, 'a', 1, None).await;
+	let b = put(&lmdb, 'b', 2, None).await;
+	let c = put(&lmdb, 'c', 3, None).await;
+
+	// Touch the first object, so that it is the most recently touched.
+	lmdb.touch(&a, 4).await.unwrap();
+
+	// Evict until only one object fits.
+	lmdb.evict((SIZE + SIZE / 2).try_into().unwrap())
+		.await
+		.unwrap();
+	assert!(lmdb.try_get(&a).await.unwrap().is_some());
+	assert!(lmdb.try_get(&b).await.unwrap().is_none());
+	assert!(lmdb.try_get(&c).await.unwrap().is_none());
+}
+
+/// Test that eviction does not delete objects with cache references.
+#[tokio::test]
+async fn evict_skips_cache_references() {
+	let temp = Temp::new();
+	let lmdb = create(&temp).await;
+	let reference = CacheReference {
+		artifact: tg::directory::Id::new(&Bytes::new()).into(),
+		length: 0,
+		position: 0,
+		subpath: None,
+	};
+	let a = put(&lmdb, 'a', 1, Some(reference)).await;
+	let b = put(&lmdb, 'b', 2, None).await;
+
+	// Evict everything that can be evicted.
+	lmdb.evict(0).await.unwrap();
+	assert!(lmdb.try_get(&a).await.unwrap().is_some());
+	assert!(lmdb.try_get_cache_reference(&a).await.unwrap().is_some());
+	assert!(lmdb.try_get(&b).await.unwrap().is_none());
+}
+
+async fn create(temp: &Temp) -> Lmdb {
+	tokio::fs::create_dir_all(temp.path()).await.unwrap();
+	let config = crate::config::LmdbStore {
+		path: temp.path().join("store"),
+	};
+	Lmdb::new(&config).unwrap()
+}
+
+async fn put(
+	lmdb: &Lmdb,
+	byte: char,
+	touched_at: i64,
+	cache_reference: Option<CacheReference>,
+) -> tg::object::Id {
+	let bytes = Bytes::from(byte.to_string().repeat(SIZE));
+	let id: tg::object::Id = tg::blob::Id::new(&bytes).into();
+	let arg = PutArg {
+		bytes: Some(bytes),
+		cache_reference,
+		id: id.clone(),
+		touched_at,
+	};
+	lmdb.put(arg).await.unwrap();
+	id
+}
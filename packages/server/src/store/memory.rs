@@ -34,7 +34,7 @@ impl Memory {
 		let Some(bytes) = &entry.bytes else {
 			return Ok(None);
 		};
-		let data = tg::object::Data::deserialize(id.kind(), bytes.as_ref())?;
+		let data = tg::object::Data::deserialize(id.kind(), super::decompress(bytes.as_ref())?)?;
 		Ok(Some(data))
 	}
 
@@ -44,6 +44,24 @@ impl Memory {
 		Some(cache_reference)
 	}
 
+	pub fn try_get_touched_at_batch(&self, ids: &[tg::object::Id]) -> Vec<Option<i64>> {
+		ids.iter()
+			.map(|id| Some(self.0.get(id)?.touched_at))
+			.collect()
+	}
+
+	pub fn list(&self, after: Option<&tg::object::Id>, limit: usize) -> Vec<tg::object::Id> {
+		let mut ids = self
+			.0
+			.iter()
+			.map(|entry| entry.key().clone())
+			.filter(|id| after.is_none_or(|after| id > after))
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids.truncate(limit);
+		ids
+	}
+
 	pub fn put(&self, arg: super::PutArg) {
 		let entry = Entry {
 			bytes: arg.bytes,
@@ -0,0 +1,155 @@
+use super::{Backend, PutBatchArg, Store};
+use futures::{Stream, TryStreamExt as _, stream::FuturesOrdered};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use tangram_client as tg;
+use tangram_futures::stream::Ext as _;
+use tokio_util::task::AbortOnDropHandle;
+
+#[cfg(test)]
+mod tests;
+
+const BATCH_SIZE: usize = 1_000;
+
+pub struct MigrateStoreArg {
+	/// The path to a file that records the last migrated object, so that an interrupted migration can be resumed.
+	pub checkpoint: Option<PathBuf>,
+
+	/// The compression to apply to objects written to the destination store.
+	pub compression: Option<crate::config::StoreCompression>,
+
+	/// The store to migrate from.
+	pub from: crate::config::Store,
+
+	/// The store to migrate to.
+	pub to: crate::config::Store,
+}
+
+/// Copy every object and cache reference from one store to another.
+pub fn migrate_store(
+	arg: MigrateStoreArg,
+) -> tg::Result<impl Stream<Item = tg::Result<tg::progress::Event<()>>> + Send + 'static> {
+	let metrics = Arc::new(crate::metrics::Metrics::new());
+	let from = Store::new(Backend::new(&arg.from)?, None, metrics.clone());
+	let to = Store::new(Backend::new(&arg.to)?, arg.compression.clone(), metrics);
+	let progress = crate::progress::Handle::new();
+	let task = AbortOnDropHandle::new(tokio::spawn({
+		let progress = progress.clone();
+		async move {
+			let result = migrate_store_inner(&from, &to, arg.checkpoint.as_ref(), &progress).await;
+			progress.finish_all();
+			match result {
+				Ok(()) => {
+					progress.output(());
+				},
+				Err(error) => {
+					progress.error(error);
+				},
+			}
+		}
+	}));
+	let stream = progress.stream().attach(task);
+	Ok(stream)
+}
+
+async fn migrate_store_inner(
+	from: &Store,
+	to: &Store,
+	checkpoint: Option<&PathBuf>,
+	progress: &crate::progress::Handle<()>,
+) -> tg::Result<()> {
+	// Resume after the last migrated object if there is a checkpoint.
+	let mut after = if let Some(path) = checkpoint {
+		match tokio::fs::read_to_string(path).await {
+			Ok(string) => Some(string.trim().parse::<tg::object::Id>()?),
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+			Err(source) => {
+				return Err(
+					tg::error!(!source, %path = path.display(), "failed to read the checkpoint"),
+				);
+			},
+		}
+	} else {
+		None
+	};
+
+	progress.start(
+		"objects".to_owned(),
+		"objects".to_owned(),
+		tg::progress::IndicatorFormat::Normal,
+		Some(0),
+		None,
+	);
+
+	loop {
+		// Get a batch of ids.
+		let ids = from.backend.list(after.as_ref(), BATCH_SIZE).await?;
+		let Some(last) = ids.last().cloned() else {
+			break;
+		};
+
+		// Get the bytes and cache references.
+		let bytes = from.try_get_batch(&ids).await?;
+		let references = ids
+			.iter()
+			.map(|id| from.try_get_cache_reference(id))
+			.collect::<FuturesOrdered<_>>()
+			.try_collect::<Vec<_>>()
+			.await?;
+
+		// Get the touch times. Objects without one are touched now.
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let touched_ats = from.backend.try_get_touched_at_batch(&ids).await?;
+
+		// Verify the ids and group the objects by their touch times.
+		let mut objects = BTreeMap::<i64, Vec<_>>::new();
+		let mut count = 0;
+		for (((id, bytes), reference), touched_at) in
+			ids.into_iter().zip(bytes).zip(references).zip(touched_ats)
+		{
+			if let Some(bytes) = &bytes {
+				let actual = tg::object::Id::new(id.kind(), bytes);
+				if actual != id {
+					return Err(tg::error!(
+						%expected = id,
+						%actual,
+						"the object's bytes do not match its id"
+					));
+				}
+			}
+			if bytes.is_none() && reference.is_none() {
+				continue;
+			}
+			objects
+				.entry(touched_at.unwrap_or(now))
+				.or_default()
+				.push((id, bytes, reference));
+			count += 1;
+		}
+
+		// Put the objects with their touch times.
+		for (touched_at, objects) in objects {
+			let arg = PutBatchArg {
+				objects,
+				touched_at,
+			};
+			to.put_batch(arg).await?;
+		}
+		progress.increment("objects", count.try_into().unwrap());
+
+		// Update the checkpoint.
+		if let Some(path) = checkpoint {
+			tokio::fs::write(path, last.to_string()).await.map_err(
+				|source| tg::error!(!source, %path = path.display(), "failed to write the checkpoint"),
+			)?;
+		}
+
+		after = Some(last);
+	}
+
+	// Remove the checkpoint once the migration is complete.
+	if let Some(path) = checkpoint {
+		crate::util::fs::remove(path).await.ok();
+	}
+
+	Ok(())
+}
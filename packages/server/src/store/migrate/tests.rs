@@ -0,0 +1,122 @@
+use super::migrate_store_inner;
+use crate::store::{Backend, CacheReference, PutArg, PutBatchArg, Store};
+use bytes::Bytes;
+use std::sync::Arc;
+use tangram_client as tg;
+use tangram_temp::Temp;
+
+const COUNT: usize = 2_500;
+
+/// Test migrating every object from an lmdb store to an fs store.
+#[tokio::test]
+async fn lmdb_to_fs() {
+	let temp = Temp::new();
+	let (from, to) = create(&temp).await;
+	let (ids, reference) = put(&from).await;
+
+	// Migrate the objects.
+	let progress = crate::progress::Handle::new();
+	migrate_store_inner(&from, &to, None, &progress)
+		.await
+		.unwrap();
+
+	// Confirm that every object and cache reference was migrated.
+	let bytes = to.try_get_batch(&ids).await.unwrap();
+	assert!(bytes.iter().all(Option::is_some));
+	assert!(
+		to.try_get_cache_reference(&reference)
+			.await
+			.unwrap()
+			.is_some()
+	);
+
+	// Confirm that the objects kept their touch times.
+	let touched_ats = to.backend.try_get_touched_at_batch(&ids).await.unwrap();
+	assert!(touched_ats.iter().all(|touched_at| *touched_at == Some(0)));
+}
+
+/// Test resuming a migration from a checkpoint.
+#[tokio::test]
+async fn resume_from_checkpoint() {
+	let temp = Temp::new();
+	let (from, to) = create(&temp).await;
+	put(&from).await;
+
+	// Write a checkpoint in the middle of the listed objects.
+	let listed = from.backend.list(None, COUNT + 1).await.unwrap();
+	assert_eq!(listed.len(), COUNT + 1);
+	let (before, after) = listed.split_at(COUNT / 2);
+	let checkpoint = temp.path().join("checkpoint");
+	tokio::fs::write(&checkpoint, before.last().unwrap().to_string())
+		.await
+		.unwrap();
+
+	// Migrate the objects.
+	let progress = crate::progress::Handle::new();
+	migrate_store_inner(&from, &to, Some(&checkpoint), &progress)
+		.await
+		.unwrap();
+
+	// Confirm that only the objects after the checkpoint were migrated and that the checkpoint was removed.
+	for id in before {
+		assert!(to.try_get(id).await.unwrap().is_none());
+		assert!(to.try_get_cache_reference(id).await.unwrap().is_none());
+	}
+	for id in after {
+		let migrated = to.try_get(id).await.unwrap().is_some()
+			|| to.try_get_cache_reference(id).await.unwrap().is_some();
+		assert!(migrated);
+	}
+	assert!(!tokio::fs::try_exists(&checkpoint).await.unwrap());
+}
+
+async fn create(temp: &Temp) -> (Store, Store) {
+	tokio::fs::create_dir_all(temp.path()).await.unwrap();
+	let metrics = Arc::new(crate::metrics::Metrics::new());
+	let from = Backend::new_lmdb(&crate::config::LmdbStore {
+		path: temp.path().join("store"),
+	})
+	.unwrap();
+	let from = Store::new(from, None, metrics.clone());
+	let to = Backend::new_fs(&crate::config::FsStore {
+		path: temp.path().join("objects"),
+	})
+	.unwrap();
+	let to = Store::new(to, None, metrics);
+	(from, to)
+}
+
+/// Put objects with bytes and an object with only a cache reference.
+async fn put(store: &Store) -> (Vec<tg::object::Id>, tg::object::Id) {
+	let mut ids = Vec::with_capacity(COUNT);
+	let mut objects = Vec::with_capacity(COUNT);
+	for index in 0..COUNT {
+		let leaf = tg::blob::Data::Leaf(tg::blob::data::Leaf {
+			bytes: Bytes::from(index.to_string()),
+		});
+		let bytes = leaf.serialize().unwrap();
+		let id: tg::object::Id = tg::blob::Id::new(&bytes).into();
+		ids.push(id.clone());
+		objects.push((id, Some(bytes), None));
+	}
+	let arg = PutBatchArg {
+		objects,
+		touched_at: 0,
+	};
+	store.put_batch(arg).await.unwrap();
+	let reference = CacheReference {
+		artifact: tg::directory::Id::new(&Bytes::new()).into(),
+		length: 0,
+		position: 0,
+		subpath: None,
+	};
+	let id: tg::object::Id = tg::blob::Id::new(b"reference").into();
+	let arg = PutArg {
+		bytes: None,
+		cache_reference: Some(reference),
+		id: id.clone(),
+		touched_at: 0,
+	};
+	store.put(arg).await.unwrap();
+	(ids, id)
+}
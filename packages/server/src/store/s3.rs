@@ -1,10 +1,23 @@
 use super::CacheReference;
 use bytes::Bytes;
-use futures::{TryStreamExt as _, stream::FuturesUnordered};
+use futures::{
+	TryStreamExt as _,
+	stream::{FuturesOrdered, FuturesUnordered},
+};
 use num::ToPrimitive as _;
+use std::{fmt::Write as _, time::Duration};
 use tangram_client as tg;
 use time::format_description::well_known::Rfc2822;
 
+#[cfg(test)]
+mod tests;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+const MAX_PARTS: usize = 10_000;
+
 pub struct S3 {
 	credentials: Option<aws_credential_types::Credentials>,
 	config: crate::config::S3Store,
@@ -29,7 +42,7 @@ impl S3 {
 			None
 		};
 		let reqwest = reqwest::Client::new();
-		let semaphore = tokio::sync::Semaphore::new(256);
+		let semaphore = tokio::sync::Semaphore::new(config.concurrency);
 		Self {
 			credentials,
 			config: config.clone(),
@@ -39,24 +52,10 @@ impl S3 {
 	}
 
 	pub async fn try_get(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
-		let _permit = self.semaphore.acquire().await;
-		let method = reqwest::Method::GET;
-		let url = tangram_uri::Reference::parse(self.config.url.as_str()).unwrap();
-		let authority = url.authority().ok_or_else(|| tg::error!("invalid url"))?;
-		let bucket = &self.config.bucket;
-		let url = url
-			.to_builder()
-			.authority(format!("{bucket}.{authority}"))
-			.path(format!("/{id}"))
-			.build()
-			.unwrap();
-		let request = self.reqwest.request(method, url.as_str()).build().unwrap();
-		let request = self.sign_request(request)?;
+		let url = self.url(&id.to_string(), None)?;
 		let response = self
-			.reqwest
-			.execute(request)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to send the request"))?;
+			.send(reqwest::Method::GET, &url, http::HeaderMap::new(), None)
+			.await?;
 		if response.status() == http::StatusCode::NOT_FOUND {
 			return Ok(None);
 		}
@@ -77,7 +76,7 @@ impl S3 {
 	pub async fn try_get_batch(&self, ids: &[tg::object::Id]) -> tg::Result<Vec<Option<Bytes>>> {
 		ids.iter()
 			.map(|id| self.try_get(id))
-			.collect::<FuturesUnordered<_>>()
+			.collect::<FuturesOrdered<_>>()
 			.try_collect()
 			.await
 	}
@@ -89,41 +88,160 @@ impl S3 {
 		Ok(None)
 	}
 
+	pub async fn try_get_touched_at_batch(
+		&self,
+		ids: &[tg::object::Id],
+	) -> tg::Result<Vec<Option<i64>>> {
+		// Objects are not touched in S3.
+		Ok(vec![None; ids.len()])
+	}
+
+	pub async fn list(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		let mut query = format!("list-type=2&max-keys={limit}");
+		if let Some(after) = after {
+			write!(query, "&start-after={after}").unwrap();
+		}
+		let url = self.url("", Some(query))?;
+		let response = self
+			.send(reqwest::Method::GET, &url, http::HeaderMap::new(), None)
+			.await?;
+		let text = Self::response_text(response).await?;
+		let ids = xml_elements(&text, "Key")
+			.filter_map(|key| key.parse().ok())
+			.collect();
+		Ok(ids)
+	}
+
 	pub async fn put(&self, arg: super::PutArg) -> tg::Result<()> {
-		let _permit = self.semaphore.acquire().await;
-		let method = reqwest::Method::PUT;
-		let url = tangram_uri::Reference::parse(self.config.url.as_str()).unwrap();
-		let authority = url.authority().ok_or_else(|| tg::error!("invalid url"))?;
-		let bucket = &self.config.bucket;
-		let url = url
-			.to_builder()
-			.authority(format!("{bucket}.{authority}"))
-			.path(format!("/{id}", id = arg.id))
-			.build()
-			.unwrap();
-		let request = self
-			.reqwest
-			.request(method, url.as_str())
-			.header(
-				http::header::CONTENT_LENGTH,
-				arg.bytes.as_ref().unwrap().len().to_string(),
+		let bytes = arg
+			.bytes
+			.ok_or_else(|| tg::error!(id = %arg.id, "expected the bytes to be set"))?;
+		if bytes.len() > self.config.multipart_threshold {
+			return self.put_multipart(&arg.id, bytes).await;
+		}
+		let url = self.url(&arg.id.to_string(), None)?;
+		let response = self
+			.send(
+				reqwest::Method::PUT,
+				&url,
+				http::HeaderMap::new(),
+				Some(bytes),
 			)
-			.body(arg.bytes.unwrap())
-			.build()
-			.unwrap();
-		let request = self.sign_request(request)?;
+			.await?;
+		Self::response_text(response).await?;
+		Ok(())
+	}
+
+	async fn put_multipart(&self, id: &tg::object::Id, bytes: Bytes) -> tg::Result<()> {
+		// Create the upload.
+		let key = id.to_string();
+		let url = self.url(&key, Some("uploads".to_owned()))?;
 		let response = self
-			.reqwest
-			.execute(request)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to send the request"))?;
-		if !response.status().is_success() {
-			let text = response
-				.text()
+			.send(
+				reqwest::Method::POST,
+				&url,
+				http::HeaderMap::new(),
+				Some(Bytes::new()),
+			)
+			.await?;
+		let text = Self::response_text(response).await?;
+		let upload_id = xml_element(&text, "UploadId")
+			.ok_or_else(|| tg::error!(%text, "failed to get the upload id"))?
+			.to_owned();
+
+		// Upload the parts and complete the upload. If either fails, then abort the upload.
+		let result = self.put_multipart_inner(&key, &upload_id, bytes).await;
+		if result.is_err() {
+			let query = format!("uploadId={}", urlencoding::encode(&upload_id));
+			let url = self.url(&key, Some(query))?;
+			self.send(reqwest::Method::DELETE, &url, http::HeaderMap::new(), None)
 				.await
-				.map_err(|source| tg::error!(!source, "failed to read the response body"))?;
-			return Err(tg::error!(%text, "the request failed"));
+				.inspect_err(|error| tracing::error!(?error, "failed to abort the upload"))
+				.ok();
+		}
+
+		result
+	}
+
+	async fn put_multipart_inner(
+		&self,
+		key: &str,
+		upload_id: &str,
+		bytes: Bytes,
+	) -> tg::Result<()> {
+		// Upload the parts. S3 allows at most 10,000 parts, so increase the part size if necessary.
+		let part_size = self
+			.config
+			.multipart_part_size
+			.max(bytes.len().div_ceil(MAX_PARTS));
+		let upload_id = urlencoding::encode(upload_id);
+		let parts = (0..bytes.len())
+			.step_by(part_size)
+			.enumerate()
+			.map(|(index, start)| {
+				let end = (start + part_size).min(bytes.len());
+				let bytes = bytes.slice(start..end);
+				let number = index + 1;
+				let upload_id = upload_id.clone();
+				async move {
+					let query = format!("partNumber={number}&uploadId={upload_id}");
+					let url = self.url(key, Some(query))?;
+					let response = self
+						.send(
+							reqwest::Method::PUT,
+							&url,
+							http::HeaderMap::new(),
+							Some(bytes),
+						)
+						.await?;
+					if !response.status().is_success() {
+						let text = response.text().await.map_err(|source| {
+							tg::error!(!source, "failed to read the response body")
+						})?;
+						return Err(tg::error!(%text, "the request failed"));
+					}
+					let etag = response
+						.headers()
+						.get(http::header::ETAG)
+						.and_then(|value| value.to_str().ok())
+						.ok_or_else(|| tg::error!("expected the etag header to be set"))?
+						.to_owned();
+					Ok::<_, tg::Error>((number, etag))
+				}
+			})
+			.collect::<FuturesOrdered<_>>()
+			.try_collect::<Vec<_>>()
+			.await?;
+
+		// Complete the upload.
+		let mut body = String::from("<CompleteMultipartUpload>");
+		for (number, etag) in parts {
+			write!(
+				body,
+				"<Part><PartNumber>{number}</PartNumber><ETag>{etag}</ETag></Part>"
+			)
+			.unwrap();
+		}
+		body.push_str("</CompleteMultipartUpload>");
+		let query = format!("uploadId={upload_id}");
+		let url = self.url(key, Some(query))?;
+		let response = self
+			.send(
+				reqwest::Method::POST,
+				&url,
+				http::HeaderMap::new(),
+				Some(body.into()),
+			)
+			.await?;
+		let text = Self::response_text(response).await?;
+		if text.contains("<Error>") {
+			return Err(tg::error!(%text, "failed to complete the upload"));
 		}
+
 		Ok(())
 	}
 
@@ -148,41 +266,21 @@ impl S3 {
 	}
 
 	pub async fn delete(&self, arg: super::DeleteArg) -> tg::Result<()> {
-		let _permit = self.semaphore.acquire().await;
-		let method = reqwest::Method::DELETE;
-		let url = tangram_uri::Reference::parse(self.config.url.as_str()).unwrap();
-		let authority = url.authority().ok_or_else(|| tg::error!("invalid url"))?;
-		let bucket = &self.config.bucket;
-		let url = url
-			.to_builder()
-			.authority(format!("{bucket}.{authority}"))
-			.path(format!("/{id}", id = arg.id))
-			.build()
-			.unwrap();
+		let url = self.url(&arg.id.to_string(), None)?;
 		let if_unmodified_since =
 			time::OffsetDateTime::from_unix_timestamp(arg.now - arg.ttl.to_i64().unwrap())
 				.unwrap()
 				.format(&Rfc2822)
 				.unwrap();
-		let request = self
-			.reqwest
-			.request(method, url.as_str())
-			.header(http::header::IF_UNMODIFIED_SINCE, if_unmodified_since)
-			.build()
-			.unwrap();
-		let request = self.sign_request(request)?;
+		let mut headers = http::HeaderMap::new();
+		headers.insert(
+			http::header::IF_UNMODIFIED_SINCE,
+			if_unmodified_since.parse().unwrap(),
+		);
 		let response = self
-			.reqwest
-			.execute(request)
-			.await
-			.map_err(|source| tg::error!(!source, "failed to send the request"))?;
-		if !response.status().is_success() {
-			let text = response
-				.text()
-				.await
-				.map_err(|source| tg::error!(!source, "failed to read the response body"))?;
-			return Err(tg::error!(%text, "the request failed"));
-		}
+			.send(reqwest::Method::DELETE, &url, headers, None)
+			.await?;
+		Self::response_text(response).await?;
 		Ok(())
 	}
 
@@ -202,6 +300,81 @@ impl S3 {
 			.await
 	}
 
+	fn url(&self, key: &str, query: Option<String>) -> tg::Result<tangram_uri::Reference> {
+		let url = tangram_uri::Reference::parse(self.config.url.as_str()).unwrap();
+		let bucket = &self.config.bucket;
+		let builder = if self.config.path_style {
+			let path = url.path().trim_end_matches('/');
+			url.to_builder().path(format!("{path}/{bucket}/{key}"))
+		} else {
+			let authority = url.authority().ok_or_else(|| tg::error!("invalid url"))?;
+			url.to_builder()
+				.authority(format!("{bucket}.{authority}"))
+				.path(format!("/{key}"))
+		};
+		let url = builder.query(query).build().unwrap();
+		Ok(url)
+	}
+
+	async fn send(
+		&self,
+		method: reqwest::Method,
+		url: &tangram_uri::Reference,
+		headers: http::HeaderMap,
+		body: Option<Bytes>,
+	) -> tg::Result<reqwest::Response> {
+		let mut retries = 0;
+		loop {
+			// Build and sign the request.
+			let mut request = self
+				.reqwest
+				.request(method.clone(), url.as_str())
+				.headers(headers.clone());
+			if let Some(body) = &body {
+				request = request
+					.header(http::header::CONTENT_LENGTH, body.len().to_string())
+					.body(body.clone());
+			}
+			let request = request.build().unwrap();
+			let request = self.sign_request(request)?;
+
+			// Send the request.
+			let result = {
+				let _permit = self.semaphore.acquire().await;
+				self.reqwest.execute(request).await
+			};
+
+			// Retry on errors, server errors, and throttling.
+			let retry = match &result {
+				Ok(response) => retryable(response.status()),
+				Err(_) => true,
+			};
+			if retry && retries < self.config.max_retries {
+				let backoff = backoff(retries);
+				let jitter = backoff.mul_f64(rand::random::<f64>() / 2.0);
+				tokio::time::sleep(backoff / 2 + jitter).await;
+				retries += 1;
+				continue;
+			}
+
+			let response =
+				result.map_err(|source| tg::error!(!source, "failed to send the request"))?;
+			return Ok(response);
+		}
+	}
+
+	async fn response_text(response: reqwest::Response) -> tg::Result<String> {
+		let success = response.status().is_success();
+		let text = response
+			.text()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to read the response body"))?;
+		if !success {
+			return Err(tg::error!(%text, "the request failed"));
+		}
+		Ok(text)
+	}
+
 	fn sign_request(&self, request: reqwest::Request) -> tg::Result<reqwest::Request> {
 		let mut signing_params_builder = aws_sigv4::sign::v4::SigningParams::builder();
 		let identity = self.credentials.clone().map(Into::into);
@@ -242,3 +415,32 @@ impl S3 {
 		Ok(request)
 	}
 }
+
+/// Determine whether a request that received a response with this status should be retried.
+fn retryable(status: http::StatusCode) -> bool {
+	status.is_server_error() || status == http::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Get the backoff before the retry after `retries` retries, without jitter.
+fn backoff(retries: usize) -> Duration {
+	INITIAL_BACKOFF
+		.saturating_mul(2u32.saturating_pow(retries.to_u32().unwrap_or(u32::MAX)))
+		.min(MAX_BACKOFF)
+}
+
+fn xml_element<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+	xml_elements(text, name).next()
+}
+
+fn xml_elements<'a>(text: &'a str, name: &str) -> impl Iterator<Item = &'a str> + use<'a> {
+	let start_tag = format!("<{name}>");
+	let end_tag = format!("</{name}>");
+	let mut text = text;
+	std::iter::from_fn(move || {
+		let start = text.find(&start_tag)? + start_tag.len();
+		let end = start + text[start..].find(&end_tag)?;
+		let element = &text[start..end];
+		text = &text[end + end_tag.len()..];
+		Some(element)
+	})
+}
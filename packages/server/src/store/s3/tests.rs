@@ -0,0 +1,163 @@
+use super::{MAX_BACKOFF, S3, backoff, retryable, xml_element, xml_elements};
+use bytes::Bytes;
+use std::time::Duration;
+use tangram_client as tg;
+
+/// Test the url of a path style request.
+#[test]
+fn url_path_style() {
+	let config = crate::config::S3Store {
+		bucket: "bucket".to_owned(),
+		path_style: true,
+		url: "http://localhost:9000/prefix/".parse().unwrap(),
+		..Default::default()
+	};
+	let s3 = S3::new(&config);
+	let url = s3.url("key", None).unwrap();
+	assert_eq!(url.as_str(), "http://localhost:9000/prefix/bucket/key");
+	let url = s3.url("", Some("list-type=2".to_owned())).unwrap();
+	assert_eq!(
+		url.as_str(),
+		"http://localhost:9000/prefix/bucket/?list-type=2"
+	);
+}
+
+/// Test the url of a virtual hosted style request.
+#[test]
+fn url_virtual_hosted_style() {
+	let config = crate::config::S3Store {
+		bucket: "bucket".to_owned(),
+		path_style: false,
+		url: "http://localhost:9000".parse().unwrap(),
+		..Default::default()
+	};
+	let s3 = S3::new(&config);
+	let url = s3.url("key", Some("uploads".to_owned())).unwrap();
+	assert_eq!(url.as_str(), "http://bucket.localhost:9000/key?uploads");
+}
+
+/// Test getting elements from xml.
+#[test]
+fn xml() {
+	let text = "<ListBucketResult><Contents><Key>a</Key></Contents><Contents><Key>b</Key></Contents><IsTruncated>false</IsTruncated></ListBucketResult>";
+	assert_eq!(xml_elements(text, "Key").collect::<Vec<_>>(), ["a", "b"]);
+	assert_eq!(xml_element(text, "Key"), Some("a"));
+	assert_eq!(xml_element(text, "IsTruncated"), Some("false"));
+	assert_eq!(xml_element(text, "UploadId"), None);
+	assert_eq!(xml_element("<Key>a", "Key"), None);
+	assert_eq!(xml_elements("", "Key").count(), 0);
+}
+
+/// Test which responses are retried.
+#[test]
+fn retry_classification() {
+	assert!(retryable(http::StatusCode::INTERNAL_SERVER_ERROR));
+	assert!(retryable(http::StatusCode::SERVICE_UNAVAILABLE));
+	assert!(retryable(http::StatusCode::TOO_MANY_REQUESTS));
+	assert!(!retryable(http::StatusCode::OK));
+	assert!(!retryable(http::StatusCode::NOT_FOUND));
+	assert!(!retryable(http::StatusCode::FORBIDDEN));
+	assert!(!retryable(http::StatusCode::PRECONDITION_FAILED));
+}
+
+/// Test that the backoff doubles with each retry up to the maximum.
+#[test]
+fn retry_backoff() {
+	assert_eq!(backoff(0), Duration::from_millis(100));
+	assert_eq!(backoff(1), Duration::from_millis(200));
+	assert_eq!(backoff(3), Duration::from_millis(800));
+	assert_eq!(backoff(10), MAX_BACKOFF);
+	assert_eq!(backoff(usize::MAX), MAX_BACKOFF);
+}
+
+/// Test that failed requests are retried until they succeed or the retries are exhausted.
+#[tokio::test]
+async fn retry() {
+	let server = crate::test::s3::S3::start().await;
+	let s3 = S3::new(&server.config());
+	let id = id(b"Hello, World!");
+	put(&s3, &id, Bytes::from_static(b"Hello, World!"))
+		.await
+		.unwrap();
+
+	// Two failures are within the default three retries.
+	server.fail(http::Method::GET, 2);
+	let requests = server.requests();
+	let bytes = s3.try_get(&id).await.unwrap().unwrap();
+	assert_eq!(bytes, Bytes::from_static(b"Hello, World!"));
+	assert_eq!(server.requests() - requests, 3);
+
+	// Four failures exhaust them.
+	server.fail(http::Method::GET, 4);
+	let requests = server.requests();
+	assert!(s3.try_get(&id).await.is_err());
+	assert_eq!(server.requests() - requests, 4);
+}
+
+/// Test listing objects in pages.
+#[tokio::test]
+async fn list() {
+	let server = crate::test::s3::S3::start().await;
+	let s3 = S3::new(&server.config());
+	let mut ids = Vec::new();
+	for index in 0..5u8 {
+		let id = id(&[index]);
+		put(&s3, &id, Bytes::from(vec![index])).await.unwrap();
+		ids.push(id);
+	}
+	ids.sort_by_key(ToString::to_string);
+	let first = s3.list(None, 3).await.unwrap();
+	assert_eq!(first, ids[..3]);
+	let second = s3.list(first.last(), 3).await.unwrap();
+	assert_eq!(second, ids[3..]);
+}
+
+/// Test that a large object is uploaded in parts.
+#[tokio::test]
+async fn multipart() {
+	let server = crate::test::s3::S3::start().await;
+	let config = crate::config::S3Store {
+		multipart_part_size: 4,
+		multipart_threshold: 8,
+		..server.config()
+	};
+	let s3 = S3::new(&config);
+	let bytes = Bytes::from_static(b"0123456789");
+	let id = id(&bytes);
+	put(&s3, &id, bytes.clone()).await.unwrap();
+	assert_eq!(server.get(&id.to_string()), Some(bytes));
+	assert_eq!(server.uploads(), 0);
+}
+
+/// Test that a multipart upload is aborted if a part fails to upload.
+#[tokio::test]
+async fn multipart_abort() {
+	let server = crate::test::s3::S3::start().await;
+	let config = crate::config::S3Store {
+		max_retries: 0,
+		multipart_part_size: 4,
+		multipart_threshold: 8,
+		..server.config()
+	};
+	let s3 = S3::new(&config);
+	let bytes = Bytes::from_static(b"0123456789");
+	let id = id(&bytes);
+	server.fail(http::Method::PUT, 1);
+	assert!(put(&s3, &id, bytes).await.is_err());
+	assert_eq!(server.get(&id.to_string()), None);
+	assert_eq!(server.uploads(), 0);
+}
+
+fn id(bytes: &[u8]) -> tg::object::Id {
+	tg::blob::Id::new(bytes).into()
+}
+
+async fn put(s3: &S3, id: &tg::object::Id, bytes: Bytes) -> tg::Result<()> {
+	let arg = super::super::PutArg {
+		bytes: Some(bytes),
+		cache_reference: None,
+		id: id.clone(),
+		touched_at: 0,
+	};
+	s3.put(arg).await
+}
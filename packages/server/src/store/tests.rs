@@ -0,0 +1,177 @@
+use super::{COMPRESSED, PutBatchArg};
+use crate::test::test;
+use bytes::Bytes;
+use std::{collections::BTreeMap, path::Path};
+use tangram_client as tg;
+use tangram_temp::Temp;
+
+/// Test reading compressed and uncompressed objects from the fs store.
+#[tokio::test]
+async fn compression_fs() {
+	test_compression(|directory| {
+		crate::config::Store::Fs(crate::config::FsStore {
+			path: directory.join("objects"),
+		})
+	})
+	.await;
+}
+
+/// Test reading compressed and uncompressed objects from the lmdb store.
+#[tokio::test]
+async fn compression_lmdb() {
+	test_compression(|directory| {
+		crate::config::Store::Lmdb(crate::config::LmdbStore {
+			path: directory.join("store"),
+		})
+	})
+	.await;
+}
+
+/// Test reading compressed and uncompressed objects from the memory store.
+#[tokio::test]
+async fn compression_memory() {
+	test_compression(|_| crate::config::Store::Memory).await;
+}
+
+/// Test that the fs store removes the temp files of interrupted writes and shards objects by the start of their ids' bodies.
+#[tokio::test]
+async fn fs_temp_files_and_shards() {
+	let temp = Temp::new();
+	let config = crate::config::FsStore {
+		path: temp.path().to_owned(),
+	};
+	std::fs::create_dir_all(temp.path().join("tmp")).unwrap();
+	std::fs::write(temp.path().join("tmp").join("0123456789abcdef"), b"").unwrap();
+	let fs = super::Fs::new(&config).unwrap();
+	assert_eq!(
+		std::fs::read_dir(temp.path().join("tmp")).unwrap().count(),
+		0
+	);
+
+	let (id, bytes, _) = objects().remove(0);
+	let arg = PutBatchArg {
+		objects: vec![(id.clone(), Some(bytes), None)],
+		touched_at: 0,
+	};
+	fs.put_batch(arg).await.unwrap();
+	let id_ = id.to_string();
+	let body = &id_[id_.find('_').unwrap() + 3..];
+	let path = temp.path().join(&body[0..2]).join(&body[2..4]).join(&id_);
+	assert!(path.exists());
+	assert_eq!(fs.list(None, 10).await.unwrap(), vec![id]);
+}
+
+/// Test that serialized objects never begin with the compressed flag byte.
+#[test]
+fn compressed_flag() {
+	for (_, bytes, _) in objects() {
+		assert_ne!(bytes.first(), Some(&COMPRESSED));
+		let decompressed = super::decompress(bytes.as_ref()).unwrap();
+		assert_eq!(decompressed.as_ref(), bytes.as_ref());
+	}
+}
+
+async fn test_compression(store: impl FnOnce(&Path) -> crate::config::Store + Send) {
+	test(async move |context| {
+		let temp = Temp::new();
+		let mut config = crate::test::config(temp.path());
+		config.advanced.store_compression = Some(crate::config::StoreCompression::default());
+		config.store = store(temp.path());
+		let server = context
+			.start_server_with_temp_and_config(temp, config)
+			.await;
+
+		// Put the objects.
+		let objects = objects();
+		let arg = PutBatchArg {
+			objects: objects
+				.iter()
+				.map(|(id, bytes, _)| (id.clone(), Some(bytes.clone()), None))
+				.collect(),
+			touched_at: 0,
+		};
+		server.store.put_batch(arg).await.unwrap();
+
+		for (id, bytes, compressed) in &objects {
+			// Confirm whether the object was stored compressed.
+			let stored = server.store.backend.try_get(id).await.unwrap().unwrap();
+			assert_eq!(stored.first() == Some(&COMPRESSED), *compressed);
+
+			// Read the object's bytes.
+			let output = server.try_get_object_sync(id, &mut None).unwrap().unwrap();
+			assert_eq!(output.bytes, *bytes);
+
+			// Read the object's data.
+			let data = match &server.store.backend {
+				crate::store::Backend::Fs(fs) => fs.try_get_object_data_sync(id),
+				crate::store::Backend::Lmdb(lmdb) => lmdb.try_get_object_data_sync(id),
+				crate::store::Backend::Memory(memory) => memory.try_get_object_data(id),
+				_ => unreachable!(),
+			}
+			.unwrap()
+			.unwrap();
+			assert_eq!(data.serialize().unwrap(), *bytes);
+		}
+	})
+	.await;
+}
+
+/// Create objects that are stored compressed and uncompressed with the default compression config.
+fn objects() -> Vec<(tg::object::Id, Bytes, bool)> {
+	let mut objects = Vec::new();
+
+	// A compressible leaf.
+	let leaf = tg::blob::Data::Leaf(tg::blob::data::Leaf {
+		bytes: Bytes::from(vec![b'a'; 4096]),
+	});
+	let bytes = leaf.serialize().unwrap();
+	let leaf_id = tg::blob::Id::new(&bytes);
+	objects.push((leaf_id.clone().into(), bytes, true));
+
+	// A leaf smaller than the threshold.
+	let leaf = tg::blob::Data::Leaf(tg::blob::data::Leaf {
+		bytes: Bytes::from_static(b"Hello, World!"),
+	});
+	let bytes = leaf.serialize().unwrap();
+	objects.push((tg::blob::Id::new(&bytes).into(), bytes, false));
+
+	// An incompressible leaf whose contents begin with the compressed flag byte.
+	let contents = std::iter::once(COMPRESSED)
+		.chain((0..4096).map(|_| rand::random::<u8>()))
+		.collect::<Vec<_>>();
+	let leaf = tg::blob::Data::Leaf(tg::blob::data::Leaf {
+		bytes: contents.into(),
+	});
+	let bytes = leaf.serialize().unwrap();
+	objects.push((tg::blob::Id::new(&bytes).into(), bytes, false));
+
+	// A compressible branch.
+	let branch = tg::blob::Data::Branch(tg::blob::data::Branch {
+		children: (0..64)
+			.map(|_| tg::blob::data::Child {
+				blob: leaf_id.clone(),
+				length: 4096,
+			})
+			.collect(),
+	});
+	let bytes = branch.serialize().unwrap();
+	objects.push((tg::blob::Id::new(&bytes).into(), bytes, true));
+
+	// A compressible directory.
+	let entries = (0..64)
+		.map(|index| {
+			let file = tg::file::Data::Node(tg::file::data::Node {
+				contents: leaf_id.clone(),
+				dependencies: BTreeMap::new(),
+				executable: false,
+			});
+			let id = tg::file::Id::new(&file.serialize().unwrap());
+			(format!("{index}.txt"), id.into())
+		})
+		.collect();
+	let directory = tg::directory::Data::Node(tg::directory::data::Node { entries });
+	let bytes = directory.serialize().unwrap();
+	objects.push((tg::directory::Id::new(&bytes).into(), bytes, true));
+
+	objects
+}
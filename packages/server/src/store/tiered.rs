@@ -0,0 +1,249 @@
+use super::{CacheReference, Lmdb, S3};
+use bytes::Bytes;
+use futures::future;
+use std::sync::Arc;
+use tangram_client as tg;
+
+#[cfg(test)]
+mod tests;
+
+pub struct Tiered {
+	local: Arc<Lmdb>,
+	remote: S3,
+	task: tokio::task::JoinHandle<()>,
+}
+
+impl Tiered {
+	pub fn new(config: &crate::config::TieredStore) -> tg::Result<Self> {
+		let local = Arc::new(Lmdb::new(&config.local)?);
+		let remote = S3::new(&config.remote);
+
+		// Create the eviction task.
+		let task = tokio::spawn({
+			let local = local.clone();
+			let interval = config.eviction_interval;
+			let max_size = config.max_size;
+			async move {
+				loop {
+					tokio::time::sleep(interval).await;
+					local
+						.evict(max_size)
+						.await
+						.inspect_err(|error| tracing::error!(?error, "failed to evict objects"))
+						.ok();
+				}
+			}
+		});
+
+		Ok(Self {
+			local,
+			remote,
+			task,
+		})
+	}
+
+	pub async fn try_get(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
+		// Attempt to get the bytes from the local store.
+		if let Some(bytes) = self.local.try_get(id).await? {
+			return Ok(Some(bytes));
+		}
+
+		// Attempt to get the bytes from the remote store.
+		self.try_get_remote(id).await
+	}
+
+	/// Get an object's bytes from the local store, falling back to the remote store. This blocks on the remote store, so it must be called from a blocking thread.
+	pub fn try_get_sync(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
+		// Attempt to get the bytes from the local store.
+		if let Some(bytes) = self.local.try_get_sync(id)? {
+			return Ok(Some(bytes));
+		}
+
+		// Attempt to get the bytes from the remote store.
+		tokio::runtime::Handle::current().block_on(self.try_get_remote(id))
+	}
+
+	pub fn try_get_object_data_sync(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<tg::object::Data>> {
+		let Some(bytes) = self.try_get_sync(id)? else {
+			return Ok(None);
+		};
+		let data = tg::object::Data::deserialize(id.kind(), super::decompress(bytes)?)?;
+		Ok(Some(data))
+	}
+
+	async fn try_get_remote(&self, id: &tg::object::Id) -> tg::Result<Option<Bytes>> {
+		// Get the bytes from the remote store.
+		let Some(bytes) = self.remote.try_get(id).await? else {
+			return Ok(None);
+		};
+
+		// Populate the local store. A failure is logged rather than returned, because the bytes were read successfully.
+		let arg = super::PutArg {
+			bytes: Some(bytes.clone()),
+			cache_reference: None,
+			id: id.clone(),
+			touched_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+		};
+		self.local
+			.put(arg)
+			.await
+			.inspect_err(|error| tracing::error!(?error, "failed to populate the local store"))
+			.ok();
+
+		Ok(Some(bytes))
+	}
+
+	pub async fn try_get_batch(&self, ids: &[tg::object::Id]) -> tg::Result<Vec<Option<Bytes>>> {
+		// Get the bytes from the local store.
+		let mut output = self.local.try_get_batch(ids).await?;
+
+		// Get the missing bytes from the remote store.
+		let (indices, missing): (Vec<_>, Vec<_>) = output
+			.iter()
+			.enumerate()
+			.filter(|(_, bytes)| bytes.is_none())
+			.map(|(index, _)| (index, ids[index].clone()))
+			.unzip();
+		if missing.is_empty() {
+			return Ok(output);
+		}
+		let bytes = self.remote.try_get_batch(&missing).await?;
+
+		// Populate the local store. A failure is logged rather than returned, because the bytes were read successfully.
+		let objects = missing
+			.into_iter()
+			.zip(&bytes)
+			.filter_map(|(id, bytes)| Some((id, Some(bytes.clone()?), None)))
+			.collect();
+		let arg = super::PutBatchArg {
+			objects,
+			touched_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+		};
+		self.local
+			.put_batch(arg)
+			.await
+			.inspect_err(|error| tracing::error!(?error, "failed to populate the local store"))
+			.ok();
+
+		// Fill in the output.
+		for (index, bytes) in indices.into_iter().zip(bytes) {
+			output[index] = bytes;
+		}
+
+		Ok(output)
+	}
+
+	pub async fn try_get_cache_reference(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<CacheReference>> {
+		self.local.try_get_cache_reference(id).await
+	}
+
+	pub fn try_get_cache_reference_sync(
+		&self,
+		id: &tg::object::Id,
+	) -> tg::Result<Option<CacheReference>> {
+		self.local.try_get_cache_reference_sync(id)
+	}
+
+	pub async fn try_get_touched_at_batch(
+		&self,
+		ids: &[tg::object::Id],
+	) -> tg::Result<Vec<Option<i64>>> {
+		// Objects are only touched in the local store.
+		self.local.try_get_touched_at_batch(ids).await
+	}
+
+	pub async fn list(
+		&self,
+		after: Option<&tg::object::Id>,
+		limit: usize,
+	) -> tg::Result<Vec<tg::object::Id>> {
+		// List the objects with bytes, which are stored in the remote store, followed by the objects with only a cache reference, which are stored only in the local store. If the last listed object has only a cache reference, then the remote store has already been listed.
+		let local = if let Some(after) = after {
+			self.local.try_get_cache_reference(after).await?.is_some()
+				&& self.local.try_get(after).await?.is_none()
+		} else {
+			false
+		};
+		if local {
+			return self.local.list_cache_references(after, limit).await;
+		}
+		let mut ids = self.remote.list(after, limit).await?;
+		if ids.len() < limit {
+			let references = self
+				.local
+				.list_cache_references(None, limit - ids.len())
+				.await?;
+			ids.extend(references);
+		}
+		Ok(ids)
+	}
+
+	pub async fn put(&self, arg: super::PutArg) -> tg::Result<()> {
+		// Objects with only a cache reference are stored locally.
+		let remote = if let Some(bytes) = &arg.bytes {
+			let arg = super::PutArg {
+				bytes: Some(bytes.clone()),
+				cache_reference: None,
+				id: arg.id.clone(),
+				touched_at: arg.touched_at,
+			};
+			Some(arg)
+		} else {
+			None
+		};
+		let remote = async {
+			if let Some(arg) = remote {
+				self.remote.put(arg).await?;
+			}
+			Ok::<_, tg::Error>(())
+		};
+		future::try_join(self.local.put(arg), remote).await?;
+		Ok(())
+	}
+
+	pub async fn put_batch(&self, arg: super::PutBatchArg) -> tg::Result<()> {
+		// Objects with only a cache reference are stored locally.
+		let objects = arg
+			.objects
+			.iter()
+			.filter(|(_, bytes, _)| bytes.is_some())
+			.map(|(id, bytes, _)| (id.clone(), bytes.clone(), None))
+			.collect();
+		let remote = super::PutBatchArg {
+			objects,
+			touched_at: arg.touched_at,
+		};
+		future::try_join(self.local.put_batch(arg), self.remote.put_batch(remote)).await?;
+		Ok(())
+	}
+
+	pub async fn delete_batch(&self, arg: super::DeleteBatchArg) -> tg::Result<()> {
+		let local = super::DeleteBatchArg {
+			ids: arg.ids.clone(),
+			now: arg.now,
+			ttl: arg.ttl,
+		};
+		future::try_join(
+			self.local.delete_batch(local),
+			self.remote.delete_batch(arg),
+		)
+		.await?;
+		Ok(())
+	}
+
+	pub async fn touch(&self, id: &tg::object::Id, touched_at: i64) -> tg::Result<()> {
+		self.local.touch(id, touched_at).await
+	}
+}
+
+impl Drop for Tiered {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
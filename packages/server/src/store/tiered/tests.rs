@@ -0,0 +1,102 @@
+use crate::test::{s3::S3, test};
+use std::{path::Path, pin::pin, time::Duration};
+use tangram_client as tg;
+use tangram_futures::stream::TryExt as _;
+use tangram_temp::Temp;
+
+/// Test checking out and caching an artifact whose objects have been evicted from the local store.
+#[tokio::test]
+async fn checkout() {
+	test(async move |context| {
+		let s3 = S3::start().await;
+		let temp = Temp::new();
+		let mut config = crate::test::config(temp.path());
+		config.store = crate::config::Store::Tiered(crate::config::TieredStore {
+			eviction_interval: Duration::from_secs(3600),
+			local: crate::config::LmdbStore {
+				path: temp.path().join("store"),
+			},
+			max_size: u64::MAX,
+			remote: s3.config(),
+		});
+		let server = context
+			.start_server_with_temp_and_config(temp, config)
+			.await;
+
+		// Store the artifact.
+		let artifact = tg::Artifact::from(tg::directory! {
+			"hello.txt" => "Hello, World!",
+		});
+		let artifact = artifact.store(&server).await.unwrap();
+		assert!(!s3.keys().is_empty());
+
+		// Check out the artifact from the local store.
+		let temp = Temp::new();
+		checkout(&server, &artifact, temp.path()).await;
+		let contents = tokio::fs::read_to_string(temp.path().join("hello.txt"))
+			.await
+			.unwrap();
+		assert_eq!(contents, "Hello, World!");
+
+		// Evict every object from the local store.
+		let crate::store::Backend::Tiered(tiered) = &server.store.backend else {
+			unreachable!()
+		};
+		tiered.local.evict(0).await.unwrap();
+		assert!(
+			tiered
+				.local
+				.try_get_sync(&artifact.clone().into())
+				.unwrap()
+				.is_none()
+		);
+
+		// Check out the artifact from the remote store.
+		let temp = Temp::new();
+		checkout(&server, &artifact, temp.path()).await;
+		let contents = tokio::fs::read_to_string(temp.path().join("hello.txt"))
+			.await
+			.unwrap();
+		assert_eq!(contents, "Hello, World!");
+
+		// Cache the artifact.
+		let stream = server
+			.checkout(tg::checkout::Arg {
+				artifact: artifact.clone(),
+				dependencies: true,
+				force: false,
+				lockfile: false,
+				path: None,
+			})
+			.await
+			.unwrap();
+		let output = pin!(stream)
+			.try_last()
+			.await
+			.unwrap()
+			.and_then(|event| event.try_unwrap_output().ok())
+			.unwrap();
+		let contents = tokio::fs::read_to_string(output.path.join("hello.txt"))
+			.await
+			.unwrap();
+		assert_eq!(contents, "Hello, World!");
+	})
+	.await;
+}
+
+async fn checkout(server: &crate::Server, artifact: &tg::artifact::Id, path: &Path) {
+	let arg = tg::checkout::Arg {
+		artifact: artifact.clone(),
+		dependencies: true,
+		force: false,
+		lockfile: false,
+		path: Some(path.to_owned()),
+	};
+	let stream = server.checkout(arg).await.unwrap();
+	pin!(stream)
+		.try_last()
+		.await
+		.unwrap()
+		.and_then(|event| event.try_unwrap_output().ok())
+		.unwrap();
+}
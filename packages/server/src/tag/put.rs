@@ -28,12 +28,13 @@ impl Server {
 		let p = connection.p();
 		let statement = formatdoc!(
 			"
-				insert into tags (tag, item)
-				values ({p}1, {p}2)
-				on conflict (tag) do update set item = {p}2;
+				insert into tags (tag, item, updated_at)
+				values ({p}1, {p}2, {p}3)
+				on conflict (tag) do update set item = {p}2, updated_at = {p}3;
 			"
 		);
-		let params = db::params![tag, arg.item];
+		let updated_at = time::OffsetDateTime::now_utc().unix_timestamp();
+		let params = db::params![tag, arg.item, updated_at];
 		connection
 			.execute(statement.into(), params)
 			.await
@@ -1,8 +1,14 @@
 use crate::{Config, Server};
 use futures::FutureExt as _;
-use std::{collections::HashMap, panic::AssertUnwindSafe};
+use indoc::formatdoc;
+use std::{collections::HashMap, panic::AssertUnwindSafe, path::Path, pin::pin};
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_futures::stream::TryExt as _;
 use tangram_temp::Temp;
 
+pub mod s3;
+
 pub async fn test<F>(f: F)
 where
 	F: AsyncFnOnce(&mut Context) -> () + Send,
@@ -40,48 +46,7 @@ impl Context {
 
 	pub async fn start_server(&mut self) -> Server {
 		let temp = Temp::new();
-		let directory = temp.path().to_owned();
-		let advanced = crate::config::Advanced::default();
-		let authentication = None;
-		let cleaner = None;
-		let database = crate::config::Database::Sqlite(crate::config::SqliteDatabase {
-			connections: 1,
-			path: directory.join("database"),
-		});
-		let index = crate::config::Index::Sqlite(crate::config::SqliteIndex {
-			connections: 1,
-			path: directory.join("index"),
-		});
-		let indexer = Some(crate::config::Indexer::default());
-		let messenger = crate::config::Messenger::default();
-		let remotes = Some(Vec::new());
-		let runner = Some(crate::config::Runner::default());
-		let runtimes = HashMap::new();
-		let store = crate::config::Store::Lmdb(crate::config::LmdbStore {
-			path: directory.join("store"),
-		});
-		let http = Some(crate::config::Http::default());
-		let version = None;
-		let vfs = None;
-		let watchdog = Some(crate::config::Watchdog::default());
-		let config = Config {
-			advanced,
-			authentication,
-			cleaner,
-			database,
-			directory,
-			http,
-			index,
-			indexer,
-			messenger,
-			remotes,
-			runner,
-			runtimes,
-			store,
-			version,
-			vfs,
-			watchdog,
-		};
+		let config = config(temp.path());
 		self.start_server_with_temp_and_config(temp, config).await
 	}
 
@@ -96,3 +61,72 @@ impl Context {
 		server
 	}
 }
+
+#[must_use]
+pub fn config(directory: &Path) -> Config {
+	let directory = directory.to_owned();
+	let advanced = crate::config::Advanced::default();
+	let authentication = None;
+	let authorization = None;
+	let cleaner = None;
+	let database = crate::config::Database::Sqlite(crate::config::SqliteDatabase {
+		connections: 1,
+		path: directory.join("database"),
+	});
+	let index = crate::config::Index::Sqlite(crate::config::SqliteIndex {
+		connections: 1,
+		path: directory.join("index"),
+	});
+	let indexer = Some(crate::config::Indexer::default());
+	let messenger = crate::config::Messenger::default();
+	let remotes = Some(Vec::new());
+	let runner = Some(crate::config::Runner::default());
+	let runtimes = HashMap::new();
+	let store = crate::config::Store::Lmdb(crate::config::LmdbStore {
+		path: directory.join("store"),
+	});
+	let http = Some(crate::config::Http::default());
+	let version = None;
+	let vfs = None;
+	let watchdog = Some(crate::config::Watchdog::default());
+	Config {
+		advanced,
+		authentication,
+		authorization,
+		cleaner,
+		database,
+		directory,
+		http,
+		index,
+		indexer,
+		messenger,
+		remotes,
+		runner,
+		runtimes,
+		store,
+		version,
+		vfs,
+		watchdog,
+	}
+}
+
+/// Wait for the index to process all queued messages.
+pub async fn index(server: &Server) {
+	let stream = server.index().await.unwrap();
+	pin!(stream).try_last().await.unwrap();
+}
+
+/// Set columns of an object's row in the index, for example `"complete = 0, count = 1000"`.
+pub async fn update_object(server: &Server, id: &tg::object::Id, set: &str) {
+	let connection = server.index.write_connection().await.unwrap();
+	let p = connection.p();
+	let statement = formatdoc!(
+		"
+			update objects
+			set {set}
+			where id = {p}1;
+		"
+	);
+	let params = db::params![id];
+	connection.execute(statement.into(), params).await.unwrap();
+}
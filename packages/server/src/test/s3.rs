@@ -0,0 +1,245 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt as _, Full};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use std::{
+	collections::{BTreeMap, HashMap},
+	convert::Infallible,
+	fmt::Write as _,
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicUsize, Ordering},
+	},
+};
+
+/// An in-memory S3 server that handles the requests the S3 store makes with path style urls.
+pub struct S3 {
+	state: Arc<State>,
+	task: tokio::task::JoinHandle<()>,
+	url: url::Url,
+}
+
+#[derive(Default)]
+struct State {
+	// The number of upcoming requests with each method that will fail with a server error.
+	failures: Mutex<HashMap<http::Method, usize>>,
+
+	// The objects by key.
+	objects: Mutex<BTreeMap<String, Bytes>>,
+
+	// The number of requests received.
+	requests: AtomicUsize,
+
+	// The multipart uploads by upload id, with their keys and their parts by part number.
+	uploads: Mutex<HashMap<String, (String, BTreeMap<usize, Bytes>)>>,
+}
+
+impl S3 {
+	pub async fn start() -> Self {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let address = listener.local_addr().unwrap();
+		let url = format!("http://{address}").parse().unwrap();
+		let state = Arc::new(State::default());
+		let task = tokio::spawn({
+			let state = state.clone();
+			async move {
+				loop {
+					let Ok((stream, _)) = listener.accept().await else {
+						continue;
+					};
+					let state = state.clone();
+					tokio::spawn(async move {
+						let service = hyper::service::service_fn(move |request| {
+							let state = state.clone();
+							async move { Ok::<_, Infallible>(state.handle(request).await) }
+						});
+						hyper_util::server::conn::auto::Builder::new(TokioExecutor::new())
+							.serve_connection(TokioIo::new(stream), service)
+							.await
+							.ok();
+					});
+				}
+			}
+		});
+		Self { state, task, url }
+	}
+
+	/// Get the config for an S3 store that uses this server.
+	#[must_use]
+	pub fn config(&self) -> crate::config::S3Store {
+		crate::config::S3Store {
+			bucket: "bucket".to_owned(),
+			path_style: true,
+			url: self.url.clone(),
+			..Default::default()
+		}
+	}
+
+	/// Fail the next `count` requests with the method with a server error.
+	pub fn fail(&self, method: http::Method, count: usize) {
+		self.state.failures.lock().unwrap().insert(method, count);
+	}
+
+	/// Get an object's bytes.
+	#[must_use]
+	pub fn get(&self, key: &str) -> Option<Bytes> {
+		self.state.objects.lock().unwrap().get(key).cloned()
+	}
+
+	/// Get the keys of the stored objects.
+	#[must_use]
+	pub fn keys(&self) -> Vec<String> {
+		self.state.objects.lock().unwrap().keys().cloned().collect()
+	}
+
+	/// Get the number of requests received.
+	#[must_use]
+	pub fn requests(&self) -> usize {
+		self.state.requests.load(Ordering::SeqCst)
+	}
+
+	/// Get the number of multipart uploads that have been neither completed nor aborted.
+	#[must_use]
+	pub fn uploads(&self) -> usize {
+		self.state.uploads.lock().unwrap().len()
+	}
+}
+
+impl Drop for S3 {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
+
+impl State {
+	async fn handle(
+		&self,
+		request: http::Request<hyper::body::Incoming>,
+	) -> http::Response<Full<Bytes>> {
+		// Fail the request if necessary.
+		let count = self.requests.fetch_add(1, Ordering::SeqCst);
+		let method = request.method().clone();
+		if let Some(failures) = self.failures.lock().unwrap().get_mut(&method) {
+			if *failures > 0 {
+				*failures -= 1;
+				return response(http::StatusCode::SERVICE_UNAVAILABLE, Bytes::new());
+			}
+		}
+
+		// The path is `/{bucket}/{key}`.
+		let key = request
+			.uri()
+			.path()
+			.trim_start_matches('/')
+			.split_once('/')
+			.map_or("", |(_, key)| key)
+			.to_owned();
+		let query = request
+			.uri()
+			.query()
+			.unwrap_or_default()
+			.split('&')
+			.map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+			.map(|(name, value)| (name.to_owned(), value.to_owned()))
+			.collect::<HashMap<_, _>>();
+		let Ok(body) = request.into_body().collect().await else {
+			return response(http::StatusCode::BAD_REQUEST, Bytes::new());
+		};
+		let body = body.to_bytes();
+
+		match method {
+			// List the objects.
+			http::Method::GET if key.is_empty() => {
+				let max_keys = query
+					.get("max-keys")
+					.and_then(|max_keys| max_keys.parse().ok())
+					.unwrap_or(1000);
+				let start_after = query.get("start-after").cloned().unwrap_or_default();
+				let mut text = "<ListBucketResult>".to_owned();
+				for key in self
+					.objects
+					.lock()
+					.unwrap()
+					.keys()
+					.filter(|key| **key > start_after)
+					.take(max_keys)
+				{
+					write!(text, "<Contents><Key>{key}</Key></Contents>").unwrap();
+				}
+				text.push_str("</ListBucketResult>");
+				response(http::StatusCode::OK, text.into())
+			},
+
+			// Get an object.
+			http::Method::GET => match self.objects.lock().unwrap().get(&key) {
+				Some(bytes) => response(http::StatusCode::OK, bytes.clone()),
+				None => response(http::StatusCode::NOT_FOUND, Bytes::new()),
+			},
+
+			// Upload a part.
+			http::Method::PUT if query.contains_key("uploadId") => {
+				let number = query["partNumber"].parse().unwrap();
+				let mut uploads = self.uploads.lock().unwrap();
+				let Some((_, parts)) = uploads.get_mut(&query["uploadId"]) else {
+					return response(http::StatusCode::NOT_FOUND, Bytes::new());
+				};
+				parts.insert(number, body);
+				let mut response = response(http::StatusCode::OK, Bytes::new());
+				let etag = format!("\"{number}\"").parse().unwrap();
+				response.headers_mut().insert(http::header::ETAG, etag);
+				response
+			},
+
+			// Put an object.
+			http::Method::PUT => {
+				self.objects.lock().unwrap().insert(key, body);
+				response(http::StatusCode::OK, Bytes::new())
+			},
+
+			// Create an upload.
+			http::Method::POST if query.contains_key("uploads") => {
+				let upload_id = count.to_string();
+				self.uploads
+					.lock()
+					.unwrap()
+					.insert(upload_id.clone(), (key, BTreeMap::new()));
+				let text = format!(
+					"<InitiateMultipartUploadResult><UploadId>{upload_id}</UploadId></InitiateMultipartUploadResult>"
+				);
+				response(http::StatusCode::OK, text.into())
+			},
+
+			// Complete an upload.
+			http::Method::POST if query.contains_key("uploadId") => {
+				let upload = self.uploads.lock().unwrap().remove(&query["uploadId"]);
+				let Some((key, parts)) = upload else {
+					return response(http::StatusCode::NOT_FOUND, Bytes::new());
+				};
+				let bytes = parts.into_values().flatten().collect::<Vec<_>>();
+				self.objects.lock().unwrap().insert(key, bytes.into());
+				let text = "<CompleteMultipartUploadResult></CompleteMultipartUploadResult>";
+				response(http::StatusCode::OK, text.into())
+			},
+
+			// Abort an upload.
+			http::Method::DELETE if query.contains_key("uploadId") => {
+				self.uploads.lock().unwrap().remove(&query["uploadId"]);
+				response(http::StatusCode::NO_CONTENT, Bytes::new())
+			},
+
+			// Delete an object.
+			http::Method::DELETE => {
+				self.objects.lock().unwrap().remove(&key);
+				response(http::StatusCode::NO_CONTENT, Bytes::new())
+			},
+
+			_ => response(http::StatusCode::NOT_IMPLEMENTED, Bytes::new()),
+		}
+	}
+}
+
+fn response(status: http::StatusCode, bytes: Bytes) -> http::Response<Full<Bytes>> {
+	http::Response::builder()
+		.status(status)
+		.body(Full::new(bytes))
+		.unwrap()
+}
@@ -0,0 +1,11 @@
+use crate::Server;
+
+pub mod create;
+pub mod delete;
+pub mod list;
+
+impl Server {
+	pub(crate) fn hash_token(token: &str) -> String {
+		blake3::hash(token.as_bytes()).to_hex().to_string()
+	}
+}
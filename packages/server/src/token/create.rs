@@ -0,0 +1,108 @@
+use crate::{Config, Server, database::Database};
+use indoc::formatdoc;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_http::{Body, request::Ext as _, response::builder::Ext as _};
+
+impl Server {
+	pub async fn create_token(
+		&self,
+		arg: tg::token::create::Arg,
+	) -> tg::Result<tg::token::create::Output> {
+		Self::create_token_with_database(&self.database, arg).await
+	}
+
+	/// Create a token directly in the database, which does not require a running server. This creates the first token when authorization is enabled, because creating a token through the API requires a token with the `manage_tokens` scope.
+	pub async fn create_token_offline(
+		config: &Config,
+		arg: tg::token::create::Arg,
+	) -> tg::Result<tg::token::create::Output> {
+		let directory = tokio::fs::canonicalize(&config.directory).await.map_err(
+			|source| tg::error!(!source, %path = config.directory.display(), "failed to canonicalize directory path"),
+		)?;
+
+		// Lock the lock file. If a running server holds it, then only verify the migrations, because that server applies them.
+		let lock_file = Self::try_lock(&directory).await?;
+		let migrate = lock_file.is_some() && config.advanced.migrate;
+
+		// Create the database and the token.
+		let database = Self::create_database(config, &directory).await?;
+		Self::migrate_or_verify(migrate, "database", &database, crate::database::MIGRATIONS)
+			.await?;
+		let output = Self::create_token_with_database(&database, arg).await?;
+
+		drop(lock_file);
+
+		Ok(output)
+	}
+
+	async fn create_token_with_database(
+		database: &Database,
+		arg: tg::token::create::Arg,
+	) -> tg::Result<tg::token::create::Output> {
+		// Validate the arg.
+		if arg.scopes.is_empty() {
+			return Err(tg::error!("expected at least one scope"));
+		}
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		if arg.expires_at.is_some_and(|expires_at| expires_at <= now) {
+			return Err(tg::error!("the expiration must be in the future"));
+		}
+
+		// Create the token.
+		let id = tg::token::Id::new();
+		let bytes = rand::random::<[u8; 32]>();
+		let token = format!("tg_{}", data_encoding::HEXLOWER.encode(&bytes));
+		let hash = Self::hash_token(&token);
+
+		// Get a database connection.
+		let connection = database
+			.write_connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Insert the token.
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				insert into tokens (id, created_at, expires_at, hash, name, scopes)
+				values ({p}1, {p}2, {p}3, {p}4, {p}5, {p}6);
+			"
+		);
+		let params = db::params![
+			&id,
+			now,
+			arg.expires_at,
+			&hash,
+			arg.name,
+			db::value::Json(&arg.scopes),
+		];
+		connection
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
+		// Drop the database connection.
+		drop(connection);
+
+		let output = tg::token::create::Output { id, token };
+
+		Ok(output)
+	}
+
+	pub(crate) async fn handle_create_token_request<H>(
+		handle: &H,
+		request: http::Request<Body>,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
+		let arg = request.json().await?;
+		let output = handle.create_token(arg).await?;
+		let response = http::Response::builder()
+			.json(output)
+			.map_err(|source| tg::error!(!source, "failed to serialize the output"))?
+			.unwrap();
+		Ok(response)
+	}
+}
@@ -0,0 +1,53 @@
+use crate::Server;
+use indoc::formatdoc;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_http::{Body, response::builder::Ext as _};
+
+impl Server {
+	pub async fn delete_token(&self, id: &tg::token::Id) -> tg::Result<()> {
+		// Get a database connection.
+		let connection = self
+			.database
+			.write_connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Delete the token.
+		let p = connection.p();
+		let statement = formatdoc!(
+			"
+				delete from tokens
+				where id = {p}1;
+			"
+		);
+		let params = db::params![id];
+		let n = connection
+			.execute(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
+		// Drop the database connection.
+		drop(connection);
+
+		if n == 0 {
+			return Err(tg::error!(%id, "failed to find the token"));
+		}
+
+		Ok(())
+	}
+
+	pub(crate) async fn handle_delete_token_request<H>(
+		handle: &H,
+		_request: http::Request<Body>,
+		id: &str,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
+		let id = id.parse()?;
+		handle.delete_token(&id).await?;
+		let response = http::Response::builder().empty().unwrap();
+		Ok(response)
+	}
+}
@@ -0,0 +1,74 @@
+use crate::Server;
+use indoc::indoc;
+use tangram_client as tg;
+use tangram_database::{self as db, prelude::*};
+use tangram_http::{Body, request::Ext as _, response::builder::Ext as _};
+
+impl Server {
+	pub async fn list_tokens(
+		&self,
+		_arg: tg::token::list::Arg,
+	) -> tg::Result<tg::token::list::Output> {
+		// Get a database connection.
+		let connection = self
+			.database
+			.connection()
+			.await
+			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
+
+		// Get the tokens.
+		#[derive(serde::Deserialize)]
+		struct Row {
+			id: tg::token::Id,
+			created_at: i64,
+			expires_at: Option<i64>,
+			name: Option<String>,
+			scopes: db::value::Json<Vec<tg::token::Scope>>,
+		}
+		let statement = indoc!(
+			"
+				select id, created_at, expires_at, name, scopes
+				from tokens
+				order by created_at, id;
+			"
+		);
+		let params = db::params![];
+		let rows = connection
+			.query_all_into::<Row>(statement.into(), params)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to execute the statement"))?;
+
+		// Drop the database connection.
+		drop(connection);
+
+		let data = rows
+			.into_iter()
+			.map(|row| tg::token::list::Item {
+				id: row.id,
+				created_at: row.created_at,
+				expires_at: row.expires_at,
+				name: row.name,
+				scopes: row.scopes.0,
+			})
+			.collect();
+		let output = tg::token::list::Output { data };
+
+		Ok(output)
+	}
+
+	pub(crate) async fn handle_list_tokens_request<H>(
+		handle: &H,
+		request: http::Request<Body>,
+	) -> tg::Result<http::Response<Body>>
+	where
+		H: tg::Handle,
+	{
+		let arg = request.query_params().transpose()?.unwrap_or_default();
+		let output = handle.list_tokens(arg).await?;
+		let response = http::Response::builder()
+			.json(output)
+			.map_err(|source| tg::error!(!source, "failed to serialize the output"))?
+			.unwrap();
+		Ok(response)
+	}
+}
@@ -17,13 +17,15 @@ impl Server {
 		let p = connection.p();
 		let statement = formatdoc!(
 			"
-				select users.id, users.email, tokens.token
+				select users.id, users.email
 				from users
 				join tokens on tokens.user = users.id
-				where tokens.token = {p}1;
+				where tokens.hash = {p}1 and (tokens.expires_at is null or tokens.expires_at > {p}2);
 			"
 		);
-		let params = db::params![token];
+		let hash = Self::hash_token(token);
+		let now = time::OffsetDateTime::now_utc().unix_timestamp();
+		let params = db::params![hash, now];
 		let user = connection
 			.query_optional_into(statement.into(), params)
 			.await
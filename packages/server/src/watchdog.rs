@@ -1,12 +1,20 @@
 use crate::Server;
 use futures::{FutureExt, StreamExt as _, stream::FuturesUnordered};
 use indoc::formatdoc;
+use itertools::Itertools as _;
 use num::ToPrimitive as _;
 use std::pin::pin;
 use tangram_client as tg;
 use tangram_database::{self as db, prelude::*};
 use tangram_messenger::Messenger as _;
 
+#[derive(Debug, serde::Deserialize)]
+struct Row {
+	id: tg::process::Id,
+	code: Option<tg::error::Code>,
+	message: String,
+}
+
 impl Server {
 	pub async fn watchdog_task(&self, config: &crate::config::Watchdog) -> tg::Result<()> {
 		loop {
@@ -48,38 +56,51 @@ impl Server {
 			.map_err(|source| tg::error!(!source, "failed to get a database connection"))?;
 		let p = connection.p();
 
-		// Get processes to finish.
-		#[derive(Debug, serde::Deserialize)]
-		struct Row {
-			id: tg::process::Id,
-			code: Option<tg::error::Code>,
-			message: String,
-		}
+		// Get processes to finish, excluding the processes that are already being terminated before applying the limit, so that they cannot fill every batch.
+		let terminating = self.termination_task_map.keys();
+		let exclude = if terminating.is_empty() {
+			String::new()
+		} else {
+			let ids = (0..terminating.len())
+				.map(|index| format!("{p}{}", index + 5))
+				.join(", ");
+			format!("where id not in ({ids})")
+		};
 		let statement = formatdoc!(
 			"
-				select id, null as code, 'maximum depth exceeded' as message
-				from processes
-				where status = 'started' and depth > {p}1
+				select id, code, message
+				from (
+					select id, null as code, 'maximum depth exceeded' as message
+					from processes
+					where status = 'started' and depth > {p}1
 
-				union all
+					union all
 
-				select id, 'cancellation' as code, 'the process was canceled' as message
-				from processes
-				where status != 'finished' and token_count = 0
+					select id, 'cancellation' as code, 'the process was canceled' as message
+					from processes
+					where status != 'finished' and token_count = 0
 
-				union all
+					union all
 
-				select id, null as code, 'heartbeat expired' as message
-				from processes
-				where status = 'started' and heartbeat_at <= {p}2
+					select id, null as code, 'heartbeat expired' as message
+					from processes
+					where status = 'started' and heartbeat_at <= {p}2
 
-				limit {p}3;
+					union all
+
+					select id, 'timeout' as code, 'the process exceeded its timeout' as message
+					from processes
+					where status = 'started' and timeout is not null and started_at + timeout <= {p}3
+				) as candidates
+				{exclude}
+				limit {p}4;
 			"
 		);
 		let max_depth = config.max_depth;
 		let now = time::OffsetDateTime::now_utc().unix_timestamp();
 		let max_heartbeat_at = now - config.ttl.as_secs().to_i64().unwrap();
-		let params = db::params![max_depth, max_heartbeat_at, config.batch_size];
+		let mut params = db::params![max_depth, max_heartbeat_at, now, config.batch_size];
+		params.extend(terminating.iter().flat_map(|id| db::params![id]));
 		let rows = connection
 			.query_all_into::<Row>(statement.into(), params)
 			.await
@@ -88,33 +109,39 @@ impl Server {
 		// Drop the database connection.
 		drop(connection);
 
+		// Skip processes whose termination started after the query.
+		let rows = rows
+			.into_iter()
+			.filter(|row| self.termination_task_map.get_task_id(&row.id).is_none())
+			.collect::<Vec<_>>();
+
 		// Finish the processes.
 		let n = rows.len().to_u64().unwrap();
 		rows.into_iter()
 			.map(|row| {
 				let server = self.clone();
 				async move {
-					let error = tg::Error {
-						code: row.code,
-						message: Some(row.message),
-						..Default::default()
-					};
-					let error = Some(error.to_data());
-					let arg = tg::process::finish::Arg {
-						checksum: None,
-						error,
-						exit: 1,
-						force: false,
-						output: None,
-						remote: None,
-					};
-					server
-						.finish_process(&row.id, arg)
-						.await
-						.inspect_err(|error| {
-							tracing::error!(?error, "failed to cancel the process");
-						})
-						.ok();
+					// If the process exceeded its timeout, then terminate it in a task, so that waiting for the grace period does not delay the rest of the batch.
+					if matches!(row.code, Some(tg::error::Code::Timeout)) {
+						let grace_period = config.timeout_grace_period;
+						server.termination_task_map.get_or_spawn(row.id.clone(), {
+							let server = server.clone();
+							move |_| async move {
+								let finished = server
+									.terminate_process(&row.id, grace_period)
+									.await
+									.inspect_err(|error| {
+										tracing::error!(?error, "failed to terminate the process");
+									})
+									.unwrap_or_default();
+								if !finished {
+									server.watchdog_finish_process(row).await;
+								}
+							}
+						});
+						return;
+					}
+					server.watchdog_finish_process(row).await;
 				}
 				.boxed()
 			})
@@ -124,4 +151,70 @@ impl Server {
 
 		Ok(n)
 	}
+
+	async fn watchdog_finish_process(&self, row: Row) {
+		let error = tg::Error {
+			code: row.code,
+			message: Some(row.message),
+			..Default::default()
+		};
+		let error = Some(error.to_data());
+		let arg = tg::process::finish::Arg {
+			checksum: None,
+			error,
+			exit: 1,
+			force: false,
+			output: None,
+			remote: None,
+			usage: None,
+		};
+		self.finish_process(&row.id, arg)
+			.await
+			.inspect_err(|error| {
+				tracing::error!(?error, "failed to cancel the process");
+			})
+			.ok();
+	}
+
+	/// Send SIGTERM to a process and then SIGKILL if it does not finish within the grace period. Returns whether the process finished.
+	async fn terminate_process(
+		&self,
+		id: &tg::process::Id,
+		grace_period: std::time::Duration,
+	) -> tg::Result<bool> {
+		// Get a future that resolves when the process finishes.
+		let Some(future) = self.try_wait_process_future(id).await? else {
+			return Ok(false);
+		};
+
+		// Send SIGTERM.
+		self.publish_process_signal(id, tg::process::Signal::SIGTERM)
+			.await?;
+
+		// Wait for the process to finish or for the grace period to expire.
+		if let Ok(result) = tokio::time::timeout(grace_period, future).await {
+			return Ok(result.is_ok());
+		}
+
+		// Send SIGKILL.
+		self.publish_process_signal(id, tg::process::Signal::SIGKILL)
+			.await?;
+
+		Ok(false)
+	}
+
+	async fn publish_process_signal(
+		&self,
+		id: &tg::process::Id,
+		signal: tg::process::Signal,
+	) -> tg::Result<()> {
+		let payload = serde_json::to_vec(&tg::process::signal::get::Event::Signal(signal))
+			.unwrap()
+			.into();
+		self.messenger
+			.publish(format!("processes.{id}.signal"), payload)
+			.await
+			.map_err(|source| tg::error!(!source, "failed to signal the process"))?;
+		Ok(())
+	}
 }